@@ -6,6 +6,8 @@ use crate::horizon::FetchError;
 
 use sp_std::vec::Vec;
 
+use crate::types::ScValType;
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum StellarSdkError {
     InvalidBase32Character {
@@ -44,6 +46,11 @@ pub enum StellarSdkError {
     /// The transaction envelope already has the maximal number of signatures (20)
     TooManySignatures,
 
+    /// The transaction envelope at `index` is for another transaction
+    TransactionHashMismatch {
+        index: usize,
+    },
+
     AssetCodeTooLong,
 
     InvalidAssetCodeCharacter,
@@ -86,6 +93,150 @@ pub enum StellarSdkError {
 
     CantWrapFeeBumpTransaction,
 
+    /// The transaction envelope or the inner transaction of a fee bump transaction has an
+    /// unknown type
+    UnsupportedEnvelopeType,
+
+    /// The two assets of a liquidity pool are the same
+    IdenticalLiquidityPoolAssets,
+
+    /// The transaction does not contain any operation
+    NoOperations,
+
+    /// The fee is lower than `BASE_FEE_STROOPS` per operation
+    InsufficientFee {
+        found: u32,
+        minimum: u32,
+    },
+
+    /// The total fee of the transaction does not fit into 32 bits
+    FeeOverflow,
+
+    /// Neither time bounds nor a timeout have been set for the transaction
+    MissingTimeBounds,
+
+    /// The lower time bound is greater than the upper time bound
+    InvalidTimeBounds,
+
+    /// The lower ledger bound is not smaller than the upper ledger bound
+    InvalidLedgerBounds,
+
+    /// The minimal sequence number is negative or not smaller than the sequence number of the transaction
+    InvalidMinSequenceNumber,
+
+    /// The same signer key has been added twice as extra signer
+    DuplicateExtraSigners,
+
+    /// A `%` in a percent encoded string is not followed by two hexadecimal digits
+    InvalidPercentEncoding {
+        at_position: usize,
+    },
+
+    InvalidBase58Character {
+        at_position: usize,
+    },
+
+    /// The SS58 address has an invalid length or uses a reserved address format
+    InvalidSs58Encoding,
+
+    /// The checksum of the SS58 address is invalid
+    InvalidSs58Checksum,
+
+    /// The SS58 address is valid but encoded for another network
+    UnexpectedSs58Prefix {
+        expected: u16,
+        found: u16,
+    },
+
+    /// The signers and thresholds of an account involved in the transaction are not known
+    UnknownSignersOfAccount {
+        account_id: Vec<u8>,
+    },
+
+    /// The key store is malformed or its public key does not match the encrypted secret key
+    InvalidKeyStore,
+
+    /// The version of the key store format is not supported
+    UnsupportedKeyStoreVersion {
+        found: u32,
+    },
+
+    /// The KDF parameters of the key store are not supported
+    InvalidKdfParameters,
+
+    /// The key store can't be decrypted, the password is wrong or the ciphertext has been modified
+    KeyStoreDecryptionFailed,
+
+    /// The Txrep line with number `line` (starting at 1) is not of the form `key: value` or repeats a key
+    InvalidTxrepLine {
+        line: usize,
+    },
+
+    /// The Txrep representation does not contain the required key `key`
+    MissingTxrepKey {
+        key: Vec<u8>,
+    },
+
+    /// The value of the Txrep key `key` is malformed
+    InvalidTxrepValue {
+        key: Vec<u8>,
+    },
+
+    /// The string is not a `web+stellar:tx` or `web+stellar:pay` URI (SEP-7)
+    InvalidSep7Uri,
+
+    /// The SEP-7 URI lacks the required parameter `name`
+    MissingSep7Parameter {
+        name: Vec<u8>,
+    },
+
+    /// The SEP-7 parameter `name` is repeated or its value is malformed
+    InvalidSep7Parameter {
+        name: Vec<u8>,
+    },
+
+    /// The server key does not belong to the server account of the SEP-10 challenge or the
+    /// challenge has a memo and a muxed client account
+    InvalidSep10Challenge,
+
+    /// The operation is not an `InvokeHostFunction` operation
+    NoInvokeHostFunctionOperation,
+
+    /// The `ScVal` has another type than the value it is converted to
+    UnexpectedScValType {
+        expected: ScValType,
+        found: ScValType,
+    },
+
+    /// The `ScVal` can't be converted, e.g., a tuple or byte array has the wrong length, a map
+    /// has duplicate keys or a string is not valid UTF-8
+    InvalidScVal,
+
+    /// The symbol is longer than `SCSYMBOL_LIMIT` or contains characters other than `a-zA-Z0-9_`
+    InvalidScSymbol,
+
+    /// The Soroban authorization entry is authorized by the source account, not by an address
+    NoAddressCredentials,
+
+    /// The signature of the Soroban authorization entry is not an account signature, is not
+    /// sorted by public key or is invalid
+    InvalidSorobanAuthorizationSignature,
+
+    /// The Wasm code is malformed or its `contractspecv0` section is not a sequence of
+    /// `ScSpecEntry`s
+    InvalidContractSpec,
+
+    /// The contract spec has no function or type with this name
+    UnknownContractSpecEntry {
+        name: Vec<u8>,
+    },
+
+    /// A value does not match the type given by the contract spec
+    ContractSpecMismatch,
+
+    /// The body of the contract event has an unknown version
+    InvalidContractEvent,
+
     #[cfg(feature = "offchain")]
     FetchError(FetchError),
 
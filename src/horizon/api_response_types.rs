@@ -1,5 +1,8 @@
 use super::{fetch::FetchError, json_response_types};
 use core::convert::{TryFrom, TryInto};
+use sp_std::vec::Vec;
+
+use crate::{AccountId, AccountSigners, Signer, SignerKey, StellarSdkError};
 
 #[derive(Debug, PartialEq)]
 pub struct FeeStats {
@@ -64,3 +67,33 @@ impl TryFrom<json_response_types::FeeStats> for FeeStats {
         })
     }
 }
+
+impl TryFrom<&json_response_types::AccountResponse> for AccountSigners {
+    type Error = StellarSdkError;
+
+    /// Collect the signers and thresholds of the account
+    ///
+    /// Horizon lists the master key among the signers, its weight becomes the master weight.
+    fn try_from(account: &json_response_types::AccountResponse) -> Result<Self, Self::Error> {
+        let account_id = AccountId::from_encoding(&account.account_id)?;
+
+        let mut master_weight = 0;
+        let mut signers = Vec::with_capacity(account.signers.len());
+        for signer in account.signers.iter() {
+            let key = SignerKey::from_encoding(&signer.key)?;
+            let weight = signer.weight.try_into().map_err(|_| StellarSdkError::InvalidSignerWeight)?;
+
+            match key == SignerKey::SignerKeyTypeEd25519(*account_id.as_binary()) {
+                true => master_weight = weight,
+                false => signers.push(Signer { key, weight: weight as u32 }),
+            }
+        }
+
+        let thresholds = &account.thresholds;
+        Ok(AccountSigners::new(
+            account_id,
+            [master_weight, thresholds.low_threshold, thresholds.med_threshold, thresholds.high_threshold],
+            signers,
+        ))
+    }
+}
@@ -4,6 +4,7 @@ mod json_response_types;
 
 pub mod api_response_types;
 pub mod fetch;
+pub mod sequence_manager;
 pub mod submit_transaction;
 
 pub struct Horizon {
@@ -11,6 +12,7 @@ pub struct Horizon {
 }
 
 pub use fetch::FetchError;
+pub use sequence_manager::{is_bad_sequence_error, SequenceManager};
 
 impl Horizon {
     pub fn new(base_url: &str) -> Horizon {
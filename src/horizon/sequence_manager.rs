@@ -0,0 +1,249 @@
+//! Reservation of sequence numbers across offchain worker runs
+//!
+//! `Horizon::fetch_next_sequence_number` returns the same sequence number to every caller until
+//! a transaction of the account is included in the ledger. If two offchain worker runs overlap,
+//! they build transactions with the same sequence number and one of them fails with
+//! `tx_bad_seq`. The `SequenceManager` hands out every sequence number only once. Its state is
+//! kept in the persistent offchain local storage and guarded by a `StorageLock`.
+
+use sp_io::offchain::{local_storage_get, local_storage_set};
+use sp_runtime::offchain::{storage_lock::StorageLock, Duration, StorageKind};
+use sp_std::vec::Vec;
+
+use super::{FetchError, Horizon};
+use crate::{
+    xdr::streams::{DecodeError, ReadStream, WriteStream},
+    IntoAccountId, XdrCodec,
+};
+
+const LOCK_SUFFIX: &[u8] = b"::lock";
+const RESULT_CODE_BAD_SEQUENCE: &[u8] = b"tx_bad_seq";
+
+/// The reservations of one source account as stored in the offchain local storage
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+struct Reservations {
+    /// The sequence number that is handed out next if no released number is available
+    next_sequence_number: i64,
+    /// Sequence numbers that are handed out and not yet confirmed or released
+    pending: Vec<i64>,
+    /// Sequence numbers below `next_sequence_number` that have been released and are reused
+    released: Vec<i64>,
+}
+
+fn write_sequence_numbers(sequence_numbers: &[i64], write_stream: &mut WriteStream) {
+    write_stream.write_next_u32(sequence_numbers.len() as u32);
+    for sequence_number in sequence_numbers {
+        write_stream.write_next_i64(*sequence_number);
+    }
+}
+
+fn read_sequence_numbers<T: AsRef<[u8]>>(read_stream: &mut ReadStream<T>) -> Result<Vec<i64>, DecodeError> {
+    let length = read_stream.read_next_u32()?;
+    (0..length).map(|_| read_stream.read_next_i64()).collect()
+}
+
+impl XdrCodec for Reservations {
+    fn to_xdr_buffered(&self, write_stream: &mut WriteStream) {
+        write_stream.write_next_i64(self.next_sequence_number);
+        write_sequence_numbers(&self.pending, write_stream);
+        write_sequence_numbers(&self.released, write_stream);
+    }
+
+    fn from_xdr_buffered<T: AsRef<[u8]>>(read_stream: &mut ReadStream<T>) -> Result<Self, DecodeError> {
+        Ok(Reservations {
+            next_sequence_number: read_stream.read_next_i64()?,
+            pending: read_sequence_numbers(read_stream)?,
+            released: read_sequence_numbers(read_stream)?,
+        })
+    }
+}
+
+impl Reservations {
+    fn reserve(&mut self) -> i64 {
+        let sequence_number = match self.released.iter().min().copied() {
+            Some(sequence_number) => {
+                self.released.retain(|released| *released != sequence_number);
+                sequence_number
+            },
+            None => {
+                self.next_sequence_number += 1;
+                self.next_sequence_number - 1
+            },
+        };
+        self.pending.push(sequence_number);
+        sequence_number
+    }
+
+    fn confirm(&mut self, sequence_number: i64) {
+        self.pending.retain(|pending| *pending != sequence_number);
+        // the sequence number of the account is now `sequence_number`, lower numbers are unusable
+        self.released.retain(|released| *released > sequence_number);
+    }
+
+    fn release(&mut self, sequence_number: i64) {
+        if !self.pending.contains(&sequence_number) {
+            return
+        }
+        self.pending.retain(|pending| *pending != sequence_number);
+        self.released.push(sequence_number);
+
+        while self.released.contains(&(self.next_sequence_number - 1)) {
+            self.next_sequence_number -= 1;
+            let sequence_number = self.next_sequence_number;
+            self.released.retain(|released| *released != sequence_number);
+        }
+    }
+}
+
+/// Hands out sequence numbers of source accounts such that no number is used twice
+///
+/// The typical cycle is: `reserve_sequence_number`, build and submit the transaction and then
+/// `confirm_sequence_number` if the submission succeeded, `resync` if it failed with
+/// `tx_bad_seq` (see `is_bad_sequence_error`) or `release_sequence_number` if the transaction
+/// is abandoned.
+pub struct SequenceManager<'a> {
+    horizon: &'a Horizon,
+    storage_prefix: Vec<u8>,
+    lock_expiration: Duration,
+}
+
+impl<'a> SequenceManager<'a> {
+    /// Create a sequence manager that stores its state under keys starting with `storage_prefix`
+    ///
+    /// The storage lock of an account expires after `lock_expiration_milliseconds`, this should
+    /// be longer than the timeout of a Horizon request.
+    pub fn new<T: AsRef<[u8]>>(horizon: &'a Horizon, storage_prefix: T, lock_expiration_milliseconds: u64) -> Self {
+        SequenceManager {
+            horizon,
+            storage_prefix: storage_prefix.as_ref().to_vec(),
+            lock_expiration: Duration::from_millis(lock_expiration_milliseconds),
+        }
+    }
+
+    fn get_storage_key(&self, account_encoding: &[u8]) -> Vec<u8> {
+        [&self.storage_prefix[..], b"::", account_encoding].concat()
+    }
+
+    fn load(&self, storage_key: &[u8]) -> Option<Reservations> {
+        local_storage_get(StorageKind::PERSISTENT, storage_key).and_then(|value| Reservations::from_xdr(value).ok())
+    }
+
+    fn store(&self, storage_key: &[u8], reservations: &Reservations) {
+        local_storage_set(StorageKind::PERSISTENT, storage_key, &reservations.to_xdr());
+    }
+
+    /// Lock the state of the account and update it with `update`
+    ///
+    /// If there is no state yet, it is initialized with the next sequence number from Horizon
+    /// if `timeout_milliseconds` is given. Otherwise nothing is updated and `None` is returned.
+    fn update<T: IntoAccountId, R>(
+        &self,
+        account_id: T,
+        timeout_milliseconds: Option<u64>,
+        update: impl FnOnce(&mut Reservations) -> R,
+    ) -> Result<Option<R>, FetchError> {
+        let account_encoding = account_id.into_encoding();
+        let storage_key = self.get_storage_key(&account_encoding);
+        let lock_key = [&storage_key[..], LOCK_SUFFIX].concat();
+
+        let mut lock = StorageLock::with_deadline(&lock_key, self.lock_expiration);
+        let _guard = lock.lock();
+
+        let mut reservations = match (self.load(&storage_key), timeout_milliseconds) {
+            (Some(reservations), _) => reservations,
+            (None, Some(timeout_milliseconds)) => Reservations {
+                next_sequence_number: self
+                    .horizon
+                    .fetch_next_sequence_number(&account_encoding, timeout_milliseconds)?,
+                ..Default::default()
+            },
+            (None, None) => return Ok(None),
+        };
+
+        let result = update(&mut reservations);
+        self.store(&storage_key, &reservations);
+        Ok(Some(result))
+    }
+
+    /// Reserve the next sequence number of the account
+    ///
+    /// Released sequence numbers are handed out again before new ones, so that the account
+    /// does not get a gap in its sequence numbers.
+    pub fn reserve_sequence_number<T: IntoAccountId>(
+        &self,
+        account_id: T,
+        timeout_milliseconds: u64,
+    ) -> Result<i64, FetchError> {
+        self.update(account_id, Some(timeout_milliseconds), Reservations::reserve)
+            .map(|sequence_number| sequence_number.expect("State has been initialized"))
+    }
+
+    /// Mark the transaction with the reserved `sequence_number` as submitted successfully
+    pub fn confirm_sequence_number<T: IntoAccountId>(&self, account_id: T, sequence_number: i64) {
+        let _ = self.update(account_id, None, |reservations| reservations.confirm(sequence_number));
+    }
+
+    /// Give the reserved `sequence_number` back because its transaction has been abandoned
+    pub fn release_sequence_number<T: IntoAccountId>(&self, account_id: T, sequence_number: i64) {
+        let _ = self.update(account_id, None, |reservations| reservations.release(sequence_number));
+    }
+
+    /// Discard all reservations of the account and fetch its next sequence number from Horizon
+    ///
+    /// This is necessary after a transaction failed with `tx_bad_seq`.
+    pub fn resync<T: IntoAccountId>(&self, account_id: T, timeout_milliseconds: u64) -> Result<(), FetchError> {
+        let account_encoding = account_id.into_encoding();
+        let storage_key = self.get_storage_key(&account_encoding);
+        let lock_key = [&storage_key[..], LOCK_SUFFIX].concat();
+
+        let mut lock = StorageLock::with_deadline(&lock_key, self.lock_expiration);
+        let _guard = lock.lock();
+
+        let next_sequence_number = self
+            .horizon
+            .fetch_next_sequence_number(&account_encoding, timeout_milliseconds)?;
+        self.store(&storage_key, &Reservations { next_sequence_number, ..Default::default() });
+        Ok(())
+    }
+}
+
+/// Return whether `error` is the response of Horizon to a transaction with a bad sequence number
+pub fn is_bad_sequence_error(error: &FetchError) -> bool {
+    match error {
+        FetchError::UnexpectedResponseStatus { status: 400, body } => body
+            .windows(RESULT_CODE_BAD_SEQUENCE.len())
+            .any(|window| window == RESULT_CODE_BAD_SEQUENCE),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Reservations;
+    use crate::XdrCodec;
+
+    #[test]
+    fn reservations() {
+        let mut reservations = Reservations { next_sequence_number: 10, ..Default::default() };
+        assert_eq!(reservations.reserve(), 10);
+        assert_eq!(reservations.reserve(), 11);
+        assert_eq!(reservations.reserve(), 12);
+
+        reservations.release(11);
+        assert_eq!(reservations.reserve(), 11);
+
+        reservations.confirm(10);
+        reservations.release(12);
+        reservations.release(11);
+        assert_eq!(reservations, Reservations { next_sequence_number: 11, pending: vec![], released: vec![] });
+
+        assert_eq!(reservations.reserve(), 11);
+        assert_eq!(reservations.reserve(), 12);
+        reservations.release(11);
+        assert_eq!(reservations.released, vec![11]);
+        assert_eq!(Reservations::from_xdr(reservations.to_xdr()).unwrap(), reservations);
+
+        reservations.confirm(12);
+        assert_eq!(reservations, Reservations { next_sequence_number: 13, pending: vec![], released: vec![] });
+    }
+}
@@ -0,0 +1,414 @@
+//! Password-encrypted storage of secret keys
+//!
+//! A `KeyStore` contains the secret seed of a `SecretKey` encrypted with xsalsa20-poly1305
+//! (the sodalite `secretbox`). The encryption key is derived from a password using the
+//! memory-hard KDF scrypt. The format version, the KDF parameters, the salt, the nonce and
+//! the public key are stored in clear, so that the public key of a stored key can be looked
+//! up without the password.
+//!
+//! The binary representation is the XDR encoding of the following structure:
+//! ```text
+//! struct KeyStore {
+//!     unsigned int version;   // always 1
+//!     unsigned int kdf;       // always 0 (scrypt)
+//!     unsigned int logN;
+//!     unsigned int r;
+//!     unsigned int p;
+//!     opaque salt[32];
+//!     opaque nonce[24];
+//!     PublicKey publicKey;
+//!     opaque ciphertext[48];  // poly1305 tag followed by the encrypted secret seed
+//! };
+//! ```
+
+use core::convert::{AsRef, TryInto};
+use sodalite::{secretbox, secretbox_open, SecretboxKey, SecretboxNonce, SECRETBOX_NONCE_LEN};
+use sp_std::{vec, vec::Vec};
+
+use crate::{
+    types::PublicKey,
+    utils::key_encoding::ED25519_SECRET_SEED_BYTE_LENGTH,
+    xdr::{
+        streams::{DecodeError, ReadStream, WriteStream},
+        xdr_codec::XdrCodec,
+    },
+    SecretKey, StellarSdkError,
+};
+
+#[cfg(feature = "std")]
+use crate::utils::base64;
+
+/// The current version of the key store format
+pub const KEY_STORE_VERSION: u32 = 1;
+
+const KDF_SCRYPT: u32 = 0;
+const SALT_LENGTH: usize = 32;
+const SECRETBOX_ZERO_LENGTH: usize = 32;
+const SECRETBOX_BOX_ZERO_LENGTH: usize = 16;
+const CIPHERTEXT_LENGTH: usize = SECRETBOX_ZERO_LENGTH - SECRETBOX_BOX_ZERO_LENGTH + ED25519_SECRET_SEED_BYTE_LENGTH;
+const BINARY_LENGTH: usize = 5 * 4 + SALT_LENGTH + SECRETBOX_NONCE_LEN + 36 + CIPHERTEXT_LENGTH;
+
+/// The parameters of the scrypt key derivation function
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KdfParameters {
+    /// The base 2 logarithm of the CPU/memory cost parameter `N`
+    pub log_n: u8,
+    /// The block size parameter
+    pub r: u32,
+    /// The parallelization parameter
+    pub p: u32,
+}
+
+impl KdfParameters {
+    /// The maximal accepted `log_n`
+    pub const MAX_LOG_N: u8 = 20;
+    /// The maximal accepted `r`
+    pub const MAX_R: u32 = 32;
+    /// The maximal accepted `p`
+    pub const MAX_P: u32 = 16;
+    /// The maximal memory of `128 * r * 2^log_n` bytes that deriving a key may require (1 GiB)
+    pub const MAX_MEMORY: u64 = 1 << 30;
+
+    /// The parameters recommended for interactive use (`log_n = 17`, `r = 8`, `p = 1`)
+    ///
+    /// Deriving a key with these parameters requires 128 MiB of memory.
+    pub fn recommended() -> Self {
+        KdfParameters { log_n: 17, r: 8, p: 1 }
+    }
+
+    /// Check that the parameters do not exceed the limits
+    ///
+    /// Key stores are untrusted input, unbounded parameters would let a key store exhaust the
+    /// memory or time of the decrypting process.
+    pub fn check(&self) -> Result<(), StellarSdkError> {
+        if self.log_n > Self::MAX_LOG_N ||
+            self.r > Self::MAX_R ||
+            self.p > Self::MAX_P ||
+            (128 * self.r as u64) << self.log_n > Self::MAX_MEMORY
+        {
+            return Err(StellarSdkError::InvalidKdfParameters)
+        }
+        Ok(())
+    }
+
+    fn derive_key(&self, password: &[u8], salt: &[u8; SALT_LENGTH]) -> Result<SecretboxKey, StellarSdkError> {
+        self.check()?;
+        let params =
+            scrypt::Params::new(self.log_n, self.r, self.p, 32).map_err(|_| StellarSdkError::InvalidKdfParameters)?;
+
+        let mut key: SecretboxKey = [0; 32];
+        scrypt::scrypt(password, salt, &params, &mut key).map_err(|_| StellarSdkError::InvalidKdfParameters)?;
+        Ok(key)
+    }
+}
+
+/// A `SecretKey` encrypted with a password
+/// ```
+/// use substrate_stellar_sdk::{KdfParameters, KeyStore, SecretKey};
+///
+/// let secret_key = SecretKey::from_encoding("SCDSVACTNFNSD5LQZ5LWUWEY3UIAML2J7ALPFCD6ZX4D3TVJV7X243N3").unwrap();
+/// let parameters = KdfParameters { log_n: 10, r: 8, p: 1 };
+///
+/// // salt and nonce need to be fresh random values
+/// let key_store = KeyStore::encrypt(&secret_key, "password", parameters, [1; 32], [2; 24]).unwrap();
+/// let key_store = KeyStore::from_binary(key_store.to_binary()).unwrap();
+///
+/// assert_eq!(key_store.get_public(), secret_key.get_public());
+/// assert_eq!(key_store.decrypt("password"), Ok(secret_key));
+/// assert!(key_store.decrypt("wrong password").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyStore {
+    kdf_parameters: KdfParameters,
+    salt: [u8; SALT_LENGTH],
+    nonce: SecretboxNonce,
+    public_key: PublicKey,
+    ciphertext: [u8; CIPHERTEXT_LENGTH],
+}
+
+impl KeyStore {
+    /// Encrypt the `secret_key` with the `password`
+    ///
+    /// The `salt` and the `nonce` must be generated by a cryptographically secure random
+    /// number generator and must never be reused.
+    pub fn encrypt<T: AsRef<[u8]>>(
+        secret_key: &SecretKey,
+        password: T,
+        kdf_parameters: KdfParameters,
+        salt: [u8; SALT_LENGTH],
+        nonce: [u8; SECRETBOX_NONCE_LEN],
+    ) -> Result<Self, StellarSdkError> {
+        let key = kdf_parameters.derive_key(password.as_ref(), &salt)?;
+
+        let mut message = vec![0; SECRETBOX_ZERO_LENGTH];
+        message.extend_from_slice(secret_key.as_binary());
+        let mut ciphertext = vec![0; message.len()];
+        secretbox(&mut ciphertext, &message, &nonce, &key).map_err(|_| StellarSdkError::InvalidKeyStore)?;
+
+        Ok(KeyStore {
+            kdf_parameters,
+            salt,
+            nonce,
+            public_key: secret_key.get_public().clone(),
+            ciphertext: ciphertext[SECRETBOX_BOX_ZERO_LENGTH..].try_into().unwrap(),
+        })
+    }
+
+    /// Decrypt the secret key with the `password`
+    ///
+    /// Returns `KeyStoreDecryptionFailed` if the password is wrong or the key store has been
+    /// tampered with.
+    pub fn decrypt<T: AsRef<[u8]>>(&self, password: T) -> Result<SecretKey, StellarSdkError> {
+        let key = self.kdf_parameters.derive_key(password.as_ref(), &self.salt)?;
+
+        let mut ciphertext = vec![0; SECRETBOX_BOX_ZERO_LENGTH];
+        ciphertext.extend_from_slice(&self.ciphertext);
+        let mut message = vec![0; ciphertext.len()];
+        secretbox_open(&mut message, &ciphertext, &self.nonce, &key)
+            .map_err(|_| StellarSdkError::KeyStoreDecryptionFailed)?;
+
+        let secret_key = SecretKey::from_binary(message[SECRETBOX_ZERO_LENGTH..].try_into().unwrap());
+        if secret_key.get_public() != &self.public_key {
+            return Err(StellarSdkError::InvalidKeyStore)
+        }
+
+        Ok(secret_key)
+    }
+
+    /// Return the public key of the encrypted secret key
+    pub fn get_public(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Return the KDF parameters used to derive the encryption key
+    pub fn get_kdf_parameters(&self) -> &KdfParameters {
+        &self.kdf_parameters
+    }
+
+    /// Encode the key store in its binary representation
+    pub fn to_binary(&self) -> Vec<u8> {
+        let mut write_stream = WriteStream::new();
+        write_stream.write_next_u32(KEY_STORE_VERSION);
+        write_stream.write_next_u32(KDF_SCRYPT);
+        write_stream.write_next_u32(self.kdf_parameters.log_n as u32);
+        write_stream.write_next_u32(self.kdf_parameters.r);
+        write_stream.write_next_u32(self.kdf_parameters.p);
+        self.salt.to_xdr_buffered(&mut write_stream);
+        self.nonce.to_xdr_buffered(&mut write_stream);
+        self.public_key.to_xdr_buffered(&mut write_stream);
+        self.ciphertext.to_xdr_buffered(&mut write_stream);
+        write_stream.get_result()
+    }
+
+    /// Decode a key store from its binary representation
+    pub fn from_binary<T: AsRef<[u8]>>(binary: T) -> Result<Self, StellarSdkError> {
+        let invalid = |_: DecodeError| StellarSdkError::InvalidKeyStore;
+        let length = binary.as_ref().len();
+        if length < 4 {
+            return Err(StellarSdkError::InvalidKeyStore)
+        }
+
+        let mut read_stream = ReadStream::new(binary);
+        let version = read_stream.read_next_u32().map_err(invalid)?;
+        if version != KEY_STORE_VERSION {
+            return Err(StellarSdkError::UnsupportedKeyStoreVersion { found: version })
+        }
+        if length != BINARY_LENGTH {
+            return Err(StellarSdkError::InvalidKeyStore)
+        }
+
+        if read_stream.read_next_u32().map_err(invalid)? != KDF_SCRYPT {
+            return Err(StellarSdkError::InvalidKdfParameters)
+        }
+
+        let log_n = read_stream.read_next_u32().map_err(invalid)?;
+        let r = read_stream.read_next_u32().map_err(invalid)?;
+        let p = read_stream.read_next_u32().map_err(invalid)?;
+        let salt = XdrCodec::from_xdr_buffered(&mut read_stream).map_err(invalid)?;
+        let nonce = XdrCodec::from_xdr_buffered(&mut read_stream).map_err(invalid)?;
+        let public_key = XdrCodec::from_xdr_buffered(&mut read_stream).map_err(invalid)?;
+        let ciphertext = XdrCodec::from_xdr_buffered(&mut read_stream).map_err(invalid)?;
+
+        let log_n = log_n.try_into().map_err(|_| StellarSdkError::InvalidKdfParameters)?;
+        let kdf_parameters = KdfParameters { log_n, r, p };
+        kdf_parameters.check()?;
+        Ok(KeyStore { kdf_parameters, salt, nonce, public_key, ciphertext })
+    }
+}
+
+#[cfg(feature = "std")]
+mod json {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    pub struct KeyStoreJson {
+        pub version: u32,
+        pub public_key: String,
+        pub crypto: CryptoJson,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct CryptoJson {
+        pub cipher: String,
+        pub ciphertext: String,
+        pub nonce: String,
+        pub kdf: String,
+        pub kdf_params: KdfParamsJson,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    pub struct KdfParamsJson {
+        pub log_n: u8,
+        pub r: u32,
+        pub p: u32,
+        pub salt: String,
+    }
+}
+
+#[cfg(feature = "std")]
+const JSON_CIPHER: &str = "xsalsa20-poly1305";
+#[cfg(feature = "std")]
+const JSON_KDF: &str = "scrypt";
+
+#[cfg(feature = "std")]
+impl KeyStore {
+    /// Encode the key store as JSON
+    ///
+    /// Binary values are base64 encoded, the public key is given as a G-address.
+    pub fn to_json(&self) -> String {
+        let key_store = json::KeyStoreJson {
+            version: KEY_STORE_VERSION,
+            public_key: String::from_utf8(self.public_key.to_encoding()).unwrap(),
+            crypto: json::CryptoJson {
+                cipher: JSON_CIPHER.to_string(),
+                ciphertext: String::from_utf8(base64::encode(self.ciphertext)).unwrap(),
+                nonce: String::from_utf8(base64::encode(self.nonce)).unwrap(),
+                kdf: JSON_KDF.to_string(),
+                kdf_params: json::KdfParamsJson {
+                    log_n: self.kdf_parameters.log_n,
+                    r: self.kdf_parameters.r,
+                    p: self.kdf_parameters.p,
+                    salt: String::from_utf8(base64::encode(self.salt)).unwrap(),
+                },
+            },
+        };
+
+        serde_json::to_string(&key_store).unwrap()
+    }
+
+    /// Decode a key store from its JSON representation
+    pub fn from_json<T: AsRef<[u8]>>(json: T) -> Result<Self, StellarSdkError> {
+        let key_store: json::KeyStoreJson =
+            serde_json::from_slice(json.as_ref()).map_err(|_| StellarSdkError::InvalidKeyStore)?;
+
+        if key_store.version != KEY_STORE_VERSION {
+            return Err(StellarSdkError::UnsupportedKeyStoreVersion { found: key_store.version })
+        }
+
+        let crypto = key_store.crypto;
+        if crypto.cipher != JSON_CIPHER || crypto.kdf != JSON_KDF {
+            return Err(StellarSdkError::InvalidKeyStore)
+        }
+
+        let kdf_parameters =
+            KdfParameters { log_n: crypto.kdf_params.log_n, r: crypto.kdf_params.r, p: crypto.kdf_params.p };
+        kdf_parameters.check()?;
+
+        Ok(KeyStore {
+            kdf_parameters,
+            salt: decode_base64_array(crypto.kdf_params.salt)?,
+            nonce: decode_base64_array(crypto.nonce)?,
+            public_key: PublicKey::from_encoding(key_store.public_key)?,
+            ciphertext: decode_base64_array(crypto.ciphertext)?,
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+fn decode_base64_array<const N: usize>(encoded: String) -> Result<[u8; N], StellarSdkError> {
+    let binary = base64::decode(encoded).map_err(StellarSdkError::InvalidBase64Encoding)?;
+    let found_length = binary.len();
+    binary
+        .try_into()
+        .map_err(|_| StellarSdkError::InvalidBinaryLength { found_length, expected_length: N })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "SCDSVACTNFNSD5LQZ5LWUWEY3UIAML2J7ALPFCD6ZX4D3TVJV7X243N3";
+    const TEST_PARAMETERS: KdfParameters = KdfParameters { log_n: 4, r: 8, p: 1 };
+
+    #[test]
+    fn encrypt_and_decrypt() {
+        let secret_key = SecretKey::from_encoding(SECRET).unwrap();
+        let key_store = KeyStore::encrypt(&secret_key, "correct horse", TEST_PARAMETERS, [7; 32], [9; 24]).unwrap();
+
+        assert_eq!(key_store.decrypt("correct horse"), Ok(secret_key));
+        assert_eq!(key_store.decrypt("battery staple"), Err(StellarSdkError::KeyStoreDecryptionFailed));
+
+        let binary = key_store.to_binary();
+        assert_eq!(binary.len(), 160);
+        assert_eq!(KeyStore::from_binary(&binary[..159]), Err(StellarSdkError::InvalidKeyStore));
+        assert_eq!(KeyStore::from_binary(&binary), Ok(key_store));
+
+        let mut tampered = binary.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert_eq!(
+            KeyStore::from_binary(tampered).unwrap().decrypt("correct horse"),
+            Err(StellarSdkError::KeyStoreDecryptionFailed)
+        );
+
+        let mut other_version = binary;
+        other_version[3] = 2;
+        assert_eq!(KeyStore::from_binary(other_version), Err(StellarSdkError::UnsupportedKeyStoreVersion { found: 2 }));
+    }
+
+    #[test]
+    fn oversized_kdf_parameters() {
+        let secret_key = SecretKey::from_encoding(SECRET).unwrap();
+        let binary = KeyStore::encrypt(&secret_key, "correct horse", TEST_PARAMETERS, [7; 32], [9; 24])
+            .unwrap()
+            .to_binary();
+
+        // log_n = 40, r = 8
+        let mut oversized = binary.clone();
+        oversized[11] = 40;
+        assert_eq!(KeyStore::from_binary(&oversized), Err(StellarSdkError::InvalidKdfParameters));
+
+        // r = 33, p = 17 and log_n = 20 with r = 32 (4 GiB)
+        for (offset, value) in [(15, 33), (19, 17)] {
+            let mut oversized = binary.clone();
+            oversized[offset] = value;
+            assert_eq!(KeyStore::from_binary(&oversized), Err(StellarSdkError::InvalidKdfParameters));
+        }
+        let mut oversized = binary;
+        oversized[11] = 20;
+        oversized[15] = 32;
+        assert_eq!(KeyStore::from_binary(oversized), Err(StellarSdkError::InvalidKdfParameters));
+
+        let parameters = KdfParameters { log_n: 40, r: 8, p: 1 };
+        assert_eq!(
+            KeyStore::encrypt(&secret_key, "correct horse", parameters, [7; 32], [9; 24]),
+            Err(StellarSdkError::InvalidKdfParameters)
+        );
+        assert_eq!(KdfParameters::recommended().check(), Ok(()));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn json_representation() {
+        let secret_key = SecretKey::from_encoding(SECRET).unwrap();
+        let key_store = KeyStore::encrypt(&secret_key, "correct horse", TEST_PARAMETERS, [7; 32], [9; 24]).unwrap();
+
+        let json = key_store.to_json();
+        assert_eq!(KeyStore::from_json(&json), Ok(key_store));
+        assert_eq!(KeyStore::from_json(&json).unwrap().decrypt("correct horse"), Ok(secret_key));
+        assert_eq!(KeyStore::from_json("{}"), Err(StellarSdkError::InvalidKeyStore));
+        assert_eq!(
+            KeyStore::from_json(json.replace("\"log_n\":4", "\"log_n\":40")),
+            Err(StellarSdkError::InvalidKdfParameters)
+        );
+    }
+}
@@ -18,9 +18,15 @@ mod lib {
 mod amount;
 mod binary;
 mod error;
+mod key_store;
+mod multisig;
 pub mod network;
 mod public_key;
 mod secret_key;
+mod sep10;
+mod sep29;
+mod sep7;
+mod txrep;
 mod utils;
 mod xdr;
 
@@ -31,11 +37,29 @@ pub const BASE_FEE_STROOPS: u32 = 100;
 #[cfg(feature = "offchain")]
 pub mod horizon;
 
+#[cfg(feature = "substrate")]
+pub mod substrate;
+
 pub use xdr::{
     compound_types,
     impls::{
-        account_id::IntoAccountId, claimable_balance_id::IntoClaimbleBalanceId, data_value::IntoDataValue,
-        hash::IntoHash, muxed_account::IntoMuxedAccountId, time_bounds::*,
+        account_id::IntoAccountId,
+        claimable_balance_id::IntoClaimbleBalanceId,
+        constant_product::{LiquidityPoolError, PoolDeposit, PoolWithdrawal, SwapDirection},
+        contract_event::{TokenEvent, TokenEventKind},
+        data_value::IntoDataValue,
+        hash::IntoHash,
+        liquidity_pool::LIQUIDITY_POOL_FEE_V18,
+        muxed_account::IntoMuxedAccountId,
+        order_book::{OfferOutcome, OrderBook, OrderBookError, PathPaymentOutcome},
+        path_finding::{PaymentPath, MAX_PATH_LENGTH},
+        preconditions::{LedgerSnapshot, PreconditionFailure},
+        sc_address::IntoScAddress,
+        sc_val::{AsScBytes, AsScSymbol, IntoScVal, TryFromScVal},
+        time_bounds::*,
+        transaction_builder::TransactionBuilder,
+        transaction_envelope::{MergedEnvelope, SignatureStatus, SignatureVerificationReport},
+        validation::{OperationValidationError, ValidationError},
     },
     streams::{ReadStream, WriteStream},
     types::{
@@ -46,6 +70,13 @@ pub use xdr::{
     xdr_codec::XdrCodec,
 };
 
+#[cfg(feature = "all-types")]
+pub use xdr::impls::contract_spec::{ContractSpec, SpecValue};
+
+#[cfg(feature = "all-types")]
+#[doc(hidden)]
+pub use xdr::impls::contract_bindings::support as __contract_bindings;
+
 #[cfg(feature = "all-types")]
 pub use xdr::impls::transaction_set_type::*;
 
@@ -57,5 +88,10 @@ pub use utils::std::*;
 
 pub use amount::*;
 pub use binary::*;
+pub use key_store::*;
+pub use multisig::*;
 pub use public_key::*;
 pub use secret_key::*;
+pub use sep10::*;
+pub use sep29::*;
+pub use sep7::*;
@@ -0,0 +1,397 @@
+//! Analysis of the signatures of a transaction envelope
+//!
+//! Every account that is involved in a transaction (the source account of the transaction
+//! and the source accounts of its operations) needs to authorize the transaction. It does so
+//! if the total weight of its signers that signed the transaction reaches the threshold that
+//! the operations of this account require. See
+//! [Multisig](https://developers.stellar.org/docs/learn/encyclopedia/security/signatures-multisig).
+
+use sp_std::{boxed::Box, vec, vec::Vec};
+
+use crate::{
+    network::Network,
+    types::{
+        AccountEntry, DecoratedSignature, FeeBumpTransactionInnerTx, MuxedAccount, OperationBody, Signer, SignerKey,
+        Thresholds, TransactionEnvelope,
+    },
+    AccountId, PublicKey, StellarSdkError, Transaction,
+};
+
+const THRESHOLD_MASTER_WEIGHT: usize = 0;
+const THRESHOLD_LOW: usize = 1;
+const THRESHOLD_MED: usize = 2;
+const THRESHOLD_HIGH: usize = 3;
+
+/// The threshold level that an operation requires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThresholdLevel {
+    Low,
+    Medium,
+    High,
+}
+
+/// The signers and thresholds of an account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountSigners {
+    pub account_id: AccountId,
+    /// The thresholds as stored in the ledger: master weight, low, medium and high threshold
+    pub thresholds: Thresholds,
+    /// The additional signers of the account (without the master key)
+    pub signers: Vec<Signer>,
+}
+
+impl AccountSigners {
+    pub fn new(account_id: AccountId, thresholds: Thresholds, signers: Vec<Signer>) -> Self {
+        AccountSigners { account_id, thresholds, signers }
+    }
+
+    pub fn from_account_entry(account: &AccountEntry) -> Self {
+        AccountSigners {
+            account_id: account.account_id.clone(),
+            thresholds: account.thresholds,
+            signers: account.signers.get_vec().clone(),
+        }
+    }
+
+    /// Return the weight required for the threshold `level`
+    pub fn get_threshold(&self, level: ThresholdLevel) -> u8 {
+        match level {
+            ThresholdLevel::Low => self.thresholds[THRESHOLD_LOW],
+            ThresholdLevel::Medium => self.thresholds[THRESHOLD_MED],
+            ThresholdLevel::High => self.thresholds[THRESHOLD_HIGH],
+        }
+    }
+
+    /// Return all signers with a positive weight, including the master key
+    pub fn get_effective_signers(&self) -> Vec<Signer> {
+        let mut signers = Vec::with_capacity(self.signers.len() + 1);
+        if self.thresholds[THRESHOLD_MASTER_WEIGHT] > 0 {
+            signers.push(Signer {
+                key: SignerKey::SignerKeyTypeEd25519(*self.account_id.as_binary()),
+                weight: self.thresholds[THRESHOLD_MASTER_WEIGHT] as u32,
+            });
+        }
+        signers.extend(self.signers.iter().filter(|signer| signer.weight > 0).cloned());
+        signers
+    }
+}
+
+/// The result of the signature analysis for one account
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountSignatureReport {
+    pub account_id: AccountId,
+    pub required_level: ThresholdLevel,
+    pub required_weight: u8,
+    /// The total weight of the signers that signed the transaction
+    pub signed_weight: u32,
+    pub threshold_met: bool,
+    /// The signers of the account that did not sign the transaction
+    pub missing_signers: Vec<Signer>,
+}
+
+/// The result of the signature analysis of a transaction envelope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureReport {
+    pub accounts: Vec<AccountSignatureReport>,
+    /// The indices of the signatures of the envelope that do not belong to any signer of the
+    /// involved accounts (stellar-core rejects transactions with such signatures)
+    pub unused_signatures: Vec<usize>,
+    /// The report for the inner transaction of a fee bump transaction
+    pub inner_transaction: Option<Box<SignatureReport>>,
+}
+
+impl SignatureReport {
+    /// Return whether all thresholds are met and all signatures are used
+    pub fn is_sufficient(&self) -> bool {
+        self.accounts.iter().all(|account| account.threshold_met) &&
+            self.unused_signatures.is_empty() &&
+            self.inner_transaction
+                .as_ref()
+                .map(|inner| inner.is_sufficient())
+                .unwrap_or(true)
+    }
+}
+
+/// Return the threshold level that the operation `body` requires from its source account
+pub fn get_threshold_level(body: &OperationBody) -> ThresholdLevel {
+    match body {
+        OperationBody::AllowTrust(_) |
+        OperationBody::SetTrustLineFlags(_) |
+        OperationBody::BumpSequence(_) |
+        OperationBody::ClaimClaimableBalance(_) |
+        OperationBody::Inflation |
+        OperationBody::ExtendFootprintTtl(_) |
+        OperationBody::RestoreFootprint(_) => ThresholdLevel::Low,
+        OperationBody::AccountMerge(_) => ThresholdLevel::High,
+        OperationBody::SetOptions(set_options) => {
+            let changes_signers = set_options.master_weight.is_some() ||
+                set_options.low_threshold.is_some() ||
+                set_options.med_threshold.is_some() ||
+                set_options.high_threshold.is_some() ||
+                set_options.signer.is_some();
+            match changes_signers {
+                true => ThresholdLevel::High,
+                false => ThresholdLevel::Medium,
+            }
+        },
+        _ => ThresholdLevel::Medium,
+    }
+}
+
+fn get_account_id(muxed_account: &MuxedAccount) -> Result<AccountId, StellarSdkError> {
+    match muxed_account {
+        MuxedAccount::KeyTypeEd25519(key) => Ok(PublicKey::PublicKeyTypeEd25519(*key)),
+        MuxedAccount::KeyTypeMuxedEd25519(muxed_account) => Ok(PublicKey::PublicKeyTypeEd25519(muxed_account.ed25519)),
+        MuxedAccount::Default(_) => Err(StellarSdkError::InvalidStellarKeyEncoding),
+    }
+}
+
+fn add_requirement(requirements: &mut Vec<(AccountId, ThresholdLevel)>, account_id: AccountId, level: ThresholdLevel) {
+    match requirements
+        .iter_mut()
+        .find(|(existing_account_id, _)| *existing_account_id == account_id)
+    {
+        Some((_, existing_level)) => *existing_level = level.max(*existing_level),
+        None => requirements.push((account_id, level)),
+    }
+}
+
+/// Return the accounts that need to authorize the `transaction` together with the threshold
+/// level they need to reach
+///
+/// The source account of the transaction always needs to reach the low threshold. Returns an
+/// error if a source account is not an ed25519 account.
+pub fn get_required_thresholds(transaction: &Transaction) -> Result<Vec<(AccountId, ThresholdLevel)>, StellarSdkError> {
+    let transaction_source = get_account_id(&transaction.source_account)?;
+    let mut requirements = vec![(transaction_source.clone(), ThresholdLevel::Low)];
+
+    for operation in transaction.operations.get_vec() {
+        let source = match &operation.source_account {
+            Some(source_account) => get_account_id(source_account)?,
+            None => transaction_source.clone(),
+        };
+        add_requirement(&mut requirements, source, get_threshold_level(&operation.body));
+    }
+
+    Ok(requirements)
+}
+
+fn analyze(
+    transaction_hash: [u8; 32],
+    signatures: &[DecoratedSignature],
+    requirements: Vec<(AccountId, ThresholdLevel)>,
+    accounts: &[AccountSigners],
+) -> Result<SignatureReport, StellarSdkError> {
+    let mut used_signatures = vec![false; signatures.len()];
+    let mut account_reports = Vec::with_capacity(requirements.len());
+
+    for (account_id, required_level) in requirements {
+        let account = accounts
+            .iter()
+            .find(|account| account.account_id == account_id)
+            .ok_or_else(|| StellarSdkError::UnknownSignersOfAccount { account_id: account_id.to_encoding() })?;
+
+        let mut signed_weight = 0u32;
+        let mut has_signed_signer = false;
+        let mut missing_signers = vec![];
+
+        for signer in account.get_effective_signers() {
+            let signed = match &signer.key {
+                SignerKey::SignerKeyTypePreAuthTx(hash) => *hash == transaction_hash,
+                signer_key => {
+                    let mut signed = false;
+                    for (index, signature) in signatures.iter().enumerate() {
+                        if signer_key.is_signed_by(&transaction_hash, signature) {
+                            used_signatures[index] = true;
+                            signed = true;
+                        }
+                    }
+                    signed
+                },
+            };
+
+            match signed {
+                true => {
+                    has_signed_signer = true;
+                    signed_weight += signer.weight.min(u8::MAX as u32);
+                },
+                false => missing_signers.push(signer),
+            }
+        }
+
+        let required_weight = account.get_threshold(required_level);
+        account_reports.push(AccountSignatureReport {
+            account_id,
+            required_level,
+            required_weight,
+            signed_weight,
+            threshold_met: has_signed_signer && signed_weight >= required_weight as u32,
+            missing_signers,
+        });
+    }
+
+    let unused_signatures = used_signatures
+        .iter()
+        .enumerate()
+        .filter(|(_, used)| !**used)
+        .map(|(index, _)| index)
+        .collect();
+
+    Ok(SignatureReport { accounts: account_reports, unused_signatures, inner_transaction: None })
+}
+
+impl TransactionEnvelope {
+    /// Check whether the envelope carries enough signatures
+    ///
+    /// `accounts` must contain the signers and thresholds of all accounts involved in the
+    /// transaction, otherwise `UnknownSignersOfAccount` is returned. For a fee bump transaction
+    /// the fee source needs to reach the low threshold and the inner transaction is analyzed
+    /// separately (see `SignatureReport::inner_transaction`). Envelopes of an unknown type and
+    /// source accounts that are not ed25519 accounts are rejected.
+    pub fn analyze_signatures(
+        &self,
+        network: &Network,
+        accounts: &[AccountSigners],
+    ) -> Result<SignatureReport, StellarSdkError> {
+        match self {
+            TransactionEnvelope::EnvelopeTypeTxV0(envelope) => {
+                let transaction: Transaction = envelope.tx.clone().into();
+                analyze(
+                    self.get_hash(network),
+                    envelope.signatures.get_vec(),
+                    get_required_thresholds(&transaction)?,
+                    accounts,
+                )
+            },
+            TransactionEnvelope::EnvelopeTypeTx(envelope) => analyze(
+                self.get_hash(network),
+                envelope.signatures.get_vec(),
+                get_required_thresholds(&envelope.tx)?,
+                accounts,
+            ),
+            TransactionEnvelope::EnvelopeTypeTxFeeBump(envelope) => {
+                let inner_envelope = match &envelope.tx.inner_tx {
+                    FeeBumpTransactionInnerTx::EnvelopeTypeTx(inner) =>
+                        TransactionEnvelope::EnvelopeTypeTx(inner.clone()),
+                    FeeBumpTransactionInnerTx::Default(_) => return Err(StellarSdkError::UnsupportedEnvelopeType),
+                };
+
+                let requirements = vec![(get_account_id(&envelope.tx.fee_source)?, ThresholdLevel::Low)];
+                let mut report =
+                    analyze(self.get_hash(network), envelope.signatures.get_vec(), requirements, accounts)?;
+                report.inner_transaction = Some(Box::new(inner_envelope.analyze_signatures(network, accounts)?));
+                Ok(report)
+            },
+            TransactionEnvelope::Default(_) => Err(StellarSdkError::UnsupportedEnvelopeType),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_std::vec;
+
+    use crate::{
+        network::TEST_NETWORK,
+        types::{CryptoKeyType, DecoratedSignature, MuxedAccount},
+        utils::sha256::sha256,
+        xdr::compound_types::{LimitedVarArray, LimitedVarOpaque},
+        AccountSigners, Asset, IntoSecretKey, Operation, SecondEpochTime, Signer, SignerKey, StellarSdkError,
+        ThresholdLevel, TransactionBuilder, TransactionEnvelope, XdrCodec,
+    };
+
+    const SIGNER1: &str = "SCVKZEONBSU3XD6OTHXGAP6BTEWHOU4RPZQZJJ5AVAGPXUZ5A4D7MU6S";
+    const SIGNER2: &str = "SDOKV37I4TI655LMEMDQFOWESJ3LK6DDFKIVTYKN4YYTSAYFIBPP7MYI";
+    const SIGNER3: &str = "SCDSVACTNFNSD5LQZ5LWUWEY3UIAML2J7ALPFCD6ZX4D3TVJV7X243N3";
+
+    #[test]
+    fn analyze_signatures() {
+        let key1 = SIGNER1.into_secret_key().unwrap();
+        let key2 = SIGNER2.into_secret_key().unwrap();
+        let key3 = SIGNER3.into_secret_key().unwrap();
+        let account1 = key1.get_public().clone();
+        let account2 = key2.get_public().clone();
+
+        // account 1 is a 2-of-3 multisig account for medium operations
+        let preimage = b"secret preimage".to_vec();
+        let accounts = [
+            AccountSigners::new(
+                account1.clone(),
+                [1, 1, 2, 3],
+                vec![
+                    Signer::from_ed25519_public_key(key2.get_public().clone(), 1).unwrap(),
+                    Signer::from_hash_x(sha256(&preimage), 1).unwrap(),
+                ],
+            ),
+            AccountSigners::new(account2.clone(), [1, 0, 0, 0], vec![]),
+        ];
+
+        let transaction = TransactionBuilder::new(account1.clone(), 100)
+            .unwrap()
+            .set_timeout(SecondEpochTime(1_000), 100)
+            .add_operation(Operation::new_payment(account2.clone(), Asset::native(), "1").unwrap())
+            .add_operation(
+                Operation::new_bump_sequence(200)
+                    .unwrap()
+                    .set_source_account(account2.clone())
+                    .unwrap(),
+            )
+            .build()
+            .unwrap();
+        let mut envelope = TransactionEnvelope::from(transaction);
+
+        envelope.sign(&TEST_NETWORK, vec![&key1, &key3]).unwrap();
+        let report = envelope.analyze_signatures(&TEST_NETWORK, &accounts).unwrap();
+
+        assert_eq!(report.accounts[0].required_level, ThresholdLevel::Medium);
+        assert_eq!(report.accounts[0].signed_weight, 1);
+        assert!(!report.accounts[0].threshold_met);
+        assert_eq!(report.accounts[0].missing_signers.len(), 2);
+        assert_eq!(report.accounts[1].required_level, ThresholdLevel::Low);
+        assert!(!report.accounts[1].threshold_met);
+        assert_eq!(report.unused_signatures, vec![1]);
+        assert!(!report.is_sufficient());
+
+        let mut envelope = match envelope {
+            TransactionEnvelope::EnvelopeTypeTx(mut envelope) => {
+                envelope.signatures.pop();
+                envelope
+                    .signatures
+                    .push(DecoratedSignature {
+                        hint: SignerKey::from_hash_x(sha256(&preimage)).unwrap().get_signature_hint(),
+                        signature: LimitedVarOpaque::new(preimage).unwrap(),
+                    })
+                    .unwrap();
+                TransactionEnvelope::EnvelopeTypeTx(envelope)
+            },
+            _ => unreachable!(),
+        };
+        let report = envelope.analyze_signatures(&TEST_NETWORK, &accounts).unwrap();
+        assert_eq!(report.accounts[0].signed_weight, 2);
+        assert!(report.accounts[0].threshold_met);
+        assert!(report.unused_signatures.is_empty());
+
+        envelope.sign(&TEST_NETWORK, vec![&key2]).unwrap();
+        let report = envelope.analyze_signatures(&TEST_NETWORK, &accounts).unwrap();
+        assert_eq!(report.accounts[0].signed_weight, 3);
+        assert!(report.is_sufficient());
+        assert!(envelope.analyze_signatures(&TEST_NETWORK, &accounts[..1]).is_err());
+
+        if let TransactionEnvelope::EnvelopeTypeTx(envelope) = &mut envelope {
+            let mut operations = envelope.tx.operations.get_vec().clone();
+            operations[1].source_account = Some(MuxedAccount::Default(CryptoKeyType::KeyTypePreAuthTx));
+            envelope.tx.operations = LimitedVarArray::new(operations).unwrap();
+        }
+        let envelope = TransactionEnvelope::from_xdr(envelope.to_xdr()).unwrap();
+        assert_eq!(
+            envelope.analyze_signatures(&TEST_NETWORK, &accounts),
+            Err(StellarSdkError::InvalidStellarKeyEncoding)
+        );
+
+        let envelope = TransactionEnvelope::from_xdr([0, 0, 0, 9]).unwrap();
+        assert_eq!(
+            envelope.analyze_signatures(&TEST_NETWORK, &accounts),
+            Err(StellarSdkError::UnsupportedEnvelopeType)
+        );
+    }
+}
@@ -10,10 +10,27 @@ use crate::{
     PublicKey, StellarSdkError, XdrCodec,
 };
 
-use crate::utils::std::StellarTypeToString;
+use crate::utils::{
+    sha256::{sha256, BinarySha256Hash},
+    std::StellarTypeToString,
+};
 
 use sodalite::{sign_attached_open, Sign as Signature, SIGN_LEN};
 
+/// The prefix that [SEP-53](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0053.md)
+/// prepends to a message before it is hashed and signed
+pub const SEP53_MESSAGE_PREFIX: &[u8] = b"Stellar Signed Message:\n";
+
+/// Return the SEP-53 payload of `message`, this is the value that is actually signed
+pub fn sep53_message_hash<T: AsRef<[u8]>>(message: T) -> BinarySha256Hash {
+    let message = message.as_ref();
+    let mut payload = Vec::with_capacity(SEP53_MESSAGE_PREFIX.len() + message.len());
+    payload.extend_from_slice(SEP53_MESSAGE_PREFIX);
+    payload.extend_from_slice(message);
+
+    sha256(payload)
+}
+
 pub trait IntoPublicKey {
     fn into_public_key(self) -> Result<PublicKey, StellarSdkError>;
 }
@@ -107,4 +124,12 @@ impl PublicKey {
 
         sign_attached_open(&mut vec![0; message.len() + SIGN_LEN], &signed_message, self.as_binary()).is_ok()
     }
+
+    /// Verify a SEP-53 signature of an arbitrary message
+    ///
+    /// The signature must have been created for the SEP-53 payload of `message`,
+    /// see `SecretKey::sign_message`.
+    pub fn verify_message<T: AsRef<[u8]>>(&self, message: T, signature: &Signature) -> bool {
+        self.verify_signature(sep53_message_hash(message), signature)
+    }
 }
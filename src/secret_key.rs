@@ -10,6 +10,7 @@ use sodalite::{
 };
 
 use crate::{
+    public_key::sep53_message_hash,
     types::{Curve25519Secret, PublicKey},
     utils::key_encoding::{
         decode_stellar_key, encode_stellar_key, ED25519_SECRET_SEED_BYTE_LENGTH, ED25519_SECRET_SEED_VERSION_BYTE,
@@ -112,11 +113,22 @@ impl SecretKey {
         signed_message.truncate(SIGN_LEN);
         signed_message.try_into().unwrap()
     }
+
+    /// Create a SEP-53 signature for an arbitrary `message`
+    ///
+    /// The message is prefixed with `"Stellar Signed Message:\n"` and hashed before signing,
+    /// so that the signature can never be mistaken for a transaction signature.
+    pub fn sign_message<T: AsRef<[u8]>>(&self, message: T) -> Signature {
+        self.create_signature(sep53_message_hash(message))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::secret_key::{PublicKey, SecretKey};
+    use crate::{
+        secret_key::{PublicKey, SecretKey},
+        utils::base64,
+    };
 
     #[test]
     fn keypair() {
@@ -137,4 +149,20 @@ mod tests {
         let public_key = public_key.unwrap();
         assert_eq!(&public_key.to_encoding().as_slice(), &public.as_bytes());
     }
+
+    #[test]
+    fn sign_message() {
+        let secret = "SAKICEVQLYWGSOJS4WW7HZJWAHZVEEBS527LHK5V4MLJALYKICQCJXMW";
+        let public = "GBXFXNDLV4LSWA4VB7YIL5GBD7BVNR22SGBTDKMO2SBZZHDXSKZYCP7L";
+        let secret_key = SecretKey::from_encoding(secret).unwrap();
+        let public_key = PublicKey::from_encoding(public).unwrap();
+
+        let signature = secret_key.sign_message("Hello, World!");
+        assert_eq!(
+            base64::encode(signature),
+            b"fO5dbYhXUhBMhe6kId/cuVq/AfEnHRHEvsP8vXh03M1uLpi5e46yO2Q8rEBzu3feXQewcQE5GArp88u6ePK6BA=="
+        );
+        assert!(public_key.verify_message("Hello, World!", &signature));
+        assert!(!public_key.verify_message("Hello, World?", &signature));
+    }
 }
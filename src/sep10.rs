@@ -0,0 +1,518 @@
+//! Challenge transactions for Stellar Web Authentication
+//!
+//! A server proves to a client that it controls the server account by signing a challenge
+//! transaction. The client proves that it controls its account by signing the challenge with
+//! enough signers of its account and sending it back. The challenge has sequence number 0, so
+//! it can never be submitted to the network. See
+//! [SEP-10](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0010.md).
+
+use core::convert::TryInto;
+use sp_std::{vec, vec::Vec};
+
+use crate::{
+    network::Network,
+    types::{ManageDataOp, MuxedAccountMed25519, OperationBody, Preconditions},
+    utils::base64,
+    AccountSigners, IntoMuxedAccountId, Memo, MuxedAccount, Operation, PublicKey, SecretKey, SignatureStatus,
+    SignerKey, StellarSdkError, ThresholdLevel, TimeBounds, Transaction, TransactionEnvelope, BASE_FEE_STROOPS,
+};
+
+/// The number of random bytes of the nonce of a challenge
+pub const CHALLENGE_NONCE_LENGTH: usize = 48;
+
+const AUTH_SUFFIX: &[u8] = b" auth";
+const WEB_AUTH_DOMAIN_NAME: &[u8] = b"web_auth_domain";
+const CLIENT_DOMAIN_NAME: &[u8] = b"client_domain";
+
+/// The lower time bound of a challenge may be this many seconds in the future
+const MIN_TIME_GRACE_PERIOD: u64 = 300;
+
+/// A SEP-10 challenge
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sep10Challenge {
+    pub server_account: PublicKey,
+    /// The account that authenticates, this can be a muxed account
+    pub client_account: MuxedAccount,
+    /// An id memo that identifies a user of a shared client account
+    pub memo: Option<u64>,
+    pub home_domain: Vec<u8>,
+    pub web_auth_domain: Vec<u8>,
+    pub nonce: [u8; CHALLENGE_NONCE_LENGTH],
+    /// The domain of the client application and the signing key in its `stellar.toml`
+    pub client_domain: Option<(Vec<u8>, PublicKey)>,
+    pub time_bounds: TimeBounds,
+}
+
+/// The reason why a challenge transaction is rejected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sep10ChallengeError {
+    /// The challenge is not a V1 transaction envelope
+    UnsupportedEnvelopeType,
+
+    /// The source account of the transaction is not the server account
+    InvalidSourceAccount,
+
+    /// The sequence number of the transaction is not 0
+    InvalidSequenceNumber,
+
+    /// The transaction has no time bounds or no upper time bound
+    MissingTimeBounds,
+
+    /// The challenge is not valid yet
+    TooEarly { min_time: u64, now: u64 },
+
+    /// The challenge has expired
+    Expired { max_time: u64, now: u64 },
+
+    /// The operation with index `index` is not a `ManageData` operation with a source account
+    InvalidOperation { index: usize },
+
+    /// The name of the first operation is not `<home domain> auth` for one of the home domains
+    InvalidHomeDomain,
+
+    /// The value of the first operation is not a base64 encoded nonce of 48 bytes
+    InvalidNonce,
+
+    /// The value of the `web_auth_domain` operation is not the domain of the server
+    InvalidWebAuthDomain,
+
+    /// The source account of the operation with index `index` is not the server account or not
+    /// an (optionally muxed) ed25519 account
+    InvalidOperationSource { index: usize },
+
+    /// The memo is not an id memo or it is combined with a muxed client account
+    InvalidMemo,
+
+    /// The transaction is not signed by the server account
+    MissingServerSignature,
+
+    /// The transaction is not signed by the signing key of the client domain
+    MissingClientDomainSignature,
+
+    /// The signers of another account than the client account have been given
+    ClientAccountMismatch,
+
+    /// The transaction contains signatures that don't belong to the server, the client domain
+    /// or a signer of the client account, or signatures that are repeated
+    UnrecognizedSignatures,
+
+    /// The weight of the client signers does not reach the medium threshold of the client account
+    ThresholdNotMet { signed_weight: u32, threshold: u32 },
+}
+
+fn get_ed25519(account: &MuxedAccount) -> Option<[u8; 32]> {
+    match account {
+        MuxedAccount::KeyTypeEd25519(ed25519) => Some(*ed25519),
+        MuxedAccount::KeyTypeMuxedEd25519(MuxedAccountMed25519 { ed25519, .. }) => Some(*ed25519),
+        MuxedAccount::Default(_) => None,
+    }
+}
+
+fn get_manage_data(operation: &Operation) -> Option<(&MuxedAccount, &ManageDataOp)> {
+    match (&operation.source_account, &operation.body) {
+        (Some(source_account), OperationBody::ManageData(manage_data)) => Some((source_account, manage_data)),
+        _ => None,
+    }
+}
+
+fn get_data_value(manage_data: &ManageDataOp) -> &[u8] {
+    match &manage_data.data_value {
+        Some(data_value) => data_value.get_vec(),
+        None => &[],
+    }
+}
+
+impl Sep10Challenge {
+    /// Create a new challenge
+    ///
+    /// The `nonce` must be generated by a cryptographically secure random number generator.
+    /// The `time_bounds` usually span 15 minutes from now.
+    pub fn new<T: IntoMuxedAccountId, S: AsRef<[u8]>, U: AsRef<[u8]>>(
+        server_account: PublicKey,
+        client_account: T,
+        home_domain: S,
+        web_auth_domain: U,
+        nonce: [u8; CHALLENGE_NONCE_LENGTH],
+        time_bounds: TimeBounds,
+    ) -> Result<Self, StellarSdkError> {
+        Ok(Sep10Challenge {
+            server_account,
+            client_account: client_account.into_muxed_account_id()?,
+            memo: None,
+            home_domain: home_domain.as_ref().to_vec(),
+            web_auth_domain: web_auth_domain.as_ref().to_vec(),
+            nonce,
+            client_domain: None,
+            time_bounds,
+        })
+    }
+
+    /// Build the challenge transaction and sign it with the key of the server account
+    pub fn to_transaction_envelope(
+        &self,
+        server_key: &SecretKey,
+        network: &Network,
+    ) -> Result<TransactionEnvelope, StellarSdkError> {
+        if server_key.get_public() != &self.server_account {
+            return Err(StellarSdkError::InvalidSep10Challenge)
+        }
+        let memo = match (self.memo, &self.client_account) {
+            (None, _) => None,
+            (Some(id), MuxedAccount::KeyTypeEd25519(_)) => Some(Memo::MemoId(id)),
+            (Some(_), _) => return Err(StellarSdkError::InvalidSep10Challenge),
+        };
+
+        let mut transaction = Transaction::new(
+            self.server_account.clone(),
+            0,
+            Some(BASE_FEE_STROOPS),
+            Preconditions::PrecondTime(self.time_bounds.clone()),
+            memo,
+        )?;
+
+        let auth_name = [&self.home_domain[..], AUTH_SUFFIX].concat();
+        transaction.append_operation(
+            Operation::new_manage_data_put(auth_name, base64::encode(self.nonce))?
+                .set_source_account(self.client_account.clone())?,
+        )?;
+        transaction.append_operation(
+            Operation::new_manage_data_put(WEB_AUTH_DOMAIN_NAME, &self.web_auth_domain)?
+                .set_source_account(self.server_account.clone())?,
+        )?;
+        if let Some((client_domain, client_domain_account)) = &self.client_domain {
+            transaction.append_operation(
+                Operation::new_manage_data_put(CLIENT_DOMAIN_NAME, client_domain)?
+                    .set_source_account(client_domain_account.clone())?,
+            )?;
+        }
+
+        let mut envelope = transaction.into_transaction_envelope();
+        envelope.sign(network, vec![server_key])?;
+        Ok(envelope)
+    }
+
+    /// Read and check a challenge transaction at time `now` (in seconds since the epoch)
+    ///
+    /// The challenge must have been created and signed by `server_account` for one of the
+    /// `home_domains`. This does not check the signatures of the client, see
+    /// `verify_client_signatures`.
+    pub fn read(
+        envelope: &TransactionEnvelope,
+        network: &Network,
+        server_account: &PublicKey,
+        home_domains: &[&[u8]],
+        web_auth_domain: &[u8],
+        now: u64,
+    ) -> Result<Self, Sep10ChallengeError> {
+        let transaction = match envelope {
+            TransactionEnvelope::EnvelopeTypeTx(envelope) => &envelope.tx,
+            _ => return Err(Sep10ChallengeError::UnsupportedEnvelopeType),
+        };
+
+        let server_muxed_account = MuxedAccount::KeyTypeEd25519(*server_account.as_binary());
+        if transaction.source_account != server_muxed_account {
+            return Err(Sep10ChallengeError::InvalidSourceAccount)
+        }
+        if transaction.seq_num != 0 {
+            return Err(Sep10ChallengeError::InvalidSequenceNumber)
+        }
+
+        let time_bounds = match &transaction.cond {
+            Preconditions::PrecondTime(time_bounds) if time_bounds.max_time != 0 => time_bounds.clone(),
+            _ => return Err(Sep10ChallengeError::MissingTimeBounds),
+        };
+        if now.saturating_add(MIN_TIME_GRACE_PERIOD) < time_bounds.min_time {
+            return Err(Sep10ChallengeError::TooEarly { min_time: time_bounds.min_time, now })
+        }
+        if now > time_bounds.max_time {
+            return Err(Sep10ChallengeError::Expired { max_time: time_bounds.max_time, now })
+        }
+
+        let operations = transaction.operations.get_vec();
+        let (client_account, auth_operation) = operations
+            .first()
+            .and_then(get_manage_data)
+            .ok_or(Sep10ChallengeError::InvalidOperation { index: 0 })?;
+        if get_ed25519(client_account).is_none() {
+            return Err(Sep10ChallengeError::InvalidOperationSource { index: 0 })
+        }
+
+        let auth_name = auth_operation.data_name.get_vec();
+        let home_domain = auth_name
+            .strip_suffix(AUTH_SUFFIX)
+            .filter(|home_domain| home_domains.contains(home_domain))
+            .ok_or(Sep10ChallengeError::InvalidHomeDomain)?;
+
+        let nonce = base64::decode(get_data_value(auth_operation)).map_err(|_| Sep10ChallengeError::InvalidNonce)?;
+        let nonce: [u8; CHALLENGE_NONCE_LENGTH] =
+            nonce.as_slice().try_into().map_err(|_| Sep10ChallengeError::InvalidNonce)?;
+
+        let memo = match (&transaction.memo, client_account) {
+            (Memo::MemoNone, _) => None,
+            (Memo::MemoId(id), MuxedAccount::KeyTypeEd25519(_)) => Some(*id),
+            _ => return Err(Sep10ChallengeError::InvalidMemo),
+        };
+
+        let mut client_domain = None;
+        for (index, operation) in operations.iter().enumerate().skip(1) {
+            let (source_account, manage_data) =
+                get_manage_data(operation).ok_or(Sep10ChallengeError::InvalidOperation { index })?;
+            let data_name = manage_data.data_name.get_vec();
+
+            if data_name == CLIENT_DOMAIN_NAME {
+                let client_domain_account = get_ed25519(source_account)
+                    .map(PublicKey::from_binary)
+                    .ok_or(Sep10ChallengeError::InvalidOperationSource { index })?;
+                client_domain = Some((get_data_value(manage_data).to_vec(), client_domain_account));
+                continue
+            }
+
+            if source_account != &server_muxed_account {
+                return Err(Sep10ChallengeError::InvalidOperationSource { index })
+            }
+            if data_name == WEB_AUTH_DOMAIN_NAME && get_data_value(manage_data) != web_auth_domain {
+                return Err(Sep10ChallengeError::InvalidWebAuthDomain)
+            }
+        }
+
+        let server_signer = SignerKey::from(server_account.clone());
+        let report = envelope.verify_signatures(network, &[server_signer.clone()]);
+        if !report
+            .signatures
+            .iter()
+            .any(|status| matches!(status, SignatureStatus::Valid { signer } if signer == &server_signer))
+        {
+            return Err(Sep10ChallengeError::MissingServerSignature)
+        }
+
+        Ok(Sep10Challenge {
+            server_account: server_account.clone(),
+            client_account: client_account.clone(),
+            memo,
+            home_domain: home_domain.to_vec(),
+            web_auth_domain: web_auth_domain.to_vec(),
+            nonce,
+            client_domain,
+            time_bounds,
+        })
+    }
+
+    /// Verify the signatures of the client in the challenge `envelope`
+    ///
+    /// `client_signers` are the signers and thresholds of the client account or `None` if the
+    /// client account does not exist. In the latter case the challenge must be signed by the
+    /// master key of the client account. Otherwise the weight of the signers that signed must
+    /// reach the medium threshold of the client account. Apart from these, only the server
+    /// and the client domain may sign the challenge.
+    ///
+    /// Returns the signers of the client account that signed the challenge.
+    pub fn verify_client_signatures(
+        &self,
+        envelope: &TransactionEnvelope,
+        network: &Network,
+        client_signers: Option<&AccountSigners>,
+    ) -> Result<Vec<SignerKey>, Sep10ChallengeError> {
+        let client_ed25519 =
+            get_ed25519(&self.client_account).ok_or(Sep10ChallengeError::InvalidOperationSource { index: 0 })?;
+        let (signers, threshold) = match client_signers {
+            Some(client_signers) => {
+                if client_signers.account_id.as_binary() != &client_ed25519 {
+                    return Err(Sep10ChallengeError::ClientAccountMismatch)
+                }
+                let signers = client_signers
+                    .get_effective_signers()
+                    .into_iter()
+                    .filter(|signer| matches!(signer.key, SignerKey::SignerKeyTypeEd25519(_)))
+                    .map(|signer| (signer.key, signer.weight))
+                    .collect::<Vec<_>>();
+                (signers, client_signers.get_threshold(ThresholdLevel::Medium) as u32)
+            },
+            None => (vec![(SignerKey::SignerKeyTypeEd25519(client_ed25519), 1)], 1),
+        };
+
+        let server_signer = SignerKey::from(self.server_account.clone());
+        let client_domain_signer = self
+            .client_domain
+            .as_ref()
+            .map(|(_, client_domain_account)| SignerKey::from(client_domain_account.clone()));
+
+        let mut candidate_signers = vec![server_signer];
+        candidate_signers.extend(client_domain_signer.clone());
+        candidate_signers.extend(signers.iter().map(|(signer, _)| signer.clone()));
+
+        let mut signed_weight = 0u32;
+        let mut client_domain_signed = false;
+        let mut signed_by = Vec::new();
+        for status in envelope.verify_signatures(network, &candidate_signers).signatures {
+            let signer = match status {
+                SignatureStatus::Valid { signer } => signer,
+                _ => return Err(Sep10ChallengeError::UnrecognizedSignatures),
+            };
+
+            if Some(&signer) == client_domain_signer.as_ref() {
+                client_domain_signed = true;
+            }
+            if let Some((_, weight)) = signers.iter().find(|(client_signer, _)| client_signer == &signer) {
+                signed_weight = signed_weight.saturating_add(*weight);
+                signed_by.push(signer);
+            }
+        }
+
+        if client_domain_signer.is_some() && !client_domain_signed {
+            return Err(Sep10ChallengeError::MissingClientDomainSignature)
+        }
+        if signed_by.is_empty() || signed_weight < threshold {
+            return Err(Sep10ChallengeError::ThresholdNotMet { signed_weight, threshold })
+        }
+
+        Ok(signed_by)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        compound_types::LimitedVarArray,
+        network::TEST_NETWORK,
+        types::{CryptoKeyType, Signer},
+        AccountSigners, MuxedAccount, SecretKey, Sep10Challenge, Sep10ChallengeError, SignerKey, TimeBounds,
+        TransactionEnvelope, XdrCodec,
+    };
+
+    const SERVER_SECRET: &str = "SCVKZEONBSU3XD6OTHXGAP6BTEWHOU4RPZQZJJ5AVAGPXUZ5A4D7MU6S";
+    const CLIENT_SECRET: &str = "SCDSVACTNFNSD5LQZ5LWUWEY3UIAML2J7ALPFCD6ZX4D3TVJV7X243N3";
+    const OTHER_SECRET: &str = "SDOKV37I4TI655LMEMDQFOWESJ3LK6DDFKIVTYKN4YYTSAYFIBPP7MYI";
+
+    const HOME_DOMAIN: &[u8] = b"example.com";
+    const WEB_AUTH_DOMAIN: &[u8] = b"auth.example.com";
+
+    #[test]
+    fn challenge() {
+        let server_key = SecretKey::from_encoding(SERVER_SECRET).unwrap();
+        let client_key = SecretKey::from_encoding(CLIENT_SECRET).unwrap();
+        let other_key = SecretKey::from_encoding(OTHER_SECRET).unwrap();
+
+        let challenge = Sep10Challenge::new(
+            server_key.get_public().clone(),
+            client_key.get_public().clone(),
+            HOME_DOMAIN,
+            WEB_AUTH_DOMAIN,
+            [7; 48],
+            TimeBounds { min_time: 1_000, max_time: 1_900 },
+        )
+        .unwrap();
+        let mut envelope = challenge.to_transaction_envelope(&server_key, &TEST_NETWORK).unwrap();
+
+        let read = |envelope, now| {
+            Sep10Challenge::read(
+                envelope,
+                &TEST_NETWORK,
+                server_key.get_public(),
+                &[b"other.com", HOME_DOMAIN],
+                WEB_AUTH_DOMAIN,
+                now,
+            )
+        };
+        assert_eq!(read(&envelope, 1_000), Ok(challenge.clone()));
+        assert_eq!(read(&envelope, 2_000), Err(Sep10ChallengeError::Expired { max_time: 1_900, now: 2_000 }));
+        assert_eq!(
+            Sep10Challenge::read(
+                &envelope,
+                &TEST_NETWORK,
+                server_key.get_public(),
+                &[b"other.com"],
+                WEB_AUTH_DOMAIN,
+                1_000
+            ),
+            Err(Sep10ChallengeError::InvalidHomeDomain)
+        );
+        assert_eq!(
+            Sep10Challenge::read(
+                &envelope,
+                &TEST_NETWORK,
+                client_key.get_public(),
+                &[HOME_DOMAIN],
+                WEB_AUTH_DOMAIN,
+                1_000
+            ),
+            Err(Sep10ChallengeError::InvalidSourceAccount)
+        );
+
+        assert_eq!(
+            challenge.verify_client_signatures(&envelope, &TEST_NETWORK, None),
+            Err(Sep10ChallengeError::ThresholdNotMet { signed_weight: 0, threshold: 1 })
+        );
+        envelope.sign(&TEST_NETWORK, vec![&client_key]).unwrap();
+        let client_signer = SignerKey::from(client_key.get_public().clone());
+        assert_eq!(challenge.verify_client_signatures(&envelope, &TEST_NETWORK, None), Ok(vec![client_signer.clone()]));
+
+        let other_signer = SignerKey::from(other_key.get_public().clone());
+        let client_signers = AccountSigners::new(
+            client_key.get_public().clone(),
+            [1, 0, 3, 5],
+            vec![Signer { key: other_signer.clone(), weight: 2 }],
+        );
+        assert_eq!(
+            challenge.verify_client_signatures(&envelope, &TEST_NETWORK, Some(&client_signers)),
+            Err(Sep10ChallengeError::ThresholdNotMet { signed_weight: 1, threshold: 3 })
+        );
+        envelope.sign(&TEST_NETWORK, vec![&other_key]).unwrap();
+        assert_eq!(
+            challenge.verify_client_signatures(&envelope, &TEST_NETWORK, Some(&client_signers)),
+            Ok(vec![client_signer, other_signer])
+        );
+        assert_eq!(
+            challenge.verify_client_signatures(&envelope, &TEST_NETWORK, None),
+            Err(Sep10ChallengeError::UnrecognizedSignatures)
+        );
+    }
+
+    #[test]
+    fn unsupported_operation_source() {
+        let server_key = SecretKey::from_encoding(SERVER_SECRET).unwrap();
+        let client_key = SecretKey::from_encoding(CLIENT_SECRET).unwrap();
+        let other_key = SecretKey::from_encoding(OTHER_SECRET).unwrap();
+
+        let mut challenge = Sep10Challenge::new(
+            server_key.get_public().clone(),
+            client_key.get_public().clone(),
+            HOME_DOMAIN,
+            WEB_AUTH_DOMAIN,
+            [7; 48],
+            TimeBounds { min_time: 1_000, max_time: 1_900 },
+        )
+        .unwrap();
+        challenge.client_domain = Some((b"client.com".to_vec(), other_key.get_public().clone()));
+        let envelope = challenge.to_transaction_envelope(&server_key, &TEST_NETWORK).unwrap();
+
+        // the operations are the client authentication, the web auth domain and the client domain
+        for index in [0, 2] {
+            let mut envelope = envelope.clone();
+            if let TransactionEnvelope::EnvelopeTypeTx(envelope) = &mut envelope {
+                let mut operations = envelope.tx.operations.get_vec().clone();
+                operations[index].source_account = Some(MuxedAccount::Default(CryptoKeyType::KeyTypePreAuthTx));
+                envelope.tx.operations = LimitedVarArray::new(operations).unwrap();
+            }
+            let envelope = TransactionEnvelope::from_xdr(envelope.to_xdr()).unwrap();
+
+            assert_eq!(
+                Sep10Challenge::read(
+                    &envelope,
+                    &TEST_NETWORK,
+                    server_key.get_public(),
+                    &[HOME_DOMAIN],
+                    WEB_AUTH_DOMAIN,
+                    1_000
+                ),
+                Err(Sep10ChallengeError::InvalidOperationSource { index })
+            );
+        }
+
+        let challenge =
+            Sep10Challenge { client_account: MuxedAccount::Default(CryptoKeyType::KeyTypePreAuthTx), ..challenge };
+        assert_eq!(
+            challenge.verify_client_signatures(&envelope, &TEST_NETWORK, None),
+            Err(Sep10ChallengeError::InvalidOperationSource { index: 0 })
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! Check whether the destinations of a transaction require a memo
+//!
+//! An account signals that incoming payments need a memo by setting its data entry
+//! `config.memo_required` to `1`. See
+//! [SEP-29](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0029.md).
+
+use sp_std::{vec, vec::Vec};
+
+use crate::{
+    types::{Claimant, FeeBumpTransactionInnerTx, MuxedAccount, Operation, OperationBody},
+    AccountId, Memo, TransactionEnvelope,
+};
+
+/// The name of the data entry that marks an account as requiring a memo
+pub const MEMO_REQUIRED_DATA_NAME: &[u8] = b"config.memo_required";
+
+/// The value of the data entry `MEMO_REQUIRED_DATA_NAME` if a memo is required
+pub const MEMO_REQUIRED_DATA_VALUE: &[u8] = b"1";
+
+/// A source of the data entries of accounts, e.g. Horizon, a cache or a ledger snapshot
+pub trait AccountDataProvider {
+    type Error;
+
+    /// Return the (decoded) value of the data entry `name` of the account `account_id`
+    ///
+    /// Return `None` if the account or the data entry does not exist.
+    fn get_account_data(&self, account_id: &AccountId, name: &[u8]) -> Result<Option<Vec<u8>>, Self::Error>;
+}
+
+fn get_destinations(operation: &Operation) -> Vec<&MuxedAccount> {
+    match &operation.body {
+        OperationBody::Payment(body) => vec![&body.destination],
+        OperationBody::PathPaymentStrictReceive(body) => vec![&body.destination],
+        OperationBody::PathPaymentStrictSend(body) => vec![&body.destination],
+        OperationBody::AccountMerge(body) => vec![body],
+        _ => Vec::new(),
+    }
+}
+
+impl TransactionEnvelope {
+    /// Return all destinations of the transaction that require a memo
+    ///
+    /// If the transaction has a memo, no destination requires one. Destinations are the
+    /// destinations of payments, path payments and account merges and the claimants of
+    /// claimable balances. Muxed destinations are skipped because the muxed id replaces the
+    /// memo. For a fee bump transaction the inner transaction is checked.
+    pub fn check_memo_required<P: AccountDataProvider>(&self, provider: &P) -> Result<Vec<AccountId>, P::Error> {
+        let (memo, operations) = match self {
+            TransactionEnvelope::EnvelopeTypeTxV0(envelope) => (&envelope.tx.memo, &envelope.tx.operations),
+            TransactionEnvelope::EnvelopeTypeTx(envelope) => (&envelope.tx.memo, &envelope.tx.operations),
+            TransactionEnvelope::EnvelopeTypeTxFeeBump(envelope) => match &envelope.tx.inner_tx {
+                FeeBumpTransactionInnerTx::EnvelopeTypeTx(envelope) => (&envelope.tx.memo, &envelope.tx.operations),
+                FeeBumpTransactionInnerTx::Default(_) => unreachable!(),
+            },
+            TransactionEnvelope::Default(_) => unreachable!(),
+        };
+
+        if *memo != Memo::MemoNone {
+            return Ok(Vec::new())
+        }
+
+        let mut destinations: Vec<AccountId> = Vec::new();
+        for operation in operations.get_vec() {
+            for destination in get_destinations(operation) {
+                match destination {
+                    MuxedAccount::KeyTypeEd25519(destination) =>
+                        destinations.push(AccountId::from_binary(*destination)),
+                    MuxedAccount::KeyTypeMuxedEd25519(_) => continue,
+                    MuxedAccount::Default(_) => unreachable!(),
+                }
+            }
+
+            if let OperationBody::CreateClaimableBalance(body) = &operation.body {
+                destinations.extend(body.claimants.get_vec().iter().map(|claimant| match claimant {
+                    Claimant::ClaimantTypeV0(claimant) => claimant.destination.clone(),
+                }));
+            }
+        }
+
+        let mut checked_destinations: Vec<AccountId> = Vec::with_capacity(destinations.len());
+        let mut offending_destinations = Vec::new();
+        for destination in destinations {
+            if checked_destinations.contains(&destination) {
+                continue
+            }
+
+            let data = provider.get_account_data(&destination, MEMO_REQUIRED_DATA_NAME)?;
+            if data.as_deref() == Some(MEMO_REQUIRED_DATA_VALUE) {
+                offending_destinations.push(destination.clone());
+            }
+            checked_destinations.push(destination);
+        }
+
+        Ok(offending_destinations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_std::{cell::RefCell, vec::Vec};
+
+    use crate::{
+        types::{ClaimPredicate, Preconditions},
+        AccountDataProvider, AccountId, Asset, Claimant, FeeBumpTransaction, Memo, MuxedAccount, Operation, PublicKey,
+        StellarSdkError, StroopAmount, Transaction, MEMO_REQUIRED_DATA_NAME,
+    };
+
+    const SOURCE: &str = "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN";
+    const MEMO_REQUIRED: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+    const CLAIMANT_MEMO_REQUIRED: &str = "GAPIIRF3JXQAY63NSD6ALZ6JVUYDXCQIQIH5MZA3XYMR7LADTFKRBXZB";
+    const NO_MEMO_REQUIRED: &str = "GBBPS4JXB72222FPAHBGPUH3M6XIPG3CFDLIWDVPTAXJFH4WLYL46HQW";
+
+    struct Ledger {
+        requests: RefCell<Vec<AccountId>>,
+    }
+
+    impl AccountDataProvider for Ledger {
+        type Error = StellarSdkError;
+
+        fn get_account_data(&self, account_id: &AccountId, name: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
+            assert_eq!(name, MEMO_REQUIRED_DATA_NAME);
+            self.requests.borrow_mut().push(account_id.clone());
+            let requires_memo = [MEMO_REQUIRED, CLAIMANT_MEMO_REQUIRED]
+                .iter()
+                .any(|account| &PublicKey::from_encoding(account).unwrap() == account_id);
+            Ok(if requires_memo { Some(b"1".to_vec()) } else { None })
+        }
+    }
+
+    #[test]
+    fn memo_required() {
+        let build = |memo: Option<Memo>| {
+            let mut transaction = Transaction::new(SOURCE, 1, None, Preconditions::PrecondNone, memo).unwrap();
+            for destination in [NO_MEMO_REQUIRED, MEMO_REQUIRED, MEMO_REQUIRED] {
+                transaction
+                    .append_operation(Operation::new_payment(destination, Asset::native(), StroopAmount(100)).unwrap())
+                    .unwrap();
+            }
+            let muxed = MuxedAccount::from_muxed_account_id(MEMO_REQUIRED, 7).unwrap();
+            transaction
+                .append_operation(Operation::new_account_merge(muxed).unwrap())
+                .unwrap();
+            let claimant = Claimant::new(CLAIMANT_MEMO_REQUIRED, ClaimPredicate::ClaimPredicateUnconditional).unwrap();
+            transaction
+                .append_operation(
+                    Operation::new_create_claimable_balance(Asset::native(), StroopAmount(100), vec![claimant])
+                        .unwrap(),
+                )
+                .unwrap();
+            transaction.into_transaction_envelope()
+        };
+
+        let ledger = Ledger { requests: RefCell::new(Vec::new()) };
+        let envelope = build(None);
+        let expected = vec![
+            PublicKey::from_encoding(MEMO_REQUIRED).unwrap(),
+            PublicKey::from_encoding(CLAIMANT_MEMO_REQUIRED).unwrap(),
+        ];
+        assert_eq!(envelope.check_memo_required(&ledger), Ok(expected.clone()));
+        assert_eq!(ledger.requests.borrow().len(), 3);
+
+        let fee_bump = FeeBumpTransaction::new(SOURCE, StroopAmount(1000), envelope)
+            .unwrap()
+            .into_transaction_envelope();
+        assert_eq!(fee_bump.check_memo_required(&ledger), Ok(expected));
+
+        assert_eq!(build(Some(Memo::MemoId(1))).check_memo_required(&ledger), Ok(Vec::new()));
+    }
+}
@@ -0,0 +1,448 @@
+//! URIs for delegated signing and payment requests
+//!
+//! A `web+stellar:` URI asks a wallet to sign a transaction (`tx`) or to make a payment (`pay`),
+//! see [SEP-7](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0007.md).
+//! The origin of a request can sign the URI, a wallet verifies this signature against the
+//! `URI_REQUEST_SIGNING_KEY` in the `stellar.toml` of the `origin_domain`.
+
+use core::{convert::TryInto, str};
+use sp_std::{vec, vec::Vec};
+
+use crate::{
+    lib::ToString,
+    types::{AlphaNum12, AlphaNum4},
+    utils::{
+        base64,
+        percent_encode::{percent_decode, percent_encode},
+    },
+    Asset, IntoAmount, Memo, PublicKey, SecretKey, Signature, StellarSdkError, TransactionEnvelope, XdrCodec,
+    STROOPS_PER_LUMEN,
+};
+
+const SCHEME: &[u8] = b"web+stellar:";
+const CALLBACK_PREFIX: &[u8] = b"url:";
+const SIGNATURE_PAYLOAD_PREFIX: &[u8] = b"stellar.sep.7 - URI Scheme";
+const MAX_MESSAGE_LENGTH: usize = 300;
+
+/// The operation that a SEP-7 URI requests
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sep7Request {
+    /// `web+stellar:tx`: sign the transaction `envelope`
+    Transaction {
+        envelope: TransactionEnvelope,
+        /// A list of fields that the wallet should replace, in the form defined in SEP-11
+        replace: Option<Vec<u8>>,
+        /// The public key that should sign the transaction
+        pubkey: Option<PublicKey>,
+        /// A nested SEP-7 URI that has been forwarded by the origin domain
+        chain: Option<Vec<u8>>,
+    },
+
+    /// `web+stellar:pay`: pay to `destination` (an account, muxed account or contract address)
+    Pay {
+        destination: Vec<u8>,
+        /// The amount in stroops, if not given the wallet asks the user for the amount
+        amount: Option<i64>,
+        asset: Asset,
+        memo: Option<Memo>,
+    },
+}
+
+/// A SEP-7 URI
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sep7Uri {
+    pub request: Sep7Request,
+    /// The URL that the signed transaction should be posted to instead of submitting it
+    pub callback: Option<Vec<u8>>,
+    /// A message for the user, at most 300 characters
+    pub msg: Option<Vec<u8>>,
+    pub network_passphrase: Option<Vec<u8>>,
+    pub origin_domain: Option<Vec<u8>>,
+    pub signature: Option<Signature>,
+}
+
+fn invalid_parameter(name: &[u8]) -> StellarSdkError {
+    StellarSdkError::InvalidSep7Parameter { name: name.to_vec() }
+}
+
+fn format_amount(stroops: i64) -> Vec<u8> {
+    let mut formatted = (stroops / STROOPS_PER_LUMEN).to_string().into_bytes();
+    let decimals = stroops % STROOPS_PER_LUMEN;
+    if decimals != 0 {
+        let mut decimals = (decimals + STROOPS_PER_LUMEN).to_string().into_bytes();
+        while decimals.last() == Some(&b'0') {
+            decimals.pop();
+        }
+        formatted.push(b'.');
+        formatted.extend_from_slice(&decimals[1..]);
+    }
+    formatted
+}
+
+fn encode_memo(memo: &Memo) -> Option<(Vec<u8>, &'static [u8])> {
+    match memo {
+        Memo::MemoNone => None,
+        Memo::MemoText(text) => Some((text.get_vec().clone(), b"MEMO_TEXT")),
+        Memo::MemoId(id) => Some((id.to_string().into_bytes(), b"MEMO_ID")),
+        Memo::MemoHash(hash) => Some((base64::encode(hash), b"MEMO_HASH")),
+        Memo::MemoReturn(hash) => Some((base64::encode(hash), b"MEMO_RETURN")),
+    }
+}
+
+fn decode_memo(memo: &[u8], memo_type: &[u8]) -> Result<Memo, StellarSdkError> {
+    let invalid = || invalid_parameter(b"memo");
+    let decode_hash = || -> Result<[u8; 32], StellarSdkError> {
+        base64::decode(memo)
+            .ok()
+            .and_then(|hash| hash.try_into().ok())
+            .ok_or_else(invalid)
+    };
+
+    match memo_type {
+        b"MEMO_TEXT" => Memo::from_text_memo(memo).map_err(|_| invalid()),
+        b"MEMO_ID" => str::from_utf8(memo)
+            .ok()
+            .and_then(|id| id.parse().ok())
+            .map(Memo::MemoId)
+            .ok_or_else(invalid),
+        b"MEMO_HASH" => Ok(Memo::MemoHash(decode_hash()?)),
+        b"MEMO_RETURN" => Ok(Memo::MemoReturn(decode_hash()?)),
+        _ => Err(invalid_parameter(b"memo_type")),
+    }
+}
+
+fn signature_payload(unsigned_uri: &[u8]) -> Vec<u8> {
+    let mut payload = vec![0; 36];
+    payload[35] = 4;
+    payload.extend_from_slice(SIGNATURE_PAYLOAD_PREFIX);
+    payload.extend_from_slice(unsigned_uri);
+    payload
+}
+
+/// Split the `signature` parameter (which has to be the last parameter) off the URI
+fn split_signature(uri: &[u8]) -> (&[u8], Option<&[u8]>) {
+    const SIGNATURE_PARAMETER: &[u8] = b"&signature=";
+    match uri
+        .windows(SIGNATURE_PARAMETER.len())
+        .rposition(|window| window == SIGNATURE_PARAMETER)
+    {
+        Some(position) => (&uri[..position], Some(&uri[position + SIGNATURE_PARAMETER.len()..])),
+        None => (uri, None),
+    }
+}
+
+fn decode_signature(encoded: &[u8]) -> Result<Signature, StellarSdkError> {
+    let signature = base64::decode(encoded).map_err(|_| invalid_parameter(b"signature"))?;
+    signature.try_into().map_err(|_| invalid_parameter(b"signature"))
+}
+
+struct Parameters {
+    parameters: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+impl Parameters {
+    fn push<T: AsRef<[u8]>>(&mut self, name: &[u8], value: Option<T>) {
+        if let Some(value) = value {
+            self.parameters.push((name.to_vec(), value.as_ref().to_vec()));
+        }
+    }
+
+    fn take(&mut self, name: &[u8]) -> Option<Vec<u8>> {
+        let position = self.parameters.iter().position(|(parameter_name, _)| parameter_name == name)?;
+        Some(self.parameters.remove(position).1)
+    }
+
+    fn take_required(&mut self, name: &[u8]) -> Result<Vec<u8>, StellarSdkError> {
+        self.take(name)
+            .ok_or_else(|| StellarSdkError::MissingSep7Parameter { name: name.to_vec() })
+    }
+}
+
+impl Sep7Uri {
+    /// Create a `web+stellar:tx` URI without optional parameters
+    pub fn transaction(envelope: TransactionEnvelope) -> Self {
+        Self::new(Sep7Request::Transaction { envelope, replace: None, pubkey: None, chain: None })
+    }
+
+    /// Create a `web+stellar:pay` URI without optional parameters, the asset is XLM
+    pub fn pay<T: AsRef<[u8]>>(destination: T) -> Self {
+        Self::new(Sep7Request::Pay {
+            destination: destination.as_ref().to_vec(),
+            amount: None,
+            asset: Asset::AssetTypeNative,
+            memo: None,
+        })
+    }
+
+    fn new(request: Sep7Request) -> Self {
+        Sep7Uri { request, callback: None, msg: None, network_passphrase: None, origin_domain: None, signature: None }
+    }
+
+    /// Parse a SEP-7 URI
+    ///
+    /// Unknown parameters are ignored.
+    pub fn parse<T: AsRef<[u8]>>(uri: T) -> Result<Self, StellarSdkError> {
+        let uri = uri.as_ref().strip_prefix(SCHEME).ok_or(StellarSdkError::InvalidSep7Uri)?;
+        let separator = uri
+            .iter()
+            .position(|char| *char == b'?')
+            .ok_or(StellarSdkError::InvalidSep7Uri)?;
+        let (operation, query) = (&uri[..separator], &uri[separator + 1..]);
+
+        let mut parameters = Parameters { parameters: Vec::new() };
+        for parameter in query.split(|char| *char == b'&').filter(|parameter| !parameter.is_empty()) {
+            let (name, value) = match parameter.iter().position(|char| *char == b'=') {
+                Some(position) => (&parameter[..position], &parameter[position + 1..]),
+                None => (parameter, &b""[..]),
+            };
+            if parameters.parameters.iter().any(|(parameter_name, _)| parameter_name == name) {
+                return Err(invalid_parameter(name))
+            }
+            let value = percent_decode(value).map_err(|_| invalid_parameter(name))?;
+            parameters.parameters.push((name.to_vec(), value));
+        }
+
+        let request = match operation {
+            b"tx" => {
+                let envelope = TransactionEnvelope::from_base64_xdr(parameters.take_required(b"xdr")?)
+                    .map_err(|_| invalid_parameter(b"xdr"))?;
+                let pubkey = match parameters.take(b"pubkey") {
+                    Some(pubkey) => Some(PublicKey::from_encoding(pubkey).map_err(|_| invalid_parameter(b"pubkey"))?),
+                    None => None,
+                };
+                Sep7Request::Transaction {
+                    envelope,
+                    replace: parameters.take(b"replace"),
+                    pubkey,
+                    chain: parameters.take(b"chain"),
+                }
+            },
+            b"pay" => {
+                let destination = parameters.take_required(b"destination")?;
+                let amount = match parameters.take(b"amount") {
+                    Some(amount) => Some(amount.into_stroop_amount(false).map_err(|_| invalid_parameter(b"amount"))?),
+                    None => None,
+                };
+                let asset = match (parameters.take(b"asset_code"), parameters.take(b"asset_issuer")) {
+                    (None, None) => Asset::AssetTypeNative,
+                    (Some(asset_code), Some(asset_issuer)) => Asset::from_asset_code(asset_code, asset_issuer)
+                        .map_err(|_| invalid_parameter(b"asset_code"))?,
+                    (None, Some(_)) =>
+                        return Err(StellarSdkError::MissingSep7Parameter { name: b"asset_code".to_vec() }),
+                    (Some(_), None) =>
+                        return Err(StellarSdkError::MissingSep7Parameter { name: b"asset_issuer".to_vec() }),
+                };
+                let memo_type = parameters.take(b"memo_type");
+                let memo = match parameters.take(b"memo") {
+                    Some(memo) => Some(decode_memo(&memo, memo_type.as_deref().unwrap_or(b"MEMO_TEXT"))?),
+                    None => None,
+                };
+                Sep7Request::Pay { destination, amount, asset, memo }
+            },
+            _ => return Err(StellarSdkError::InvalidSep7Uri),
+        };
+
+        let callback = match parameters.take(b"callback") {
+            Some(callback) => Some(
+                callback
+                    .strip_prefix(CALLBACK_PREFIX)
+                    .ok_or_else(|| invalid_parameter(b"callback"))?
+                    .to_vec(),
+            ),
+            None => None,
+        };
+        let msg = parameters.take(b"msg");
+        if matches!(&msg, Some(msg) if msg.len() > MAX_MESSAGE_LENGTH) {
+            return Err(invalid_parameter(b"msg"))
+        }
+        let signature = match parameters.take(b"signature") {
+            Some(signature) => Some(decode_signature(&signature)?),
+            None => None,
+        };
+
+        Ok(Sep7Uri {
+            request,
+            callback,
+            msg,
+            network_passphrase: parameters.take(b"network_passphrase"),
+            origin_domain: parameters.take(b"origin_domain"),
+            signature,
+        })
+    }
+
+    /// Return the URI as an ASCII string (given as `Vec<u8>`)
+    ///
+    /// The signature is always the last parameter.
+    pub fn to_uri(&self) -> Vec<u8> {
+        let mut uri = self.to_unsigned_uri();
+        if let Some(signature) = &self.signature {
+            uri.extend_from_slice(b"&signature=");
+            uri.extend(percent_encode(base64::encode(signature)));
+        }
+        uri
+    }
+
+    fn to_unsigned_uri(&self) -> Vec<u8> {
+        let mut parameters = Parameters { parameters: Vec::new() };
+        let callback = self.callback.as_ref().map(|callback| [CALLBACK_PREFIX, callback].concat());
+
+        let mut uri = SCHEME.to_vec();
+        match &self.request {
+            Sep7Request::Transaction { envelope, replace, pubkey, chain } => {
+                uri.extend_from_slice(b"tx");
+                parameters.push(b"xdr", Some(envelope.to_base64_xdr()));
+                parameters.push(b"replace", replace.as_ref());
+                parameters.push(b"callback", callback);
+                parameters.push(b"pubkey", pubkey.as_ref().map(|pubkey| pubkey.to_encoding()));
+                parameters.push(b"chain", chain.as_ref());
+            },
+            Sep7Request::Pay { destination, amount, asset, memo } => {
+                uri.extend_from_slice(b"pay");
+                parameters.push(b"destination", Some(destination));
+                parameters.push(b"amount", amount.map(format_amount));
+                let (asset_code, asset_issuer) = match asset {
+                    Asset::AssetTypeCreditAlphanum4(AlphaNum4 { asset_code, issuer }) =>
+                        (Some(asset_code.to_vec()), Some(issuer.to_encoding())),
+                    Asset::AssetTypeCreditAlphanum12(AlphaNum12 { asset_code, issuer }) =>
+                        (Some(asset_code.to_vec()), Some(issuer.to_encoding())),
+                    _ => (None, None),
+                };
+                let asset_code = asset_code
+                    .map(|asset_code| asset_code.into_iter().take_while(|char| *char != 0).collect::<Vec<_>>());
+                parameters.push(b"asset_code", asset_code);
+                parameters.push(b"asset_issuer", asset_issuer);
+                let (memo, memo_type) = match memo.as_ref().and_then(encode_memo) {
+                    Some((memo, memo_type)) => (Some(memo), Some(memo_type)),
+                    None => (None, None),
+                };
+                parameters.push(b"memo", memo);
+                parameters.push(b"memo_type", memo_type);
+                parameters.push(b"callback", callback);
+            },
+        }
+        parameters.push(b"msg", self.msg.as_ref());
+        parameters.push(b"network_passphrase", self.network_passphrase.as_ref());
+        parameters.push(b"origin_domain", self.origin_domain.as_ref());
+
+        for (index, (name, value)) in parameters.parameters.iter().enumerate() {
+            uri.push(if index == 0 { b'?' } else { b'&' });
+            uri.extend_from_slice(name);
+            uri.push(b'=');
+            uri.extend(percent_encode(value));
+        }
+        uri
+    }
+
+    /// Sign the URI with the signing key of the origin domain
+    ///
+    /// The signature covers the URI as returned by `to_uri`, so the URI must not be modified
+    /// after signing.
+    pub fn sign(&mut self, secret_key: &SecretKey) {
+        let signature = secret_key.create_signature(signature_payload(&self.to_unsigned_uri()));
+        self.signature = Some(signature);
+    }
+
+    /// Verify the signature of this URI (as returned by `to_uri`) against `public_key`
+    ///
+    /// Returns `false` if the URI is not signed. Use `verify_uri_signature` to verify the
+    /// signature of a URI that has been created by another implementation, which may order or
+    /// encode the parameters differently.
+    pub fn verify_signature(&self, public_key: &PublicKey) -> bool {
+        match &self.signature {
+            Some(signature) => public_key.verify_signature(signature_payload(&self.to_unsigned_uri()), signature),
+            None => false,
+        }
+    }
+
+    /// Verify the signature of the SEP-7 URI string `uri` against `public_key`
+    ///
+    /// The signature is verified for the URI as given, so it does not depend on how the
+    /// parameters are ordered or encoded. Returns `false` if the URI is not signed.
+    pub fn verify_uri_signature<T: AsRef<[u8]>>(uri: T, public_key: &PublicKey) -> Result<bool, StellarSdkError> {
+        let uri = uri.as_ref();
+        Sep7Uri::parse(uri)?;
+
+        match split_signature(uri) {
+            (unsigned_uri, Some(signature)) => Ok(public_key
+                .verify_signature(signature_payload(unsigned_uri), &decode_signature(&percent_decode(signature)?)?)),
+            (_, None) => Ok(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        types::Preconditions, Asset, IntoAmount, Memo, Operation, SecretKey, Sep7Request, Sep7Uri, StellarSdkError,
+        Transaction, TransactionEnvelope,
+    };
+
+    const DESTINATION: &str = "GCALNQQBXAPZ2WIRSDDBMSTAKCUH5SG6U76YBFLQLIXJTF7FE5AX7AOO";
+    const ISSUER: &str = "GBNKQVTFRP25TIQRODMU5GJGSXDKHCEUDN7LNMOS5PNM427LMR77NV4M";
+    const SECRET: &str = "SBA2XQ5SRUW5H3FUQARMC6QYEPUYNSVCMM4PGESGVB2UIFHLM73TPXXF";
+
+    #[test]
+    fn pay_uri() {
+        let mut uri = Sep7Uri::pay(DESTINATION);
+        uri.request = Sep7Request::Pay {
+            destination: DESTINATION.as_bytes().to_vec(),
+            amount: Some("120.1234567".into_stroop_amount(false).unwrap()),
+            asset: Asset::from_asset_code("USD", ISSUER).unwrap(),
+            memo: Some(Memo::from_text_memo("order 42").unwrap()),
+        };
+        uri.msg = Some(b"pay me with lumens".to_vec());
+        uri.callback = Some(b"https://example.com/callback?id=1".to_vec());
+        uri.origin_domain = Some(b"example.com".to_vec());
+
+        let encoded = uri.to_uri();
+        assert_eq!(
+            encoded,
+            [
+                "web+stellar:pay?destination=",
+                DESTINATION,
+                "&amount=120.1234567&asset_code=USD&asset_issuer=",
+                ISSUER,
+                "&memo=order%2042&memo_type=MEMO_TEXT&callback=url%3Ahttps%3A%2F%2Fexample.com%2Fcallback%3Fid%3D1",
+                "&msg=pay%20me%20with%20lumens&origin_domain=example.com"
+            ]
+            .concat()
+            .into_bytes()
+        );
+        assert_eq!(Sep7Uri::parse(&encoded), Ok(uri.clone()));
+
+        let secret_key = SecretKey::from_encoding(SECRET).unwrap();
+        uri.sign(&secret_key);
+        assert!(uri.verify_signature(secret_key.get_public()));
+        let signed = uri.to_uri();
+        assert_eq!(Sep7Uri::verify_uri_signature(&signed, secret_key.get_public()), Ok(true));
+        assert_eq!(Sep7Uri::parse(&signed), Ok(uri.clone()));
+
+        let tampered = String::from_utf8(signed).unwrap().replace("order%2042", "order%2043");
+        assert_eq!(Sep7Uri::verify_uri_signature(tampered, secret_key.get_public()), Ok(false));
+        uri.msg = None;
+        assert!(!uri.verify_signature(secret_key.get_public()));
+
+        assert_eq!(
+            Sep7Uri::parse("web+stellar:pay?amount=1"),
+            Err(StellarSdkError::MissingSep7Parameter { name: b"destination".to_vec() })
+        );
+        assert_eq!(Sep7Uri::parse("https://example.com?tx=1"), Err(StellarSdkError::InvalidSep7Uri));
+    }
+
+    #[test]
+    fn transaction_uri() {
+        let mut transaction = Transaction::new(DESTINATION, 1, None, Preconditions::PrecondNone, None).unwrap();
+        transaction.append_operation(Operation::new_bump_sequence(2).unwrap()).unwrap();
+        let envelope: TransactionEnvelope = transaction.into_transaction_envelope();
+
+        let mut uri = Sep7Uri::transaction(envelope);
+        if let Sep7Request::Transaction { replace, .. } = &mut uri.request {
+            *replace = Some(b"sourceAccount:X;X:account to pay fees from".to_vec());
+        }
+        uri.network_passphrase = Some(b"Test SDF Network ; September 2015".to_vec());
+
+        let encoded = uri.to_uri();
+        assert!(encoded.starts_with(b"web+stellar:tx?xdr=AAAAA"));
+        assert_eq!(Sep7Uri::parse(&encoded), Ok(uri));
+    }
+}
@@ -0,0 +1,255 @@
+//! Mapping between Stellar accounts and Substrate accounts
+//!
+//! A Stellar account id (G-address) and a Substrate `AccountId32` are both 32 byte
+//! ed25519 public keys, so they are mapped onto each other one to one.
+//! A muxed account (M-address) with a non-trivial sub account id is mapped
+//! to a derived account, see `MuxedAccount::to_substrate_account`.
+
+use core::convert::{AsRef, TryInto};
+use sp_core::hashing::{blake2_256, blake2_512};
+use sp_runtime::AccountId32;
+use sp_std::{vec, vec::Vec};
+
+use crate::{
+    types::MuxedAccountMed25519, utils::base58, xdr::xdr_codec::XdrCodec, MuxedAccount, PublicKey, StellarSdkError,
+};
+
+const SS58_CHECKSUM_PREFIX: &[u8] = b"SS58PRE";
+const SS58_CHECKSUM_LENGTH: usize = 2;
+const SS58_RESERVED_PREFIXES: [u16; 2] = [46, 47];
+const SS58_MAX_PREFIX: u16 = 16_383;
+
+const STRKEY_ACCOUNT_LENGTH: usize = 56;
+const STRKEY_MUXED_ACCOUNT_LENGTH: usize = 69;
+
+const MUXED_ACCOUNT_DERIVATION_PREFIX: &[u8] = b"stellar:muxed";
+
+fn ss58_checksum(data: &[u8]) -> [u8; SS58_CHECKSUM_LENGTH] {
+    let mut payload = Vec::with_capacity(SS58_CHECKSUM_PREFIX.len() + data.len());
+    payload.extend_from_slice(SS58_CHECKSUM_PREFIX);
+    payload.extend_from_slice(data);
+
+    blake2_512(&payload)[..SS58_CHECKSUM_LENGTH].try_into().unwrap()
+}
+
+/// Encode an `AccountId32` as an SS58 address for the network with the address format `prefix`
+///
+/// Returns an error if `prefix` is not a valid SS58 address format (greater than 16383
+/// or one of the reserved formats 46 and 47).
+pub fn encode_ss58(account_id: &AccountId32, prefix: u16) -> Result<Vec<u8>, StellarSdkError> {
+    if prefix > SS58_MAX_PREFIX || SS58_RESERVED_PREFIXES.contains(&prefix) {
+        return Err(StellarSdkError::InvalidSs58Encoding)
+    }
+
+    let mut data = match prefix {
+        0..=63 => vec![prefix as u8],
+        _ => vec![
+            (((prefix & 0b0000_0000_1111_1100) >> 2) as u8) | 0b0100_0000,
+            ((prefix >> 8) as u8) | (((prefix & 0b0000_0000_0000_0011) << 6) as u8),
+        ],
+    };
+    data.extend_from_slice(account_id.as_ref());
+
+    let checksum = ss58_checksum(&data);
+    data.extend_from_slice(&checksum);
+
+    Ok(base58::encode(data))
+}
+
+/// Decode an SS58 address into an `AccountId32` and its address format
+///
+/// This checks the length and the checksum of the address.
+pub fn decode_ss58<T: AsRef<[u8]>>(address: T) -> Result<(AccountId32, u16), StellarSdkError> {
+    let data = base58::decode(address)?;
+
+    let (prefix, prefix_length) = match data.first() {
+        Some(first @ 0..=63) => (*first as u16, 1),
+        Some(64..=127) if data.len() > 1 => {
+            let lower = (data[0] << 2) | (data[1] >> 6);
+            let upper = data[1] & 0b0011_1111;
+            ((lower as u16) | ((upper as u16) << 8), 2)
+        },
+        _ => return Err(StellarSdkError::InvalidSs58Encoding),
+    };
+
+    if data.len() != prefix_length + 32 + SS58_CHECKSUM_LENGTH || SS58_RESERVED_PREFIXES.contains(&prefix) {
+        return Err(StellarSdkError::InvalidSs58Encoding)
+    }
+
+    let (payload, checksum) = data.split_at(prefix_length + 32);
+    if ss58_checksum(payload) != checksum {
+        return Err(StellarSdkError::InvalidSs58Checksum)
+    }
+
+    let account_id: [u8; 32] = payload[prefix_length..].try_into().unwrap();
+    Ok((AccountId32::new(account_id), prefix))
+}
+
+impl PublicKey {
+    /// Return the SS58 address of this public key for the address format `prefix`
+    pub fn to_ss58_address(&self, prefix: u16) -> Result<Vec<u8>, StellarSdkError> {
+        encode_ss58(&AccountId32::new(*self.as_binary()), prefix)
+    }
+
+    /// Decode a public key from an SS58 address
+    ///
+    /// Returns an error if the address is not encoded for the address format `prefix`.
+    pub fn from_ss58_address<T: AsRef<[u8]>>(address: T, prefix: u16) -> Result<Self, StellarSdkError> {
+        let (account_id, found_prefix) = decode_ss58(address)?;
+        if found_prefix != prefix {
+            return Err(StellarSdkError::UnexpectedSs58Prefix { expected: prefix, found: found_prefix })
+        }
+
+        Ok(account_id.into())
+    }
+}
+
+impl From<AccountId32> for PublicKey {
+    fn from(account_id: AccountId32) -> Self {
+        PublicKey::from_binary(account_id.into())
+    }
+}
+
+impl MuxedAccount {
+    /// Return the Substrate account that this (possibly muxed) account maps to
+    ///
+    /// A `KeyTypeEd25519` account maps to the `AccountId32` with the same 32 bytes.
+    /// A `KeyTypeMuxedEd25519` account maps to a derived account: the blake2-256 hash of
+    /// `"stellar:muxed"`, followed by the XDR encoding of the muxed account (the ed25519 key
+    /// and the big endian 64 bit sub account id). This mapping is deterministic but cannot
+    /// be reversed.
+    pub fn to_substrate_account(&self) -> AccountId32 {
+        match self {
+            MuxedAccount::KeyTypeEd25519(account_id) => AccountId32::new(*account_id),
+            MuxedAccount::KeyTypeMuxedEd25519(muxed_account) => derive_muxed_sub_account(muxed_account),
+            MuxedAccount::Default(_) => unreachable!("Invalid muxed account type"),
+        }
+    }
+}
+
+fn derive_muxed_sub_account(muxed_account: &MuxedAccountMed25519) -> AccountId32 {
+    let muxed_account_xdr = muxed_account.to_xdr();
+    let mut payload = Vec::with_capacity(MUXED_ACCOUNT_DERIVATION_PREFIX.len() + muxed_account_xdr.len());
+    payload.extend_from_slice(MUXED_ACCOUNT_DERIVATION_PREFIX);
+    payload.extend_from_slice(&muxed_account_xdr);
+
+    AccountId32::new(blake2_256(&payload))
+}
+
+/// The trait for parameters that represent a Substrate `AccountId32`
+///
+/// Strings can be Stellar G-addresses and M-addresses or SS58 addresses of any network.
+/// Muxed accounts are mapped as described in `MuxedAccount::to_substrate_account`.
+pub trait IntoAccountId32 {
+    fn into_account_id32(self) -> Result<AccountId32, StellarSdkError>;
+}
+
+impl IntoAccountId32 for AccountId32 {
+    fn into_account_id32(self) -> Result<AccountId32, StellarSdkError> {
+        Ok(self)
+    }
+}
+
+impl IntoAccountId32 for PublicKey {
+    fn into_account_id32(self) -> Result<AccountId32, StellarSdkError> {
+        Ok(self.into())
+    }
+}
+
+impl IntoAccountId32 for MuxedAccount {
+    fn into_account_id32(self) -> Result<AccountId32, StellarSdkError> {
+        Ok(self.to_substrate_account())
+    }
+}
+
+impl IntoAccountId32 for &[u8] {
+    fn into_account_id32(self) -> Result<AccountId32, StellarSdkError> {
+        // Stellar strkeys of accounts have 56 (G) or 69 (M) characters, SS58 addresses of
+        // 32 byte accounts never have more than 50 characters
+        match self.len() {
+            STRKEY_ACCOUNT_LENGTH | STRKEY_MUXED_ACCOUNT_LENGTH =>
+                Ok(MuxedAccount::from_encoding(self)?.to_substrate_account()),
+            _ => decode_ss58(self).map(|(account_id, _)| account_id),
+        }
+    }
+}
+
+impl IntoAccountId32 for &str {
+    fn into_account_id32(self) -> Result<AccountId32, StellarSdkError> {
+        self.as_bytes().into_account_id32()
+    }
+}
+
+impl IntoAccountId32 for Vec<u8> {
+    fn into_account_id32(self) -> Result<AccountId32, StellarSdkError> {
+        self.as_slice().into_account_id32()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE_PUBLIC: &str = "GDKDLE6HCX65GHDBCQNL2BFJT7LIELEFLCCUZTPDTJLIJZ5FNWRH2BBY";
+    const ALICE_POLKADOT: &str = "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5";
+    const ALICE_KUSAMA: &str = "HNZata7iMYWmk5RvZRTiAsSDhV8366zq2YGb3tLH5Upf74F";
+    const ALICE_SUBSTRATE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    #[test]
+    fn public_key_to_ss58() {
+        let public_key = PublicKey::from_encoding(ALICE_PUBLIC).unwrap();
+
+        assert_eq!(public_key.to_ss58_address(0).unwrap(), ALICE_POLKADOT.as_bytes());
+        assert_eq!(public_key.to_ss58_address(2).unwrap(), ALICE_KUSAMA.as_bytes());
+        assert_eq!(public_key.to_ss58_address(42).unwrap(), ALICE_SUBSTRATE.as_bytes());
+
+        assert_eq!(PublicKey::from_ss58_address(ALICE_SUBSTRATE, 42), Ok(public_key.clone()));
+        assert_eq!(
+            PublicKey::from_ss58_address(ALICE_SUBSTRATE, 0),
+            Err(StellarSdkError::UnexpectedSs58Prefix { expected: 0, found: 42 })
+        );
+
+        for prefix in [63, 64, 1284, 16_383] {
+            let address = public_key.to_ss58_address(prefix).unwrap();
+            assert_eq!(PublicKey::from_ss58_address(address, prefix), Ok(public_key.clone()));
+        }
+
+        assert_eq!(public_key.to_ss58_address(46), Err(StellarSdkError::InvalidSs58Encoding));
+        assert_eq!(public_key.to_ss58_address(16_384), Err(StellarSdkError::InvalidSs58Encoding));
+    }
+
+    #[test]
+    fn invalid_ss58_addresses() {
+        assert_eq!(
+            decode_ss58("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ"),
+            Err(StellarSdkError::InvalidSs58Checksum)
+        );
+        assert_eq!(
+            decode_ss58("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKut"),
+            Err(StellarSdkError::InvalidSs58Encoding)
+        );
+        assert_eq!(
+            decode_ss58("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQ0"),
+            Err(StellarSdkError::InvalidBase58Character { at_position: 47 })
+        );
+    }
+
+    #[test]
+    fn map_muxed_accounts() {
+        let public_key = PublicKey::from_encoding(ALICE_PUBLIC).unwrap();
+        let account_id = public_key.clone().into_account_id32().unwrap();
+        assert_eq!(PublicKey::from(account_id.clone()), public_key);
+
+        assert_eq!(ALICE_PUBLIC.into_account_id32(), Ok(account_id.clone()));
+        assert_eq!(ALICE_SUBSTRATE.into_account_id32(), Ok(account_id.clone()));
+        assert_eq!(MuxedAccount::from_account_id(ALICE_PUBLIC).unwrap().to_substrate_account(), account_id);
+
+        let muxed_1 = MuxedAccount::from_muxed_account_id(ALICE_PUBLIC, 1).unwrap();
+        let muxed_2 = MuxedAccount::from_muxed_account_id(ALICE_PUBLIC, 2).unwrap();
+        let sub_account_1 = muxed_1.to_substrate_account();
+
+        assert_ne!(sub_account_1, account_id);
+        assert_ne!(sub_account_1, muxed_2.to_substrate_account());
+        assert_eq!(muxed_1.to_encoding().into_account_id32(), Ok(sub_account_1));
+    }
+}
@@ -0,0 +1,7 @@
+//! Types that let Substrate runtimes work with Stellar keys
+
+pub mod account;
+pub mod signature;
+
+pub use account::{decode_ss58, encode_ss58, IntoAccountId32};
+pub use signature::{StellarMessageSignature, StellarSignature, StellarSigner};
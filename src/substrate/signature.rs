@@ -0,0 +1,205 @@
+//! Stellar ed25519 signatures as signatures of Substrate extrinsics
+
+use codec::{Decode, Encode, MaxEncodedLen};
+use core::convert::TryFrom;
+use scale_info::TypeInfo;
+use sodalite::SIGN_LEN;
+use sp_core::{ed25519, RuntimeDebug};
+use sp_runtime::{
+    traits::{IdentifyAccount, Lazy, Verify},
+    AccountId32,
+};
+
+use crate::{secret_key::Signature, PublicKey, SecretKey, StellarSdkError};
+
+/// A Stellar public key in its role as the signer of an extrinsic
+///
+/// It identifies the `AccountId32` that has the same 32 bytes as the public key.
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
+pub struct StellarSigner(pub [u8; 32]);
+
+/// An ed25519 signature created by a Stellar `SecretKey`
+///
+/// This type implements `Verify`, so that a runtime can use it as the signature
+/// type of its extrinsics. The signature is created directly for the encoded
+/// payload of the extrinsic.
+/// ```
+/// use sp_runtime::traits::{IdentifyAccount, Verify};
+/// use substrate_stellar_sdk::{
+///     substrate::{StellarSignature, StellarSigner},
+///     SecretKey,
+/// };
+///
+/// let secret_key = SecretKey::from_encoding("SCDSVACTNFNSD5LQZ5LWUWEY3UIAML2J7ALPFCD6ZX4D3TVJV7X243N3").unwrap();
+/// let account_id = StellarSigner::from(secret_key.get_public().clone()).into_account();
+///
+/// let signature = StellarSignature::sign(&secret_key, b"payload");
+/// assert!(signature.verify(&b"payload"[..], &account_id));
+/// ```
+#[derive(Clone, Eq, PartialEq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
+pub struct StellarSignature(pub Signature);
+
+/// An ed25519 signature of a SEP-53 signed message
+///
+/// Like `StellarSignature` but the signature is created for the
+/// [SEP-53](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0053.md)
+/// payload of the extrinsic payload (see `SecretKey::sign_message`). This allows wallets
+/// that only support SEP-53 message signing to sign extrinsics.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, TypeInfo, MaxEncodedLen, RuntimeDebug)]
+pub struct StellarMessageSignature(pub Signature);
+
+fn public_key_of_account(account_id: &AccountId32) -> PublicKey {
+    let binary: &[u8; 32] = account_id.as_ref();
+    PublicKey::from_binary(*binary)
+}
+
+impl StellarSignature {
+    /// Sign `message` with the `secret_key`
+    pub fn sign<T: AsRef<[u8]>>(secret_key: &SecretKey, message: T) -> Self {
+        StellarSignature(secret_key.create_signature(message))
+    }
+}
+
+impl StellarMessageSignature {
+    /// Sign the SEP-53 payload of `message` with the `secret_key`
+    pub fn sign<T: AsRef<[u8]>>(secret_key: &SecretKey, message: T) -> Self {
+        StellarMessageSignature(secret_key.sign_message(message))
+    }
+}
+
+impl Verify for StellarSignature {
+    type Signer = StellarSigner;
+
+    fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &AccountId32) -> bool {
+        public_key_of_account(signer).verify_signature(msg.get(), &self.0)
+    }
+}
+
+impl Verify for StellarMessageSignature {
+    type Signer = StellarSigner;
+
+    fn verify<L: Lazy<[u8]>>(&self, mut msg: L, signer: &AccountId32) -> bool {
+        public_key_of_account(signer).verify_message(msg.get(), &self.0)
+    }
+}
+
+impl IdentifyAccount for StellarSigner {
+    type AccountId = AccountId32;
+
+    fn into_account(self) -> AccountId32 {
+        AccountId32::new(self.0)
+    }
+}
+
+impl From<PublicKey> for StellarSigner {
+    fn from(public_key: PublicKey) -> Self {
+        StellarSigner(public_key.into_binary())
+    }
+}
+
+impl From<StellarSigner> for PublicKey {
+    fn from(signer: StellarSigner) -> Self {
+        PublicKey::from_binary(signer.0)
+    }
+}
+
+impl From<ed25519::Public> for StellarSigner {
+    fn from(public: ed25519::Public) -> Self {
+        StellarSigner(public.0)
+    }
+}
+
+impl From<PublicKey> for AccountId32 {
+    fn from(public_key: PublicKey) -> Self {
+        AccountId32::new(public_key.into_binary())
+    }
+}
+
+impl From<PublicKey> for ed25519::Public {
+    fn from(public_key: PublicKey) -> Self {
+        ed25519::Public::from_raw(public_key.into_binary())
+    }
+}
+
+impl From<ed25519::Public> for PublicKey {
+    fn from(public: ed25519::Public) -> Self {
+        PublicKey::from_binary(public.0)
+    }
+}
+
+impl From<Signature> for StellarSignature {
+    fn from(signature: Signature) -> Self {
+        StellarSignature(signature)
+    }
+}
+
+impl From<ed25519::Signature> for StellarSignature {
+    fn from(signature: ed25519::Signature) -> Self {
+        StellarSignature(signature.0)
+    }
+}
+
+impl From<StellarSignature> for ed25519::Signature {
+    fn from(signature: StellarSignature) -> Self {
+        ed25519::Signature::from_raw(signature.0)
+    }
+}
+
+impl TryFrom<&[u8]> for StellarSignature {
+    type Error = StellarSdkError;
+
+    fn try_from(signature: &[u8]) -> Result<Self, Self::Error> {
+        Signature::try_from(signature)
+            .map(StellarSignature)
+            .map_err(|_| StellarSdkError::InvalidSignatureLength {
+                found_length: signature.len(),
+                expected_length: SIGN_LEN,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_core::ed25519;
+    use sp_runtime::traits::{IdentifyAccount, Verify};
+
+    use super::*;
+
+    const SECRET: &str = "SCDSVACTNFNSD5LQZ5LWUWEY3UIAML2J7ALPFCD6ZX4D3TVJV7X243N3";
+    const OTHER_SECRET: &str = "SCVKZEONBSU3XD6OTHXGAP6BTEWHOU4RPZQZJJ5AVAGPXUZ5A4D7MU6S";
+
+    #[test]
+    fn verify_extrinsic_signatures() {
+        let secret_key = SecretKey::from_encoding(SECRET).unwrap();
+        let other_secret_key = SecretKey::from_encoding(OTHER_SECRET).unwrap();
+
+        let account_id = StellarSigner::from(secret_key.get_public().clone()).into_account();
+        assert_eq!(account_id, AccountId32::from(secret_key.get_public().clone()));
+
+        let signature = StellarSignature::sign(&secret_key, b"extrinsic payload");
+        assert!(signature.verify(&b"extrinsic payload"[..], &account_id));
+        assert!(!signature.verify(&b"another payload"[..], &account_id));
+        assert!(!StellarSignature::sign(&other_secret_key, b"extrinsic payload")
+            .verify(&b"extrinsic payload"[..], &account_id));
+
+        let message_signature = StellarMessageSignature::sign(&secret_key, b"extrinsic payload");
+        assert!(message_signature.verify(&b"extrinsic payload"[..], &account_id));
+        assert!(!StellarMessageSignature(signature.0).verify(&b"extrinsic payload"[..], &account_id));
+    }
+
+    #[test]
+    fn convert_ed25519_types() {
+        let secret_key = SecretKey::from_encoding(SECRET).unwrap();
+        let public_key = secret_key.get_public().clone();
+
+        let public: ed25519::Public = public_key.clone().into();
+        assert_eq!(PublicKey::from(public), public_key);
+        assert_eq!(PublicKey::from(StellarSigner::from(public)), public_key);
+
+        let signature = StellarSignature::sign(&secret_key, b"payload");
+        let ed25519_signature: ed25519::Signature = signature.clone().into();
+        assert_eq!(StellarSignature::from(ed25519_signature), signature);
+        assert_eq!(StellarSignature::try_from(&signature.0[..]), Ok(signature));
+        assert!(StellarSignature::try_from(&[0u8; 63][..]).is_err());
+    }
+}
@@ -0,0 +1,1076 @@
+//! Txrep: a human readable representation of transaction envelopes
+//!
+//! Txrep represents a transaction envelope as a list of lines `key: value`, where the key is
+//! the path of a field in the XDR structure, see
+//! [SEP-11](https://github.com/stellar/stellar-protocol/blob/master/ecosystem/sep-0011.md).
+//! Accounts, signer keys and assets use their usual string encodings, binary data is
+//! hex encoded and strings are enclosed in double quotes.
+
+use core::str::{self, FromStr};
+use sp_std::{boxed::Box, collections::btree_map::BTreeMap, vec::Vec};
+
+use crate::{
+    compound_types::{LimitedString, LimitedVarArray, LimitedVarOpaque},
+    lib::{String, ToString},
+    types::{
+        AllowTrustOp, AlphaNum12, AlphaNum4, BeginSponsoringFutureReservesOp, BumpSequenceOp, ChangeTrustAsset,
+        ChangeTrustOp, ClaimClaimableBalanceOp, ClaimantV0, ClawbackClaimableBalanceOp, ClawbackOp, ConfigSettingId,
+        ContractDataDurability, ContractExecutable, ContractIdPreimage, ContractIdPreimageFromAddress, CreateAccountOp,
+        CreateClaimableBalanceOp, CreateContractArgs, CreateContractArgsV2, CreatePassiveSellOfferOp,
+        DecoratedSignature, ExtendFootprintTtlOp, ExtensionPoint, FeeBumpTransactionEnvelope, FeeBumpTransactionExt,
+        FeeBumpTransactionInnerTx, HostFunction, Int128Parts, Int256Parts, InvokeContractArgs, InvokeHostFunctionOp,
+        LedgerBounds, LedgerFootprint, LedgerKeyAccount, LedgerKeyClaimableBalance, LedgerKeyConfigSetting,
+        LedgerKeyContractCode, LedgerKeyContractData, LedgerKeyData, LedgerKeyLiquidityPool, LedgerKeyOffer,
+        LedgerKeyTrustLine, LedgerKeyTtl, LiquidityPoolConstantProductParameters, LiquidityPoolDepositOp,
+        LiquidityPoolParameters, LiquidityPoolWithdrawOp, ManageBuyOfferOp, ManageDataOp, ManageSellOfferOp,
+        MuxedEd25519Account, OperationBody, PathPaymentStrictReceiveOp, PathPaymentStrictSendOp, PaymentOp,
+        Preconditions, PreconditionsV2, RestoreFootprintOp, RevokeSponsorshipOp, RevokeSponsorshipOpSigner, ScAddress,
+        ScContractInstance, ScError, ScErrorCode, ScMapEntry, ScNonceKey, ScVal, SetOptionsOp, SetTrustLineFlagsOp,
+        SorobanAddressCredentials, SorobanAuthorizationEntry, SorobanAuthorizedFunction, SorobanAuthorizedInvocation,
+        SorobanCredentials, SorobanResources, SorobanResourcesExtV0, SorobanTransactionData, SorobanTransactionDataExt,
+        TransactionExt, TransactionV0, TransactionV0Envelope, TransactionV0Ext, TransactionV1Envelope, TrustLineAsset,
+        UInt128Parts, UInt256Parts,
+    },
+    Asset, AssetCode, ClaimPredicate, ClaimableBalanceId, Claimant, FeeBumpTransaction, LedgerKey, Memo, MuxedAccount,
+    Operation, Price, PublicKey, Signer, SignerKey, StellarSdkError, TimeBounds, Transaction, TransactionEnvelope,
+};
+
+const NATIVE_ASSET: &str = "XLM";
+
+struct TxrepWriter {
+    output: Vec<u8>,
+}
+
+impl TxrepWriter {
+    fn write<T: AsRef<[u8]>>(&mut self, key: &str, value: T) {
+        self.output.extend_from_slice(key.as_bytes());
+        self.output.extend_from_slice(b": ");
+        self.output.extend_from_slice(value.as_ref());
+        self.output.push(b'\n');
+    }
+}
+
+struct TxrepReader<'a> {
+    values: BTreeMap<&'a str, &'a str>,
+}
+
+impl<'a> TxrepReader<'a> {
+    fn new(txrep: &'a [u8]) -> Result<Self, StellarSdkError> {
+        let mut values = BTreeMap::new();
+        for (index, line) in txrep.split(|char| *char == b'\n').enumerate() {
+            let invalid_line = StellarSdkError::InvalidTxrepLine { line: index + 1 };
+            let line = str::from_utf8(line).map_err(|_| invalid_line.clone())?.trim();
+            if line.is_empty() {
+                continue
+            }
+
+            let separator = line.find(':').ok_or_else(|| invalid_line.clone())?;
+            let key = line[..separator].trim();
+            if key.is_empty() || values.insert(key, line[separator + 1..].trim()).is_some() {
+                return Err(invalid_line)
+            }
+        }
+        Ok(TxrepReader { values })
+    }
+
+    /// Return the value of `key` without a trailing comment
+    fn value(&self, key: &str) -> Result<&'a str, StellarSdkError> {
+        match self.values.get(key) {
+            Some(value) => Ok(value.split_whitespace().next().unwrap_or("")),
+            None => Err(StellarSdkError::MissingTxrepKey { key: key.as_bytes().to_vec() }),
+        }
+    }
+
+    fn parse<T: FromStr>(&self, key: &str) -> Result<T, StellarSdkError> {
+        self.value(key)?.parse().map_err(|_| invalid_value(key))
+    }
+
+    fn hex(&self, key: &str) -> Result<Vec<u8>, StellarSdkError> {
+        match self.value(key)? {
+            "0" => Ok(Vec::new()),
+            value => hex::decode(value).map_err(|_| invalid_value(key)),
+        }
+    }
+
+    fn string(&self, key: &str) -> Result<Vec<u8>, StellarSdkError> {
+        let value = self
+            .values
+            .get(key)
+            .ok_or_else(|| StellarSdkError::MissingTxrepKey { key: key.as_bytes().to_vec() })?;
+        unescape_string(value.as_bytes()).ok_or_else(|| invalid_value(key))
+    }
+}
+
+fn invalid_value(key: &str) -> StellarSdkError {
+    StellarSdkError::InvalidTxrepValue { key: key.as_bytes().to_vec() }
+}
+
+fn join(key: &str, name: &str) -> String {
+    match (key.is_empty(), name.is_empty()) {
+        (true, _) => name.to_string(),
+        (_, true) => key.to_string(),
+        _ => {
+            let mut joined = key.to_string();
+            joined.push('.');
+            joined.push_str(name);
+            joined
+        },
+    }
+}
+
+fn index(key: &str, index: usize) -> String {
+    let mut indexed = key.to_string();
+    indexed.push('[');
+    indexed.push_str(&index.to_string());
+    indexed.push(']');
+    indexed
+}
+
+fn escape_string(string: &[u8]) -> Vec<u8> {
+    let mut escaped = Vec::with_capacity(string.len() + 2);
+    escaped.push(b'"');
+    for char in string {
+        match char {
+            b'"' | b'\\' => escaped.extend_from_slice(&[b'\\', *char]),
+            0x20..=0x7e => escaped.push(*char),
+            _ => {
+                escaped.extend_from_slice(b"\\x");
+                escaped.extend_from_slice(hex::encode([*char]).as_bytes());
+            },
+        }
+    }
+    escaped.push(b'"');
+    escaped
+}
+
+fn unescape_string(value: &[u8]) -> Option<Vec<u8>> {
+    let mut chars = value.strip_prefix(b"\"")?.iter();
+    let mut string = Vec::new();
+    loop {
+        match chars.next()? {
+            b'"' => return Some(string),
+            b'\\' => match chars.next()? {
+                b'n' => string.push(b'\n'),
+                b'r' => string.push(b'\r'),
+                b't' => string.push(b'\t'),
+                b'x' => {
+                    let digits = [*chars.next()?, *chars.next()?];
+                    string.push(hex::decode(digits).ok()?[0]);
+                },
+                char => string.push(*char),
+            },
+            char => string.push(*char),
+        }
+    }
+}
+
+/// A type that has a Txrep representation
+///
+/// A value writes its lines for the key `key`. Compound values use `key` as the prefix of the
+/// keys of their fields.
+trait Txrep: Sized {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter);
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError>;
+}
+
+macro_rules! txrep_number {
+    ($($type:ty),*) => {
+        $(impl Txrep for $type {
+            fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+                writer.write(key, self.to_string());
+            }
+
+            fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+                reader.parse(key)
+            }
+        })*
+    };
+}
+
+txrep_number!(bool, i32, u32, i64, u64);
+
+/// Implement `Txrep` for a struct, given the Txrep names of its fields
+macro_rules! txrep_struct {
+    ($type:ident { $($field:ident: $name:literal),* $(,)? }) => {
+        impl Txrep for $type {
+            fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+                $(self.$field.write_txrep(&join(key, $name), writer);)*
+            }
+
+            fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+                Ok($type { $($field: Txrep::read_txrep(&join(key, $name), reader)?,)* })
+            }
+        }
+    };
+}
+
+/// Implement `Txrep` for an enum, given the XDR names of its variants
+macro_rules! txrep_enum {
+    ($type:ident { $($variant:ident = $name:literal),* $(,)? }) => {
+        impl Txrep for $type {
+            fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+                match self {
+                    $($type::$variant => writer.write(key, $name),)*
+                }
+            }
+
+            fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+                match reader.value(key)? {
+                    $($name => Ok($type::$variant),)*
+                    _ => Err(invalid_value(key)),
+                }
+            }
+        }
+    };
+}
+
+/// Implement `Txrep` for a union
+///
+/// The discriminant is written with the key `$discriminant`. The first list contains the
+/// variants without a value, the second list the variants with a value and the Txrep name of
+/// the value.
+macro_rules! txrep_union {
+    ($type:ident, $discriminant:literal,
+        { $($void:ident = $void_name:literal),* $(,)? },
+        { $($variant:ident = $name:literal => $arm:literal),* $(,)? }
+    ) => {
+        impl Txrep for $type {
+            #[allow(unreachable_patterns)]
+            fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+                let discriminant_key = join(key, $discriminant);
+                match self {
+                    $($type::$void => writer.write(&discriminant_key, $void_name),)*
+                    $($type::$variant(value) => {
+                        writer.write(&discriminant_key, $name);
+                        value.write_txrep(&join(key, $arm), writer);
+                    },)*
+                    _ => unreachable!(),
+                }
+            }
+
+            fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+                let discriminant_key = join(key, $discriminant);
+                match reader.value(&discriminant_key)? {
+                    $($void_name => Ok($type::$void),)*
+                    $($name => Ok($type::$variant(Txrep::read_txrep(&join(key, $arm), reader)?)),)*
+                    _ => Err(invalid_value(&discriminant_key)),
+                }
+            }
+        }
+    };
+}
+
+impl<const N: usize> Txrep for [u8; N] {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        writer.write(key, hex::encode(self));
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        let mut value = [0; N];
+        hex::decode_to_slice(reader.value(key)?, &mut value).map_err(|_| invalid_value(key))?;
+        Ok(value)
+    }
+}
+
+impl<const N: i32> Txrep for LimitedVarOpaque<N> {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        match self.get_vec().is_empty() {
+            true => writer.write(key, "0"),
+            false => writer.write(key, hex::encode(self.get_vec())),
+        }
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        LimitedVarOpaque::new(reader.hex(key)?).map_err(|_| invalid_value(key))
+    }
+}
+
+impl<const N: i32> Txrep for LimitedString<N> {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        writer.write(key, escape_string(self.get_vec()));
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        LimitedString::new(reader.string(key)?).map_err(|_| invalid_value(key))
+    }
+}
+
+impl<T: Txrep, const N: i32> Txrep for LimitedVarArray<T, N> {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        writer.write(&join(key, "len"), self.len().to_string());
+        for (element_index, element) in self.get_vec().iter().enumerate() {
+            element.write_txrep(&index(key, element_index), writer);
+        }
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        let length_key = join(key, "len");
+        let length: usize = reader.parse(&length_key)?;
+        if length > N as usize {
+            return Err(invalid_value(&length_key))
+        }
+
+        let elements = (0..length)
+            .map(|element_index| T::read_txrep(&index(key, element_index), reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        LimitedVarArray::new(elements).map_err(|_| invalid_value(&length_key))
+    }
+}
+
+impl<T: Txrep> Txrep for Option<T> {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        let present_key = join(key, "_present");
+        match self {
+            Some(value) => {
+                writer.write(&present_key, "true");
+                value.write_txrep(key, writer);
+            },
+            None => writer.write(&present_key, "false"),
+        }
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        match reader.parse(&join(key, "_present"))? {
+            true => Ok(Some(T::read_txrep(key, reader)?)),
+            false => Ok(None),
+        }
+    }
+}
+
+impl<T: Txrep> Txrep for Box<T> {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        self.as_ref().write_txrep(key, writer);
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        T::read_txrep(key, reader).map(Box::new)
+    }
+}
+
+impl Txrep for PublicKey {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        writer.write(key, self.to_encoding());
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        PublicKey::from_encoding(reader.value(key)?).map_err(|_| invalid_value(key))
+    }
+}
+
+impl Txrep for MuxedAccount {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        writer.write(key, self.to_encoding());
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        MuxedAccount::from_encoding(reader.value(key)?).map_err(|_| invalid_value(key))
+    }
+}
+
+impl Txrep for SignerKey {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        writer.write(key, self.to_encoding());
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        SignerKey::from_encoding(reader.value(key)?).map_err(|_| invalid_value(key))
+    }
+}
+
+fn trim_asset_code(asset_code: &[u8]) -> &[u8] {
+    let length = asset_code.iter().position(|char| *char == 0).unwrap_or(asset_code.len());
+    &asset_code[..length]
+}
+
+fn encode_credit_asset(asset_code: &[u8], issuer: &PublicKey) -> Vec<u8> {
+    let mut encoded = trim_asset_code(asset_code).to_vec();
+    encoded.push(b':');
+    encoded.extend(issuer.to_encoding());
+    encoded
+}
+
+/// Decode an asset in the form `XLM` or `CODE:ISSUER`
+fn decode_asset(key: &str, reader: &TxrepReader) -> Result<Asset, StellarSdkError> {
+    let value = reader.value(key)?;
+    if value == NATIVE_ASSET {
+        return Ok(Asset::AssetTypeNative)
+    }
+
+    let (asset_code, issuer) = value.split_once(':').ok_or_else(|| invalid_value(key))?;
+    if asset_code.is_empty() {
+        return Err(invalid_value(key))
+    }
+    Asset::from_asset_code(asset_code, issuer).map_err(|_| invalid_value(key))
+}
+
+impl Txrep for Asset {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        match self {
+            Asset::AssetTypeNative => writer.write(key, NATIVE_ASSET),
+            Asset::AssetTypeCreditAlphanum4(AlphaNum4 { asset_code, issuer }) =>
+                writer.write(key, encode_credit_asset(asset_code, issuer)),
+            Asset::AssetTypeCreditAlphanum12(AlphaNum12 { asset_code, issuer }) =>
+                writer.write(key, encode_credit_asset(asset_code, issuer)),
+            Asset::Default(_) => unreachable!(),
+        }
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        decode_asset(key, reader)
+    }
+}
+
+impl Txrep for AssetCode {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        match self {
+            AssetCode::AssetTypeCreditAlphanum4(asset_code) => writer.write(key, trim_asset_code(asset_code)),
+            AssetCode::AssetTypeCreditAlphanum12(asset_code) => writer.write(key, trim_asset_code(asset_code)),
+            AssetCode::Default(_) => unreachable!(),
+        }
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        match reader.value(key)? {
+            "" => Err(invalid_value(key)),
+            value => AssetCode::new(value).map_err(|_| invalid_value(key)),
+        }
+    }
+}
+
+// Trust line assets use the asset encoding unless they are pool shares. Pool shares are
+// written as a union with the discriminant `type`.
+
+impl Txrep for ChangeTrustAsset {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        match self {
+            ChangeTrustAsset::AssetTypeNative => writer.write(key, NATIVE_ASSET),
+            ChangeTrustAsset::AssetTypeCreditAlphanum4(AlphaNum4 { asset_code, issuer }) =>
+                writer.write(key, encode_credit_asset(asset_code, issuer)),
+            ChangeTrustAsset::AssetTypeCreditAlphanum12(AlphaNum12 { asset_code, issuer }) =>
+                writer.write(key, encode_credit_asset(asset_code, issuer)),
+            ChangeTrustAsset::AssetTypePoolShare(parameters) => {
+                writer.write(&join(key, "type"), "ASSET_TYPE_POOL_SHARE");
+                parameters.write_txrep(&join(key, "liquidityPool"), writer);
+            },
+        }
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        let type_key = join(key, "type");
+        if reader.values.contains_key(type_key.as_str()) {
+            return match reader.value(&type_key)? {
+                "ASSET_TYPE_POOL_SHARE" =>
+                    Ok(ChangeTrustAsset::AssetTypePoolShare(Txrep::read_txrep(&join(key, "liquidityPool"), reader)?)),
+                _ => Err(invalid_value(&type_key)),
+            }
+        }
+
+        Ok(match decode_asset(key, reader)? {
+            Asset::AssetTypeNative => ChangeTrustAsset::AssetTypeNative,
+            Asset::AssetTypeCreditAlphanum4(asset) => ChangeTrustAsset::AssetTypeCreditAlphanum4(asset),
+            Asset::AssetTypeCreditAlphanum12(asset) => ChangeTrustAsset::AssetTypeCreditAlphanum12(asset),
+            Asset::Default(_) => unreachable!(),
+        })
+    }
+}
+
+impl Txrep for TrustLineAsset {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        match self {
+            TrustLineAsset::AssetTypeNative => writer.write(key, NATIVE_ASSET),
+            TrustLineAsset::AssetTypeCreditAlphanum4(AlphaNum4 { asset_code, issuer }) =>
+                writer.write(key, encode_credit_asset(asset_code, issuer)),
+            TrustLineAsset::AssetTypeCreditAlphanum12(AlphaNum12 { asset_code, issuer }) =>
+                writer.write(key, encode_credit_asset(asset_code, issuer)),
+            TrustLineAsset::AssetTypePoolShare(pool_id) => {
+                writer.write(&join(key, "type"), "ASSET_TYPE_POOL_SHARE");
+                pool_id.write_txrep(&join(key, "liquidityPoolID"), writer);
+            },
+        }
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        let type_key = join(key, "type");
+        if reader.values.contains_key(type_key.as_str()) {
+            return match reader.value(&type_key)? {
+                "ASSET_TYPE_POOL_SHARE" =>
+                    Ok(TrustLineAsset::AssetTypePoolShare(Txrep::read_txrep(&join(key, "liquidityPoolID"), reader)?)),
+                _ => Err(invalid_value(&type_key)),
+            }
+        }
+
+        Ok(match decode_asset(key, reader)? {
+            Asset::AssetTypeNative => TrustLineAsset::AssetTypeNative,
+            Asset::AssetTypeCreditAlphanum4(asset) => TrustLineAsset::AssetTypeCreditAlphanum4(asset),
+            Asset::AssetTypeCreditAlphanum12(asset) => TrustLineAsset::AssetTypeCreditAlphanum12(asset),
+            Asset::Default(_) => unreachable!(),
+        })
+    }
+}
+
+// The V0 transaction contains the raw ed25519 key of the source account, it is written as
+// account id
+impl Txrep for TransactionV0 {
+    fn write_txrep(&self, key: &str, writer: &mut TxrepWriter) {
+        PublicKey::PublicKeyTypeEd25519(self.source_account_ed25519)
+            .write_txrep(&join(key, "sourceAccountEd25519"), writer);
+        self.fee.write_txrep(&join(key, "fee"), writer);
+        self.seq_num.write_txrep(&join(key, "seqNum"), writer);
+        self.time_bounds.write_txrep(&join(key, "timeBounds"), writer);
+        self.memo.write_txrep(&join(key, "memo"), writer);
+        self.operations.write_txrep(&join(key, "operations"), writer);
+        self.ext.write_txrep(&join(key, "ext"), writer);
+    }
+
+    fn read_txrep(key: &str, reader: &TxrepReader) -> Result<Self, StellarSdkError> {
+        let PublicKey::PublicKeyTypeEd25519(source_account_ed25519) =
+            Txrep::read_txrep(&join(key, "sourceAccountEd25519"), reader)?;
+        Ok(TransactionV0 {
+            source_account_ed25519,
+            fee: Txrep::read_txrep(&join(key, "fee"), reader)?,
+            seq_num: Txrep::read_txrep(&join(key, "seqNum"), reader)?,
+            time_bounds: Txrep::read_txrep(&join(key, "timeBounds"), reader)?,
+            memo: Txrep::read_txrep(&join(key, "memo"), reader)?,
+            operations: Txrep::read_txrep(&join(key, "operations"), reader)?,
+            ext: Txrep::read_txrep(&join(key, "ext"), reader)?,
+        })
+    }
+}
+
+// Envelopes
+
+txrep_union!(TransactionEnvelope, "type", {}, {
+    EnvelopeTypeTxV0 = "ENVELOPE_TYPE_TX_V0" => "",
+    EnvelopeTypeTx = "ENVELOPE_TYPE_TX" => "",
+    EnvelopeTypeTxFeeBump = "ENVELOPE_TYPE_TX_FEE_BUMP" => "feeBump",
+});
+txrep_struct!(TransactionV0Envelope { tx: "tx", signatures: "signatures" });
+txrep_struct!(TransactionV1Envelope { tx: "tx", signatures: "signatures" });
+txrep_struct!(FeeBumpTransactionEnvelope { tx: "tx", signatures: "signatures" });
+txrep_struct!(DecoratedSignature { hint: "hint", signature: "signature" });
+txrep_struct!(FeeBumpTransaction { fee_source: "feeSource", fee: "fee", inner_tx: "innerTx", ext: "ext" });
+txrep_union!(FeeBumpTransactionInnerTx, "type", {}, { EnvelopeTypeTx = "ENVELOPE_TYPE_TX" => "tx" });
+txrep_union!(FeeBumpTransactionExt, "v", { V0 = "0" }, {});
+txrep_union!(TransactionV0Ext, "v", { V0 = "0" }, {});
+
+// Transactions
+
+txrep_struct!(Transaction {
+    source_account: "sourceAccount",
+    fee: "fee",
+    seq_num: "seqNum",
+    cond: "cond",
+    memo: "memo",
+    operations: "operations",
+    ext: "ext",
+});
+txrep_union!(TransactionExt, "v", { V0 = "0" }, { V1 = "1" => "sorobanData" });
+txrep_union!(Preconditions, "type", { PrecondNone = "PRECOND_NONE" }, {
+    PrecondTime = "PRECOND_TIME" => "timeBounds",
+    PrecondV2 = "PRECOND_V2" => "v2",
+});
+txrep_struct!(PreconditionsV2 {
+    time_bounds: "timeBounds",
+    ledger_bounds: "ledgerBounds",
+    min_seq_num: "minSeqNum",
+    min_seq_age: "minSeqAge",
+    min_seq_ledger_gap: "minSeqLedgerGap",
+    extra_signers: "extraSigners",
+});
+txrep_struct!(TimeBounds { min_time: "minTime", max_time: "maxTime" });
+txrep_struct!(LedgerBounds { min_ledger: "minLedger", max_ledger: "maxLedger" });
+txrep_union!(Memo, "type", { MemoNone = "MEMO_NONE" }, {
+    MemoText = "MEMO_TEXT" => "text",
+    MemoId = "MEMO_ID" => "id",
+    MemoHash = "MEMO_HASH" => "hash",
+    MemoReturn = "MEMO_RETURN" => "retHash",
+});
+
+// Operations
+
+txrep_struct!(Operation { source_account: "sourceAccount", body: "body" });
+txrep_union!(OperationBody, "type", {
+    Inflation = "INFLATION",
+    EndSponsoringFutureReserves = "END_SPONSORING_FUTURE_RESERVES",
+}, {
+    CreateAccount = "CREATE_ACCOUNT" => "createAccountOp",
+    Payment = "PAYMENT" => "paymentOp",
+    PathPaymentStrictReceive = "PATH_PAYMENT_STRICT_RECEIVE" => "pathPaymentStrictReceiveOp",
+    ManageSellOffer = "MANAGE_SELL_OFFER" => "manageSellOfferOp",
+    CreatePassiveSellOffer = "CREATE_PASSIVE_SELL_OFFER" => "createPassiveSellOfferOp",
+    SetOptions = "SET_OPTIONS" => "setOptionsOp",
+    ChangeTrust = "CHANGE_TRUST" => "changeTrustOp",
+    AllowTrust = "ALLOW_TRUST" => "allowTrustOp",
+    AccountMerge = "ACCOUNT_MERGE" => "destination",
+    ManageData = "MANAGE_DATA" => "manageDataOp",
+    BumpSequence = "BUMP_SEQUENCE" => "bumpSequenceOp",
+    ManageBuyOffer = "MANAGE_BUY_OFFER" => "manageBuyOfferOp",
+    PathPaymentStrictSend = "PATH_PAYMENT_STRICT_SEND" => "pathPaymentStrictSendOp",
+    CreateClaimableBalance = "CREATE_CLAIMABLE_BALANCE" => "createClaimableBalanceOp",
+    ClaimClaimableBalance = "CLAIM_CLAIMABLE_BALANCE" => "claimClaimableBalanceOp",
+    BeginSponsoringFutureReserves = "BEGIN_SPONSORING_FUTURE_RESERVES" => "beginSponsoringFutureReservesOp",
+    RevokeSponsorship = "REVOKE_SPONSORSHIP" => "revokeSponsorshipOp",
+    Clawback = "CLAWBACK" => "clawbackOp",
+    ClawbackClaimableBalance = "CLAWBACK_CLAIMABLE_BALANCE" => "clawbackClaimableBalanceOp",
+    SetTrustLineFlags = "SET_TRUST_LINE_FLAGS" => "setTrustLineFlagsOp",
+    LiquidityPoolDeposit = "LIQUIDITY_POOL_DEPOSIT" => "liquidityPoolDepositOp",
+    LiquidityPoolWithdraw = "LIQUIDITY_POOL_WITHDRAW" => "liquidityPoolWithdrawOp",
+    InvokeHostFunction = "INVOKE_HOST_FUNCTION" => "invokeHostFunctionOp",
+    ExtendFootprintTtl = "EXTEND_FOOTPRINT_TTL" => "extendFootprintTTLOp",
+    RestoreFootprint = "RESTORE_FOOTPRINT" => "restoreFootprintOp",
+});
+txrep_struct!(CreateAccountOp { destination: "destination", starting_balance: "startingBalance" });
+txrep_struct!(PaymentOp { destination: "destination", asset: "asset", amount: "amount" });
+txrep_struct!(PathPaymentStrictReceiveOp {
+    send_asset: "sendAsset",
+    send_max: "sendMax",
+    destination: "destination",
+    dest_asset: "destAsset",
+    dest_amount: "destAmount",
+    path: "path",
+});
+txrep_struct!(PathPaymentStrictSendOp {
+    send_asset: "sendAsset",
+    send_amount: "sendAmount",
+    destination: "destination",
+    dest_asset: "destAsset",
+    dest_min: "destMin",
+    path: "path",
+});
+txrep_struct!(ManageSellOfferOp {
+    selling: "selling",
+    buying: "buying",
+    amount: "amount",
+    price: "price",
+    offer_id: "offerID",
+});
+txrep_struct!(ManageBuyOfferOp {
+    selling: "selling",
+    buying: "buying",
+    buy_amount: "buyAmount",
+    price: "price",
+    offer_id: "offerID",
+});
+txrep_struct!(CreatePassiveSellOfferOp { selling: "selling", buying: "buying", amount: "amount", price: "price" });
+txrep_struct!(Price { n: "n", d: "d" });
+txrep_struct!(SetOptionsOp {
+    inflation_dest: "inflationDest",
+    clear_flags: "clearFlags",
+    set_flags: "setFlags",
+    master_weight: "masterWeight",
+    low_threshold: "lowThreshold",
+    med_threshold: "medThreshold",
+    high_threshold: "highThreshold",
+    home_domain: "homeDomain",
+    signer: "signer",
+});
+txrep_struct!(Signer { key: "key", weight: "weight" });
+txrep_struct!(ChangeTrustOp { line: "line", limit: "limit" });
+txrep_union!(LiquidityPoolParameters, "type", {}, {
+    LiquidityPoolConstantProduct = "LIQUIDITY_POOL_CONSTANT_PRODUCT" => "constantProduct",
+});
+txrep_struct!(LiquidityPoolConstantProductParameters { asset_a: "assetA", asset_b: "assetB", fee: "fee" });
+txrep_struct!(AllowTrustOp { trustor: "trustor", asset: "asset", authorize: "authorize" });
+txrep_struct!(ManageDataOp { data_name: "dataName", data_value: "dataValue" });
+txrep_struct!(BumpSequenceOp { bump_to: "bumpTo" });
+txrep_struct!(CreateClaimableBalanceOp { asset: "asset", amount: "amount", claimants: "claimants" });
+txrep_union!(Claimant, "type", {}, { ClaimantTypeV0 = "CLAIMANT_TYPE_V0" => "v0" });
+txrep_struct!(ClaimantV0 { destination: "destination", predicate: "predicate" });
+txrep_union!(ClaimPredicate, "type", { ClaimPredicateUnconditional = "CLAIM_PREDICATE_UNCONDITIONAL" }, {
+    ClaimPredicateAnd = "CLAIM_PREDICATE_AND" => "andPredicates",
+    ClaimPredicateOr = "CLAIM_PREDICATE_OR" => "orPredicates",
+    ClaimPredicateNot = "CLAIM_PREDICATE_NOT" => "notPredicate",
+    ClaimPredicateBeforeAbsoluteTime = "CLAIM_PREDICATE_BEFORE_ABSOLUTE_TIME" => "absBefore",
+    ClaimPredicateBeforeRelativeTime = "CLAIM_PREDICATE_BEFORE_RELATIVE_TIME" => "relBefore",
+});
+txrep_struct!(ClaimClaimableBalanceOp { balance_id: "balanceID" });
+txrep_union!(ClaimableBalanceId, "type", {}, {
+    ClaimableBalanceIdTypeV0 = "CLAIMABLE_BALANCE_ID_TYPE_V0" => "v0",
+});
+txrep_struct!(BeginSponsoringFutureReservesOp { sponsored_id: "sponsoredID" });
+txrep_union!(RevokeSponsorshipOp, "type", {}, {
+    RevokeSponsorshipLedgerEntry = "REVOKE_SPONSORSHIP_LEDGER_ENTRY" => "ledgerKey",
+    RevokeSponsorshipSigner = "REVOKE_SPONSORSHIP_SIGNER" => "signer",
+});
+txrep_struct!(RevokeSponsorshipOpSigner { account_id: "accountID", signer_key: "signerKey" });
+txrep_struct!(ClawbackOp { asset: "asset", from: "from", amount: "amount" });
+txrep_struct!(ClawbackClaimableBalanceOp { balance_id: "balanceID" });
+txrep_struct!(SetTrustLineFlagsOp {
+    trustor: "trustor",
+    asset: "asset",
+    clear_flags: "clearFlags",
+    set_flags: "setFlags",
+});
+txrep_struct!(LiquidityPoolDepositOp {
+    liquidity_pool_id: "liquidityPoolID",
+    max_amount_a: "maxAmountA",
+    max_amount_b: "maxAmountB",
+    min_price: "minPrice",
+    max_price: "maxPrice",
+});
+txrep_struct!(LiquidityPoolWithdrawOp {
+    liquidity_pool_id: "liquidityPoolID",
+    amount: "amount",
+    min_amount_a: "minAmountA",
+    min_amount_b: "minAmountB",
+});
+
+// Ledger keys
+
+txrep_union!(LedgerKey, "type", {}, {
+    Account = "ACCOUNT" => "account",
+    Trustline = "TRUSTLINE" => "trustLine",
+    Offer = "OFFER" => "offer",
+    Data = "DATA" => "data",
+    ClaimableBalance = "CLAIMABLE_BALANCE" => "claimableBalance",
+    LiquidityPool = "LIQUIDITY_POOL" => "liquidityPool",
+    ContractData = "CONTRACT_DATA" => "contractData",
+    ContractCode = "CONTRACT_CODE" => "contractCode",
+    ConfigSetting = "CONFIG_SETTING" => "configSetting",
+    Ttl = "TTL" => "ttl",
+});
+txrep_struct!(LedgerKeyAccount { account_id: "accountID" });
+txrep_struct!(LedgerKeyTrustLine { account_id: "accountID", asset: "asset" });
+txrep_struct!(LedgerKeyOffer { seller_id: "sellerID", offer_id: "offerID" });
+txrep_struct!(LedgerKeyData { account_id: "accountID", data_name: "dataName" });
+txrep_struct!(LedgerKeyClaimableBalance { balance_id: "balanceID" });
+txrep_struct!(LedgerKeyLiquidityPool { liquidity_pool_id: "liquidityPoolID" });
+txrep_struct!(LedgerKeyContractData { contract: "contract", key: "key", durability: "durability" });
+txrep_struct!(LedgerKeyContractCode { hash: "hash" });
+txrep_struct!(LedgerKeyConfigSetting { config_setting_id: "configSettingID" });
+txrep_struct!(LedgerKeyTtl { key_hash: "keyHash" });
+txrep_enum!(ContractDataDurability { Temporary = "TEMPORARY", Persistent = "PERSISTENT" });
+txrep_enum!(ConfigSettingId {
+    ConfigSettingContractMaxSizeBytes = "CONFIG_SETTING_CONTRACT_MAX_SIZE_BYTES",
+    ConfigSettingContractComputeV0 = "CONFIG_SETTING_CONTRACT_COMPUTE_V0",
+    ConfigSettingContractLedgerCostV0 = "CONFIG_SETTING_CONTRACT_LEDGER_COST_V0",
+    ConfigSettingContractHistoricalDataV0 = "CONFIG_SETTING_CONTRACT_HISTORICAL_DATA_V0",
+    ConfigSettingContractEventsV0 = "CONFIG_SETTING_CONTRACT_EVENTS_V0",
+    ConfigSettingContractBandwidthV0 = "CONFIG_SETTING_CONTRACT_BANDWIDTH_V0",
+    ConfigSettingContractCostParamsCpuInstructions = "CONFIG_SETTING_CONTRACT_COST_PARAMS_CPU_INSTRUCTIONS",
+    ConfigSettingContractCostParamsMemoryBytes = "CONFIG_SETTING_CONTRACT_COST_PARAMS_MEMORY_BYTES",
+    ConfigSettingContractDataKeySizeBytes = "CONFIG_SETTING_CONTRACT_DATA_KEY_SIZE_BYTES",
+    ConfigSettingContractDataEntrySizeBytes = "CONFIG_SETTING_CONTRACT_DATA_ENTRY_SIZE_BYTES",
+    ConfigSettingStateArchival = "CONFIG_SETTING_STATE_ARCHIVAL",
+    ConfigSettingContractExecutionLanes = "CONFIG_SETTING_CONTRACT_EXECUTION_LANES",
+    ConfigSettingLiveSorobanStateSizeWindow = "CONFIG_SETTING_LIVE_SOROBAN_STATE_SIZE_WINDOW",
+    ConfigSettingEvictionIterator = "CONFIG_SETTING_EVICTION_ITERATOR",
+    ConfigSettingContractParallelComputeV0 = "CONFIG_SETTING_CONTRACT_PARALLEL_COMPUTE_V0",
+    ConfigSettingContractLedgerCostExtV0 = "CONFIG_SETTING_CONTRACT_LEDGER_COST_EXT_V0",
+    ConfigSettingScpTiming = "CONFIG_SETTING_SCP_TIMING",
+});
+
+// Soroban
+
+txrep_struct!(SorobanTransactionData { ext: "ext", resources: "resources", resource_fee: "resourceFee" });
+txrep_union!(SorobanTransactionDataExt, "v", { V0 = "0" }, { V1 = "1" => "resourceExt" });
+txrep_struct!(SorobanResourcesExtV0 { archived_soroban_entries: "archivedSorobanEntries" });
+txrep_struct!(SorobanResources {
+    footprint: "footprint",
+    instructions: "instructions",
+    disk_read_bytes: "diskReadBytes",
+    write_bytes: "writeBytes",
+});
+txrep_struct!(LedgerFootprint { read_only: "readOnly", read_write: "readWrite" });
+txrep_union!(ExtensionPoint, "v", { V0 = "0" }, {});
+txrep_struct!(ExtendFootprintTtlOp { ext: "ext", extend_to: "extendTo" });
+txrep_struct!(RestoreFootprintOp { ext: "ext" });
+txrep_struct!(InvokeHostFunctionOp { host_function: "hostFunction", auth: "auth" });
+txrep_union!(HostFunction, "type", {}, {
+    HostFunctionTypeInvokeContract = "HOST_FUNCTION_TYPE_INVOKE_CONTRACT" => "invokeContract",
+    HostFunctionTypeCreateContract = "HOST_FUNCTION_TYPE_CREATE_CONTRACT" => "createContract",
+    HostFunctionTypeUploadContractWasm = "HOST_FUNCTION_TYPE_UPLOAD_CONTRACT_WASM" => "wasm",
+    HostFunctionTypeCreateContractV2 = "HOST_FUNCTION_TYPE_CREATE_CONTRACT_V2" => "createContractV2",
+});
+txrep_struct!(InvokeContractArgs { contract_address: "contractAddress", function_name: "functionName", args: "args" });
+txrep_struct!(CreateContractArgs { contract_id_preimage: "contractIDPreimage", executable: "executable" });
+txrep_struct!(CreateContractArgsV2 {
+    contract_id_preimage: "contractIDPreimage",
+    executable: "executable",
+    constructor_args: "constructorArgs",
+});
+txrep_union!(ContractIdPreimage, "type", {}, {
+    ContractIdPreimageFromAddress = "CONTRACT_ID_PREIMAGE_FROM_ADDRESS" => "fromAddress",
+    ContractIdPreimageFromAsset = "CONTRACT_ID_PREIMAGE_FROM_ASSET" => "fromAsset",
+});
+txrep_struct!(ContractIdPreimageFromAddress { address: "address", salt: "salt" });
+txrep_union!(ContractExecutable, "type", {
+    ContractExecutableStellarAsset = "CONTRACT_EXECUTABLE_STELLAR_ASSET",
+}, {
+    ContractExecutableWasm = "CONTRACT_EXECUTABLE_WASM" => "wasm_hash",
+});
+txrep_struct!(SorobanAuthorizationEntry { credentials: "credentials", root_invocation: "rootInvocation" });
+txrep_union!(SorobanCredentials, "type", {
+    SorobanCredentialsSourceAccount = "SOROBAN_CREDENTIALS_SOURCE_ACCOUNT",
+}, {
+    SorobanCredentialsAddress = "SOROBAN_CREDENTIALS_ADDRESS" => "address",
+});
+txrep_struct!(SorobanAddressCredentials {
+    address: "address",
+    nonce: "nonce",
+    signature_expiration_ledger: "signatureExpirationLedger",
+    signature: "signature",
+});
+txrep_struct!(SorobanAuthorizedInvocation { function: "function", sub_invocations: "subInvocations" });
+txrep_union!(SorobanAuthorizedFunction, "type", {}, {
+    SorobanAuthorizedFunctionTypeContractFn = "SOROBAN_AUTHORIZED_FUNCTION_TYPE_CONTRACT_FN" => "contractFn",
+    SorobanAuthorizedFunctionTypeCreateContractHostFn =
+        "SOROBAN_AUTHORIZED_FUNCTION_TYPE_CREATE_CONTRACT_HOST_FN" => "createContractHostFn",
+    SorobanAuthorizedFunctionTypeCreateContractV2HostFn =
+        "SOROBAN_AUTHORIZED_FUNCTION_TYPE_CREATE_CONTRACT_V2_HOST_FN" => "createContractV2HostFn",
+});
+txrep_union!(ScAddress, "type", {}, {
+    ScAddressTypeAccount = "SC_ADDRESS_TYPE_ACCOUNT" => "accountId",
+    ScAddressTypeContract = "SC_ADDRESS_TYPE_CONTRACT" => "contractId",
+    ScAddressTypeMuxedAccount = "SC_ADDRESS_TYPE_MUXED_ACCOUNT" => "muxedAccount",
+    ScAddressTypeClaimableBalance = "SC_ADDRESS_TYPE_CLAIMABLE_BALANCE" => "claimableBalanceId",
+    ScAddressTypeLiquidityPool = "SC_ADDRESS_TYPE_LIQUIDITY_POOL" => "liquidityPoolId",
+});
+txrep_struct!(MuxedEd25519Account { id: "id", ed25519: "ed25519" });
+txrep_union!(ScVal, "type", {
+    ScvVoid = "SCV_VOID",
+    ScvLedgerKeyContractInstance = "SCV_LEDGER_KEY_CONTRACT_INSTANCE",
+}, {
+    ScvBool = "SCV_BOOL" => "b",
+    ScvError = "SCV_ERROR" => "error",
+    ScvU32 = "SCV_U32" => "u32",
+    ScvI32 = "SCV_I32" => "i32",
+    ScvU64 = "SCV_U64" => "u64",
+    ScvI64 = "SCV_I64" => "i64",
+    ScvTimepoint = "SCV_TIMEPOINT" => "timepoint",
+    ScvDuration = "SCV_DURATION" => "duration",
+    ScvU128 = "SCV_U128" => "u128",
+    ScvI128 = "SCV_I128" => "i128",
+    ScvU256 = "SCV_U256" => "u256",
+    ScvI256 = "SCV_I256" => "i256",
+    ScvBytes = "SCV_BYTES" => "bytes",
+    ScvString = "SCV_STRING" => "str",
+    ScvSymbol = "SCV_SYMBOL" => "sym",
+    ScvVec = "SCV_VEC" => "vec",
+    ScvMap = "SCV_MAP" => "map",
+    ScvAddress = "SCV_ADDRESS" => "address",
+    ScvContractInstance = "SCV_CONTRACT_INSTANCE" => "instance",
+    ScvLedgerKeyNonce = "SCV_LEDGER_KEY_NONCE" => "nonce_key",
+});
+txrep_struct!(UInt128Parts { hi: "hi", lo: "lo" });
+txrep_struct!(Int128Parts { hi: "hi", lo: "lo" });
+txrep_struct!(UInt256Parts { hi_hi: "hi_hi", hi_lo: "hi_lo", lo_hi: "lo_hi", lo_lo: "lo_lo" });
+txrep_struct!(Int256Parts { hi_hi: "hi_hi", hi_lo: "hi_lo", lo_hi: "lo_hi", lo_lo: "lo_lo" });
+txrep_struct!(ScMapEntry { key: "key", val: "val" });
+txrep_struct!(ScContractInstance { executable: "executable", storage: "storage" });
+txrep_struct!(ScNonceKey { nonce: "nonce" });
+txrep_union!(ScError, "type", {}, {
+    SceContract = "SCE_CONTRACT" => "contractCode",
+    SceWasmVm = "SCE_WASM_VM" => "code",
+    SceContext = "SCE_CONTEXT" => "code",
+    SceStorage = "SCE_STORAGE" => "code",
+    SceObject = "SCE_OBJECT" => "code",
+    SceCrypto = "SCE_CRYPTO" => "code",
+    SceEvents = "SCE_EVENTS" => "code",
+    SceBudget = "SCE_BUDGET" => "code",
+    SceValue = "SCE_VALUE" => "code",
+    SceAuth = "SCE_AUTH" => "code",
+});
+txrep_enum!(ScErrorCode {
+    ScecArithDomain = "SCEC_ARITH_DOMAIN",
+    ScecIndexBounds = "SCEC_INDEX_BOUNDS",
+    ScecInvalidInput = "SCEC_INVALID_INPUT",
+    ScecMissingValue = "SCEC_MISSING_VALUE",
+    ScecExistingValue = "SCEC_EXISTING_VALUE",
+    ScecExceededLimit = "SCEC_EXCEEDED_LIMIT",
+    ScecInvalidAction = "SCEC_INVALID_ACTION",
+    ScecInternalError = "SCEC_INTERNAL_ERROR",
+    ScecUnexpectedType = "SCEC_UNEXPECTED_TYPE",
+    ScecUnexpectedSize = "SCEC_UNEXPECTED_SIZE",
+});
+
+impl TransactionEnvelope {
+    /// Return the Txrep representation of this envelope (SEP-11) as an ASCII string (given as `Vec<u8>`)
+    ///
+    /// Every line has the form `key: value` and ends with a newline character.
+    pub fn to_txrep(&self) -> Vec<u8> {
+        let mut writer = TxrepWriter { output: Vec::new() };
+        self.write_txrep("", &mut writer);
+        writer.output
+    }
+
+    /// Decode an envelope from its Txrep representation (SEP-11)
+    ///
+    /// Empty lines are ignored and a value may be followed by a comment, e.g.
+    /// `tx.fee: 100 (0.00001 XLM)`. Lines that are not needed for the envelope are ignored.
+    pub fn from_txrep<T: AsRef<[u8]>>(txrep: T) -> Result<Self, StellarSdkError> {
+        let reader = TxrepReader::new(txrep.as_ref())?;
+        TransactionEnvelope::read_txrep("", &reader)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        compound_types::{LimitedString, LimitedVarArray, LimitedVarOpaque},
+        network::TEST_NETWORK,
+        types::{
+            ContractExecutable, ContractIdPreimage, ContractIdPreimageFromAddress, CreateContractArgs, HostFunction,
+            InvokeContractArgs, InvokeHostFunctionOp, OperationBody, ScAddress, ScVal, SorobanAuthorizationEntry,
+            SorobanAuthorizedFunction, SorobanAuthorizedInvocation, SorobanCredentials, TransactionV0,
+            TransactionV0Envelope, TransactionV0Ext,
+        },
+        Asset, ClaimPredicate, Claimant, FeeBumpTransaction, Memo, Operation, SecretKey, StellarSdkError, TimeBounds,
+        Transaction, TransactionEnvelope,
+    };
+
+    const ACCOUNT_ID1: &str = "GDGRDTRINPF66FNC47H22NY6BNWMCD5Q4XZTVA2KG7PFZ64WHRIU62TQ";
+    const ACCOUNT_ID2: &str = "GBNKQVTFRP25TIQRODMU5GJGSXDKHCEUDN7LNMOS5PNM427LMR77NV4M";
+    const SECRET: &str = "SBA2XQ5SRUW5H3FUQARMC6QYEPUYNSVCMM4PGESGVB2UIFHLM73TPXXF";
+
+    const PAYMENT_TXREP: &str = "type: ENVELOPE_TYPE_TX
+tx.sourceAccount: GDGRDTRINPF66FNC47H22NY6BNWMCD5Q4XZTVA2KG7PFZ64WHRIU62TQ
+tx.fee: 100
+tx.seqNum: 1234
+tx.cond.type: PRECOND_TIME
+tx.cond.timeBounds.minTime: 10
+tx.cond.timeBounds.maxTime: 20
+tx.memo.type: MEMO_TEXT
+tx.memo.text: \"say \\\"hi\\\"\"
+tx.operations.len: 1
+tx.operations[0].sourceAccount._present: false
+tx.operations[0].body.type: PAYMENT
+tx.operations[0].body.paymentOp.destination: GBNKQVTFRP25TIQRODMU5GJGSXDKHCEUDN7LNMOS5PNM427LMR77NV4M
+tx.operations[0].body.paymentOp.asset: USD:GBNKQVTFRP25TIQRODMU5GJGSXDKHCEUDN7LNMOS5PNM427LMR77NV4M
+tx.operations[0].body.paymentOp.amount: 10000000
+tx.ext.v: 0
+signatures.len: 0
+";
+
+    fn round_trip(envelope: &TransactionEnvelope) {
+        let txrep = envelope.to_txrep();
+        assert_eq!(&TransactionEnvelope::from_txrep(txrep).unwrap(), envelope);
+    }
+
+    #[test]
+    fn encode_payment() {
+        let mut transaction = Transaction::new(
+            ACCOUNT_ID1,
+            1234,
+            Some(100),
+            crate::types::Preconditions::PrecondTime(TimeBounds { min_time: 10, max_time: 20 }),
+            Some(Memo::from_text_memo("say \"hi\"").unwrap()),
+        )
+        .unwrap();
+        transaction
+            .append_operation(
+                Operation::new_payment(ACCOUNT_ID2, Asset::from_asset_code("USD", ACCOUNT_ID2).unwrap(), "1").unwrap(),
+            )
+            .unwrap();
+        let envelope = transaction.into_transaction_envelope();
+
+        assert_eq!(envelope.to_txrep(), PAYMENT_TXREP.as_bytes().to_vec());
+        assert_eq!(TransactionEnvelope::from_txrep(PAYMENT_TXREP), Ok(envelope));
+
+        let commented = PAYMENT_TXREP.replace("tx.fee: 100", "\ntx.fee: 100 (0.00001 XLM)");
+        assert!(TransactionEnvelope::from_txrep(commented).is_ok());
+        assert_eq!(
+            TransactionEnvelope::from_txrep(PAYMENT_TXREP.replace("tx.seqNum: 1234\n", "")),
+            Err(StellarSdkError::MissingTxrepKey { key: b"tx.seqNum".to_vec() })
+        );
+        assert_eq!(
+            TransactionEnvelope::from_txrep(PAYMENT_TXREP.replace("PAYMENT", "PAYMENTS")),
+            Err(StellarSdkError::InvalidTxrepValue { key: b"tx.operations[0].body.type".to_vec() })
+        );
+    }
+
+    #[test]
+    fn round_trip_envelopes() {
+        let secret_key = SecretKey::from_encoding(SECRET).unwrap();
+        let mut transaction = Transaction::new(
+            ACCOUNT_ID1,
+            1,
+            None,
+            crate::types::Preconditions::PrecondNone,
+            Some(Memo::MemoHash([7; 32])),
+        )
+        .unwrap();
+        transaction
+            .append_operation(
+                Operation::new_create_claimable_balance(
+                    Asset::native(),
+                    "10",
+                    vec![Claimant::new(
+                        ACCOUNT_ID2,
+                        ClaimPredicate::ClaimPredicateNot(Some(Box::new(
+                            ClaimPredicate::ClaimPredicateBeforeRelativeTime(60),
+                        ))),
+                    )
+                    .unwrap()],
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        transaction
+            .append_operation(Operation::new_manage_data_put("name\n", LimitedVarOpaque::new_empty()).unwrap())
+            .unwrap();
+        let mut envelope = transaction.into_transaction_envelope();
+        envelope.sign(&TEST_NETWORK, vec![&secret_key]).unwrap();
+        round_trip(&envelope);
+
+        let fee_bump = FeeBumpTransaction::new(ACCOUNT_ID2, "0.1", envelope).unwrap();
+        round_trip(&fee_bump.into_transaction_envelope());
+
+        let address = ScAddress::ScAddressTypeAccount(secret_key.get_public().clone());
+        let invocation = InvokeContractArgs {
+            contract_address: ScAddress::ScAddressTypeContract([1; 32]),
+            function_name: LimitedString::new(b"transfer".to_vec()).unwrap(),
+            args: LimitedVarArray::new(vec![
+                ScVal::ScvAddress(address.clone()),
+                ScVal::ScvVec(Some(LimitedVarArray::new(vec![ScVal::ScvVoid, ScVal::ScvBool(true)]).unwrap())),
+                ScVal::ScvString(LimitedString::new(vec![0, 255]).unwrap()),
+            ])
+            .unwrap(),
+        };
+        let mut transaction =
+            Transaction::new(ACCOUNT_ID1, 2, None, crate::types::Preconditions::PrecondNone, None).unwrap();
+        transaction
+            .append_operation(Operation {
+                source_account: None,
+                body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                    host_function: HostFunction::HostFunctionTypeInvokeContract(invocation.clone()),
+                    auth: LimitedVarArray::new(vec![SorobanAuthorizationEntry {
+                        credentials: SorobanCredentials::SorobanCredentialsSourceAccount,
+                        root_invocation: SorobanAuthorizedInvocation {
+                            function: SorobanAuthorizedFunction::SorobanAuthorizedFunctionTypeContractFn(invocation),
+                            sub_invocations: LimitedVarArray::new(vec![SorobanAuthorizedInvocation {
+                                function: SorobanAuthorizedFunction::SorobanAuthorizedFunctionTypeCreateContractHostFn(
+                                    CreateContractArgs {
+                                        contract_id_preimage: ContractIdPreimage::ContractIdPreimageFromAddress(
+                                            ContractIdPreimageFromAddress { address, salt: [2; 32] },
+                                        ),
+                                        executable: ContractExecutable::ContractExecutableWasm([3; 32]),
+                                    },
+                                ),
+                                sub_invocations: LimitedVarArray::new_empty(),
+                            }])
+                            .unwrap(),
+                        },
+                    }])
+                    .unwrap(),
+                }),
+            })
+            .unwrap();
+        let envelope = transaction.clone().into_transaction_envelope();
+        round_trip(&envelope);
+
+        let v0_envelope = TransactionEnvelope::EnvelopeTypeTxV0(TransactionV0Envelope {
+            tx: TransactionV0 {
+                source_account_ed25519: *secret_key.get_public().as_binary(),
+                fee: 100,
+                seq_num: 3,
+                time_bounds: Some(TimeBounds { min_time: 0, max_time: 100 }),
+                memo: Memo::MemoId(42),
+                operations: transaction.operations,
+                ext: TransactionV0Ext::V0,
+            },
+            signatures: LimitedVarArray::new_empty(),
+        });
+        round_trip(&v0_envelope);
+    }
+}
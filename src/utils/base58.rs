@@ -0,0 +1,84 @@
+use core::convert::AsRef;
+use sp_std::{vec, vec::Vec};
+
+use crate::StellarSdkError;
+
+const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+fn ascii_to_value(char: u8) -> Option<u8> {
+    ALPHABET
+        .iter()
+        .position(|alphabet_char| *alphabet_char == char)
+        .map(|value| value as u8)
+}
+
+pub fn encode<T: AsRef<[u8]>>(binary: T) -> Vec<u8> {
+    let binary = binary.as_ref();
+    let leading_zeros = binary.iter().take_while(|byte| **byte == 0).count();
+
+    // little endian digits in base 58
+    let mut digits: Vec<u8> = Vec::with_capacity(binary.len() * 138 / 100 + 1);
+    for byte in binary[leading_zeros..].iter() {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let mut result = vec![ALPHABET[0]; leading_zeros];
+    result.extend(digits.iter().rev().map(|digit| ALPHABET[*digit as usize]));
+    result
+}
+
+pub fn decode<T: AsRef<[u8]>>(string: T) -> Result<Vec<u8>, StellarSdkError> {
+    let string = string.as_ref();
+    let leading_zeros = string.iter().take_while(|char| **char == ALPHABET[0]).count();
+
+    // little endian bytes
+    let mut bytes: Vec<u8> = Vec::with_capacity(string.len() * 733 / 1000 + 1);
+    for (position, ascii) in string.iter().enumerate().skip(leading_zeros) {
+        let mut carry = match ascii_to_value(*ascii) {
+            Some(value) => value as u32,
+            None => return Err(StellarSdkError::InvalidBase58Character { at_position: position }),
+        };
+
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = (carry & 0xff) as u8;
+            carry >>= 8;
+        }
+
+        while carry > 0 {
+            bytes.push((carry & 0xff) as u8);
+            carry >>= 8;
+        }
+    }
+
+    let mut result = vec![0; leading_zeros];
+    result.extend(bytes.iter().rev());
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode() {
+        assert_eq!(encode(b"Hello World!"), b"2NEpo7TZRRrLZSi2U".to_vec());
+        assert_eq!(decode(b"2NEpo7TZRRrLZSi2U"), Ok(b"Hello World!".to_vec()));
+
+        assert_eq!(encode([0, 0, 0x28, 0x7f, 0xb4, 0xcd]), b"11233QC4".to_vec());
+        assert_eq!(decode(b"11233QC4"), Ok(vec![0, 0, 0x28, 0x7f, 0xb4, 0xcd]));
+
+        assert_eq!(encode([]), b"".to_vec());
+        assert_eq!(decode(b"2NEpo7TZRRrLZSi2l"), Err(StellarSdkError::InvalidBase58Character { at_position: 16 }));
+    }
+}
@@ -1,6 +1,9 @@
 //! Stellar encoding of keys
 
-use core::convert::{AsRef, TryInto};
+use core::{
+    convert::{AsRef, TryInto},
+    ops::RangeInclusive,
+};
 use sp_std::vec::Vec;
 
 use super::base32::{decode, encode};
@@ -16,18 +19,36 @@ pub const ED25519_SECRET_SEED_VERSION_BYTE: u8 = 18 << 3; // S
 pub const MED25519_PUBLIC_KEY_BYTE_LENGTH: usize = 40;
 pub const MED25519_PUBLIC_KEY_VERSION_BYTE: u8 = 12 << 3; // M
 
+pub const PRE_AUTH_TX_VERSION_BYTE: u8 = 19 << 3; // T
+pub const SHA256_HASH_VERSION_BYTE: u8 = 23 << 3; // X
+pub const SIGNED_PAYLOAD_VERSION_BYTE: u8 = 15 << 3; // P
+
+pub const CONTRACT_VERSION_BYTE: u8 = 2 << 3; // C
+pub const LIQUIDITY_POOL_VERSION_BYTE: u8 = 11 << 3; // L
+pub const CLAIMABLE_BALANCE_VERSION_BYTE: u8 = 1 << 3; // B
+
 /// Use Stellar's key encoding to decode a key given as an ASCII string (as `&[u8]`)
 pub fn decode_stellar_key<T: AsRef<[u8]>, const BYTE_LENGTH: usize>(
     encoded_key: T,
     version_byte: u8,
 ) -> Result<[u8; BYTE_LENGTH], StellarSdkError> {
+    let decoded_key = decode_variable_length_stellar_key(encoded_key, version_byte, BYTE_LENGTH..=BYTE_LENGTH)?;
+    Ok(decoded_key.try_into().unwrap())
+}
+
+/// Use Stellar's key encoding to decode a key whose binary length is in `byte_length_range`
+pub fn decode_variable_length_stellar_key<T: AsRef<[u8]>>(
+    encoded_key: T,
+    version_byte: u8,
+    byte_length_range: RangeInclusive<usize>,
+) -> Result<Vec<u8>, StellarSdkError> {
     let decoded_array = decode(encoded_key.as_ref())?;
     if *encoded_key.as_ref() != encode(&decoded_array)[..] {
         return Err(StellarSdkError::InvalidStellarKeyEncoding)
     }
 
     let array_length = decoded_array.len();
-    if array_length != 3 + BYTE_LENGTH {
+    if array_length < 3 || !byte_length_range.contains(&(array_length - 3)) {
         return Err(StellarSdkError::InvalidStellarKeyEncodingLength)
     }
 
@@ -45,12 +66,17 @@ pub fn decode_stellar_key<T: AsRef<[u8]>, const BYTE_LENGTH: usize>(
         })
     }
 
-    Ok(decoded_array[1..array_length - 2].try_into().unwrap())
+    Ok(decoded_array[1..array_length - 2].to_vec())
 }
 
 /// Return the key encoding as an ASCII string (given as `Vec<u8>`)
 pub fn encode_stellar_key<const BYTE_LENGTH: usize>(key: &[u8; BYTE_LENGTH], version_byte: u8) -> Vec<u8> {
-    let mut unencoded_array = Vec::with_capacity(3 + BYTE_LENGTH);
+    encode_variable_length_stellar_key(key, version_byte)
+}
+
+/// Return the key encoding of a key of arbitrary length as an ASCII string (given as `Vec<u8>`)
+pub fn encode_variable_length_stellar_key(key: &[u8], version_byte: u8) -> Vec<u8> {
+    let mut unencoded_array = Vec::with_capacity(3 + key.len());
     unencoded_array.push(version_byte);
     unencoded_array.extend(key.iter());
 
@@ -1,4 +1,6 @@
 mod base32;
+#[cfg(feature = "substrate")]
+pub mod base58;
 pub mod base64;
 pub mod key_encoding;
 pub mod percent_encode;
@@ -2,6 +2,8 @@ use sp_std::vec::Vec;
 
 use core::convert::AsRef;
 
+use crate::StellarSdkError;
+
 fn is_unreserved_character(char: &u8) -> bool {
     match *char {
         b'-' | b'_' | b'.' | b'~' => true,
@@ -9,6 +11,15 @@ fn is_unreserved_character(char: &u8) -> bool {
     }
 }
 
+fn hex_value(char: u8) -> Option<u8> {
+    match char {
+        b'0'..=b'9' => Some(char - b'0'),
+        b'a'..=b'f' => Some(char - b'a' + 10),
+        b'A'..=b'F' => Some(char - b'A' + 10),
+        _ => None,
+    }
+}
+
 fn hex_code(char: u8) -> u8 {
     if char < 10 {
         char + b'0'
@@ -33,3 +44,39 @@ pub fn percent_encode<T: AsRef<[u8]>>(string: T) -> Vec<u8> {
 
     result
 }
+
+pub fn percent_decode<T: AsRef<[u8]>>(string: T) -> Result<Vec<u8>, StellarSdkError> {
+    let string = string.as_ref();
+    let mut result = Vec::with_capacity(string.len());
+
+    let mut position = 0;
+    while position < string.len() {
+        if string[position] == b'%' {
+            let high = string.get(position + 1).copied().and_then(hex_value);
+            let low = string.get(position + 2).copied().and_then(hex_value);
+            match (high, low) {
+                (Some(high), Some(low)) => result.push(high << 4 | low),
+                _ => return Err(StellarSdkError::InvalidPercentEncoding { at_position: position }),
+            }
+            position += 3;
+        } else {
+            result.push(string[position]);
+            position += 1;
+        }
+    }
+
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_decode() {
+        assert_eq!(percent_encode("pay me 1/2 €"), b"pay%20me%201%2F2%20%E2%82%AC".to_vec());
+        assert_eq!(percent_decode("pay%20me%201%2f2%20%E2%82%AC"), Ok("pay me 1/2 €".as_bytes().to_vec()));
+        assert_eq!(percent_decode("100%"), Err(StellarSdkError::InvalidPercentEncoding { at_position: 3 }));
+        assert_eq!(percent_decode("%4g"), Err(StellarSdkError::InvalidPercentEncoding { at_position: 0 }));
+    }
+}
@@ -38,6 +38,11 @@ impl<const N: i32> LimitedVarOpaque<N> {
         &self.0
     }
 
+    /// Returns the raw byte vector
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -97,6 +102,11 @@ impl<const N: i32> LimitedString<N> {
         &self.0
     }
 
+    /// Returns the raw byte vector
+    pub fn into_vec(self) -> Vec<u8> {
+        self.0
+    }
+
     pub fn len(&self) -> usize {
         self.0.len()
     }
@@ -160,6 +170,11 @@ impl<T, const N: i32> LimitedVarArray<T, N> {
         &self.0
     }
 
+    /// Returns the vector
+    pub fn into_vec(self) -> Vec<T> {
+        self.0
+    }
+
     /// Searches for an element in the array that satisfies the predicate.
     ///
     /// # Arguments
@@ -0,0 +1,341 @@
+//! Deposits, withdrawals and swaps of constant product liquidity pools
+//!
+//! The computations reproduce the integer arithmetic of stellar-core, including its rounding,
+//! so that the results match the ledger to the stroop. Intermediate products are computed with
+//! 256 bits where stellar-core uses big integers.
+
+use core::convert::TryFrom;
+
+use crate::{types::LiquidityPoolEntryConstantProduct, Price};
+
+/// The fee of a pool is given in basis points
+const MAX_BPS: u128 = 10_000;
+
+/// The direction of a swap with a liquidity pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapDirection {
+    /// Send asset A to the pool and receive asset B
+    AToB,
+    /// Send asset B to the pool and receive asset A
+    BToA,
+}
+
+/// The amounts of a deposit into a liquidity pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolDeposit {
+    pub amount_a: i64,
+    pub amount_b: i64,
+    /// The number of pool shares minted for the deposit
+    pub pool_shares: i64,
+}
+
+/// The amounts of a withdrawal from a liquidity pool
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolWithdrawal {
+    pub amount_a: i64,
+    pub amount_b: i64,
+}
+
+/// The reason why a deposit or withdrawal fails, corresponds to the result codes of stellar-core
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiquidityPoolError {
+    /// An amount is not positive
+    InvalidAmount,
+
+    /// The price of the deposit is outside of the price bounds (`LIQUIDITY_POOL_DEPOSIT_BAD_PRICE`)
+    BadPrice,
+
+    /// The reserves or the pool shares would exceed `i64::MAX` (`LIQUIDITY_POOL_DEPOSIT_POOL_FULL`)
+    PoolFull,
+
+    /// The pool has fewer shares than should be withdrawn
+    InsufficientPoolShares,
+
+    /// A withdrawn amount is below its minimum (`LIQUIDITY_POOL_WITHDRAW_UNDER_MINIMUM`)
+    UnderMinimum,
+}
+
+/// Compute `a * b / c` with an intermediate product of 256 bits
+///
+/// Returns `None` if `c` is 0 or the result does not fit into 128 bits.
+fn mul_div(a: u128, b: u128, c: u128, round_up: bool) -> Option<u128> {
+    if c == 0 {
+        return None
+    }
+
+    const LOW_MASK: u128 = u64::MAX as u128;
+    let (a_high, a_low) = (a >> 64, a & LOW_MASK);
+    let (b_high, b_low) = (b >> 64, b & LOW_MASK);
+
+    let low_low = a_low * b_low;
+    let low_high = a_low * b_high;
+    let high_low = a_high * b_low;
+    let high_high = a_high * b_high;
+
+    let middle = (low_low >> 64) + (low_high & LOW_MASK) + (high_low & LOW_MASK);
+    let low = (low_low & LOW_MASK) | (middle << 64);
+    let high = high_high + (low_high >> 64) + (high_low >> 64) + (middle >> 64);
+
+    if high >= c {
+        return None
+    }
+
+    let mut remainder = high;
+    let mut quotient = 0u128;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= c {
+            remainder = remainder.wrapping_sub(c);
+            quotient |= 1;
+        }
+    }
+
+    match round_up && remainder != 0 {
+        true => quotient.checked_add(1),
+        false => Some(quotient),
+    }
+}
+
+/// Compute `a * b / c` for non-negative 64 bit integers like `bigDivide` of stellar-core
+fn big_divide(a: i64, b: i64, c: i64, round_up: bool) -> Option<i64> {
+    if a < 0 || b < 0 || c <= 0 {
+        return None
+    }
+    mul_div(a as u128, b as u128, c as u128, round_up).and_then(|result| i64::try_from(result).ok())
+}
+
+/// Compute `floor(sqrt(a * b))` for non-negative 64 bit integers
+fn big_square_root(a: i64, b: i64) -> i64 {
+    let product = a as u128 * b as u128;
+    let (mut low, mut high) = (0u128, 1u128 << 64);
+    while high - low > 1 {
+        let middle = (low + high) / 2;
+        match middle * middle <= product {
+            true => low = middle,
+            false => high = middle,
+        }
+    }
+    low as i64
+}
+
+/// Return whether the price `amount_a / amount_b` is outside of the price bounds
+///
+/// Like in stellar-core a deposit that rounds to zero for one of the assets has a bad price.
+fn is_bad_price(amount_a: i64, amount_b: i64, min_price: &Price, max_price: &Price) -> bool {
+    if amount_a == 0 || amount_b == 0 {
+        return true
+    }
+    let (amount_a, amount_b) = (amount_a as i128, amount_b as i128);
+    amount_a * (min_price.d as i128) < amount_b * (min_price.n as i128) ||
+        amount_a * (max_price.d as i128) > amount_b * (max_price.n as i128)
+}
+
+impl LiquidityPoolEntryConstantProduct {
+    fn get_reserves(&self, direction: SwapDirection) -> (i64, i64) {
+        match direction {
+            SwapDirection::AToB => (self.reserve_a, self.reserve_b),
+            SwapDirection::BToA => (self.reserve_b, self.reserve_a),
+        }
+    }
+
+    fn get_fee_factor(&self) -> u128 {
+        MAX_BPS - self.params.fee as u128
+    }
+
+    /// Compute the amounts and the pool shares of a deposit of at most `max_amount_a` and
+    /// `max_amount_b`
+    ///
+    /// If the pool is empty, the maximal amounts are deposited. Otherwise the amounts are
+    /// proportional to the reserves. The price of the deposited amounts (A per B) must be
+    /// within `min_price` and `max_price`.
+    pub fn deposit(
+        &self,
+        max_amount_a: i64,
+        max_amount_b: i64,
+        min_price: &Price,
+        max_price: &Price,
+    ) -> Result<PoolDeposit, LiquidityPoolError> {
+        if max_amount_a <= 0 || max_amount_b <= 0 {
+            return Err(LiquidityPoolError::InvalidAmount)
+        }
+
+        let deposit = if self.total_pool_shares == 0 {
+            PoolDeposit {
+                amount_a: max_amount_a,
+                amount_b: max_amount_b,
+                pool_shares: big_square_root(max_amount_a, max_amount_b),
+            }
+        } else {
+            let total_pool_shares = self.total_pool_shares;
+            let shares_a = big_divide(total_pool_shares, max_amount_a, self.reserve_a, false);
+            let shares_b = big_divide(total_pool_shares, max_amount_b, self.reserve_b, false);
+            let pool_shares = shares_a.min(shares_b).ok_or(LiquidityPoolError::PoolFull)?;
+            PoolDeposit {
+                amount_a: big_divide(pool_shares, self.reserve_a, total_pool_shares, true)
+                    .ok_or(LiquidityPoolError::PoolFull)?,
+                amount_b: big_divide(pool_shares, self.reserve_b, total_pool_shares, true)
+                    .ok_or(LiquidityPoolError::PoolFull)?,
+                pool_shares,
+            }
+        };
+
+        if is_bad_price(deposit.amount_a, deposit.amount_b, min_price, max_price) {
+            return Err(LiquidityPoolError::BadPrice)
+        }
+
+        let overflows = self.reserve_a.checked_add(deposit.amount_a).is_none() ||
+            self.reserve_b.checked_add(deposit.amount_b).is_none() ||
+            self.total_pool_shares.checked_add(deposit.pool_shares).is_none();
+        match overflows {
+            true => Err(LiquidityPoolError::PoolFull),
+            false => Ok(deposit),
+        }
+    }
+
+    /// Compute the amounts that are withdrawn for `pool_shares` shares
+    ///
+    /// The amounts are rounded down and must not be less than `min_amount_a` and `min_amount_b`.
+    pub fn withdraw(
+        &self,
+        pool_shares: i64,
+        min_amount_a: i64,
+        min_amount_b: i64,
+    ) -> Result<PoolWithdrawal, LiquidityPoolError> {
+        if pool_shares <= 0 || min_amount_a < 0 || min_amount_b < 0 {
+            return Err(LiquidityPoolError::InvalidAmount)
+        }
+        if pool_shares > self.total_pool_shares {
+            return Err(LiquidityPoolError::InsufficientPoolShares)
+        }
+
+        let withdrawal = PoolWithdrawal {
+            amount_a: big_divide(pool_shares, self.reserve_a, self.total_pool_shares, false)
+                .ok_or(LiquidityPoolError::InsufficientPoolShares)?,
+            amount_b: big_divide(pool_shares, self.reserve_b, self.total_pool_shares, false)
+                .ok_or(LiquidityPoolError::InsufficientPoolShares)?,
+        };
+
+        match withdrawal.amount_a < min_amount_a || withdrawal.amount_b < min_amount_b {
+            true => Err(LiquidityPoolError::UnderMinimum),
+            false => Ok(withdrawal),
+        }
+    }
+
+    /// Compute the amount that the pool pays for `send_amount` (strict send)
+    ///
+    /// Returns `None` if the pool cannot take the amount or would pay nothing.
+    pub fn swap_strict_send(&self, direction: SwapDirection, send_amount: i64) -> Option<i64> {
+        let (reserve_to_pool, reserve_from_pool) = self.get_reserves(direction);
+        if send_amount <= 0 || reserve_to_pool <= 0 || reserve_from_pool <= 0 {
+            return None
+        }
+        if send_amount > i64::MAX - reserve_to_pool {
+            return None
+        }
+
+        let fee_factor = self.get_fee_factor();
+        let denominator = MAX_BPS * reserve_to_pool as u128 + fee_factor * send_amount as u128;
+        let receive_amount = mul_div(fee_factor * send_amount as u128, reserve_from_pool as u128, denominator, false)?;
+
+        match receive_amount > 0 && receive_amount < reserve_from_pool as u128 {
+            true => Some(receive_amount as i64),
+            false => None,
+        }
+    }
+
+    /// Compute the amount that needs to be sent to the pool to receive `receive_amount` (strict
+    /// receive)
+    ///
+    /// Returns `None` if the pool does not have enough reserves or the amount to send overflows.
+    pub fn swap_strict_receive(&self, direction: SwapDirection, receive_amount: i64) -> Option<i64> {
+        let (reserve_to_pool, reserve_from_pool) = self.get_reserves(direction);
+        if receive_amount <= 0 || reserve_to_pool <= 0 || receive_amount >= reserve_from_pool {
+            return None
+        }
+
+        let denominator = (reserve_from_pool - receive_amount) as u128 * self.get_fee_factor();
+        let send_amount = mul_div(MAX_BPS * reserve_to_pool as u128, receive_amount as u128, denominator, true)?;
+
+        match send_amount <= (i64::MAX - reserve_to_pool) as u128 {
+            true => Some(send_amount as i64),
+            false => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mul_div, LiquidityPoolError, PoolDeposit, PoolWithdrawal, SwapDirection};
+    use crate::{
+        types::{LiquidityPoolEntryConstantProduct, LiquidityPoolParameters},
+        Asset, Price, LIQUIDITY_POOL_FEE_V18,
+    };
+
+    fn pool(reserve_a: i64, reserve_b: i64, total_pool_shares: i64) -> LiquidityPoolEntryConstantProduct {
+        let issuer = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        let asset = Asset::from_asset_code("USDC", issuer).unwrap();
+        let LiquidityPoolParameters::LiquidityPoolConstantProduct(params) =
+            LiquidityPoolParameters::constant_product(Asset::native(), asset).unwrap();
+        assert_eq!(params.fee, LIQUIDITY_POOL_FEE_V18);
+        LiquidityPoolEntryConstantProduct {
+            params,
+            reserve_a,
+            reserve_b,
+            total_pool_shares,
+            pool_shares_trust_line_count: 1,
+        }
+    }
+
+    #[test]
+    fn wide_arithmetic() {
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX, false), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 3, false), Some(u128::MAX / 3 * 2));
+        assert_eq!(mul_div(u128::MAX, 2, 1, false), None);
+        assert_eq!(mul_div(7, 3, 2, false), Some(10));
+        assert_eq!(mul_div(7, 3, 2, true), Some(11));
+    }
+
+    #[test]
+    fn deposit_and_withdraw() {
+        let any_price = (Price { n: 1, d: i32::MAX }, Price { n: i32::MAX, d: 1 });
+
+        let empty = pool(0, 0, 0);
+        assert_eq!(
+            empty.deposit(10_000_000_000, 20_000_000_000, &any_price.0, &any_price.1),
+            Ok(PoolDeposit { amount_a: 10_000_000_000, amount_b: 20_000_000_000, pool_shares: 14_142_135_623 })
+        );
+        assert_eq!(empty.deposit(10, 20, &Price { n: 1, d: 1 }, &any_price.1), Err(LiquidityPoolError::BadPrice));
+
+        let pool = pool(10_000_000_000, 20_000_000_000, 14_142_135_623);
+        assert_eq!(
+            pool.deposit(1_000_000, 3_000_000, &any_price.0, &any_price.1),
+            Ok(PoolDeposit { amount_a: 1_000_000, amount_b: 2_000_000, pool_shares: 1_414_213 })
+        );
+        assert_eq!(
+            pool.deposit(333, 1_000, &any_price.0, &any_price.1),
+            Ok(PoolDeposit { amount_a: 333, amount_b: 665, pool_shares: 470 })
+        );
+        // rounds to zero pool shares and amounts
+        assert_eq!(pool.deposit(1, 1, &any_price.0, &any_price.1), Err(LiquidityPoolError::BadPrice));
+
+        assert_eq!(pool.withdraw(1_414_213, 0, 0), Ok(PoolWithdrawal { amount_a: 999_999, amount_b: 1_999_999 }));
+        assert_eq!(pool.withdraw(1_414_213, 1_000_000, 0), Err(LiquidityPoolError::UnderMinimum));
+    }
+
+    #[test]
+    fn swap() {
+        let pool = pool(10_000_000_000, 20_000_000_000, 14_142_135_623);
+        assert_eq!(pool.swap_strict_send(SwapDirection::AToB, 10_000_000), Some(19_920_139));
+        assert_eq!(pool.swap_strict_send(SwapDirection::BToA, 10_000_000), Some(4_982_516));
+        assert_eq!(pool.swap_strict_send(SwapDirection::AToB, 0), None);
+
+        assert_eq!(pool.swap_strict_receive(SwapDirection::AToB, 19_920_139), Some(10_000_000));
+        assert_eq!(pool.swap_strict_receive(SwapDirection::BToA, 10_000_000), Some(20_080_261));
+        assert_eq!(pool.swap_strict_receive(SwapDirection::AToB, 20_000_000_000), None);
+
+        let large = self::pool(i64::MAX / 2, i64::MAX / 3, 1);
+        assert_eq!(large.swap_strict_send(SwapDirection::AToB, i64::MAX / 4), Some(1_022_767_425_285_753_695));
+    }
+}
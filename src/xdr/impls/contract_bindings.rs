@@ -0,0 +1,803 @@
+//! Generation of Rust bindings from contract specifications
+//!
+//! `ContractSpec::generate_rust_bindings` emits Rust source code with a type for every
+//! struct, union, enum and error enum of the spec and a client whose methods build the
+//! `InvokeHostFunction` operations for the contract's functions. It is meant to be called
+//! from a build script:
+//!
+//! ```ignore
+//! // build.rs
+//! let spec = ContractSpec::from_wasm(std::fs::read("token.wasm").unwrap()).unwrap();
+//! let bindings = spec.generate_rust_bindings("TokenClient", "substrate_stellar_sdk").unwrap();
+//! std::fs::write(Path::new(&env::var("OUT_DIR").unwrap()).join("token.rs"), bindings).unwrap();
+//!
+//! // lib.rs
+//! mod token {
+//!     include!(concat!(env!("OUT_DIR"), "/token.rs"));
+//! }
+//! ```
+//!
+//! The generated code imports the module `__contract_bindings` of the SDK, which has all items
+//! it refers to, so it compiles without `std`. It should be included in a module of its own.
+//! Bytes and symbols are mapped to `AsScBytes<Vec<u8>>` and `AsScSymbol<Vec<u8>>`, maps with
+//! keys that are not `Ord` as well as timepoints, durations and results (except as return
+//! values) are passed as plain `ScVal`.
+
+use core::str::from_utf8;
+use scale_info::prelude::format;
+use sp_std::{vec, vec::Vec};
+
+use crate::{
+    compound_types::LimitedString,
+    lib::{String, ToString},
+    types::{ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef, ScSpecUdtUnionCaseV0},
+    xdr::impls::contract_spec::is_tuple_struct,
+    ContractSpec, StellarSdkError,
+};
+
+/// The items that generated bindings refer to
+#[doc(hidden)]
+pub mod support {
+    use core::convert::TryInto;
+
+    pub use crate::{
+        lib::String,
+        types::{Int256Parts, Operation, ScAddress, ScError, ScVal, UInt256Parts},
+        AsScBytes, AsScSymbol, IntoScVal, StellarSdkError, TryFromScVal,
+    };
+    pub use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+    use crate::types::{ScMapEntry, ScValType};
+
+    pub fn into_array<const N: usize>(values: Vec<ScVal>) -> Result<[ScVal; N], StellarSdkError> {
+        values.try_into().map_err(|_| StellarSdkError::InvalidScVal)
+    }
+
+    /// Return the elements of a vector of length `N`
+    pub fn get_vec_elements<const N: usize>(value: ScVal) -> Result<[ScVal; N], StellarSdkError> {
+        match value {
+            ScVal::ScvVec(Some(elements)) => into_array(elements.into_vec()),
+            value => Err(StellarSdkError::UnexpectedScValType { expected: ScValType::ScvVec, found: value.get_type() }),
+        }
+    }
+
+    /// Create a struct, i.e., a map with the field names as symbol keys
+    pub fn new_struct(fields: Vec<(&str, ScVal)>) -> Result<ScVal, StellarSdkError> {
+        let entries = fields
+            .into_iter()
+            .map(|(name, val)| Ok(ScMapEntry { key: ScVal::new_symbol(name)?, val }))
+            .collect::<Result<_, StellarSdkError>>()?;
+        ScVal::new_map(entries)
+    }
+
+    /// Return the values of the fields `names` of a struct that has exactly these fields
+    pub fn get_struct_fields<const N: usize>(value: ScVal, names: [&str; N]) -> Result<[ScVal; N], StellarSdkError> {
+        let entries = match value {
+            ScVal::ScvMap(Some(entries)) if entries.len() == N => entries.into_vec(),
+            ScVal::ScvMap(_) => return Err(StellarSdkError::InvalidScVal),
+            value =>
+                return Err(StellarSdkError::UnexpectedScValType {
+                    expected: ScValType::ScvMap,
+                    found: value.get_type(),
+                }),
+        };
+
+        let values = names
+            .iter()
+            .map(|name| {
+                let key = ScVal::new_symbol(name)?;
+                let entry = entries.iter().find(|entry| entry.key == key);
+                entry.map(|entry| entry.val.clone()).ok_or(StellarSdkError::InvalidScVal)
+            })
+            .collect::<Result<_, _>>()?;
+        into_array(values)
+    }
+
+    /// Create a union case, i.e., a vector of the case name as symbol followed by the values
+    pub fn new_union_case(name: &str, values: Vec<ScVal>) -> Result<ScVal, StellarSdkError> {
+        let mut elements = Vec::with_capacity(values.len() + 1);
+        elements.push(ScVal::new_symbol(name)?);
+        elements.extend(values);
+        elements.into_sc_val()
+    }
+
+    /// Return the name and the values of a union case
+    pub fn get_union_case(value: ScVal) -> Result<(Vec<u8>, Vec<ScVal>), StellarSdkError> {
+        let mut elements = match value {
+            ScVal::ScvVec(Some(elements)) => elements.into_vec().into_iter(),
+            value =>
+                return Err(StellarSdkError::UnexpectedScValType {
+                    expected: ScValType::ScvVec,
+                    found: value.get_type(),
+                }),
+        };
+        match elements.next() {
+            Some(ScVal::ScvSymbol(name)) => Ok((name.into_vec(), elements.collect())),
+            _ => Err(StellarSdkError::InvalidScVal),
+        }
+    }
+
+    /// Decode the return value of a function that returns a `Result`
+    pub fn decode_result<T: TryFromScVal, E: TryFromScVal>(value: ScVal) -> Result<Result<T, E>, StellarSdkError> {
+        match value {
+            ScVal::ScvError(_) => E::try_from_sc_val(value).map(Err),
+            value => T::try_from_sc_val(value).map(Ok),
+        }
+    }
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn", "else", "enum",
+    "extern", "false", "final", "fn", "for", "if", "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut",
+    "override", "priv", "pub", "ref", "return", "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe",
+    "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords that cannot be raw identifiers
+const RESERVED_IDENTIFIERS: &[&str] = &["crate", "self", "Self", "super"];
+
+/// The maximal number of elements of a tuple that has `IntoScVal` and `TryFromScVal`
+const MAX_TUPLE_LENGTH: usize = 6;
+
+/// Return `name` as Rust identifier
+///
+/// Only ASCII alphanumeric characters and underscores are accepted so that a malicious spec
+/// cannot inject code into the bindings.
+fn to_identifier(name: &[u8]) -> Result<String, StellarSdkError> {
+    let is_valid = name.iter().all(|char| char.is_ascii_alphanumeric() || *char == b'_') &&
+        name.first().map(|char| !char.is_ascii_digit()).unwrap_or(false);
+    if !is_valid {
+        return Err(StellarSdkError::InvalidContractSpec)
+    }
+
+    let name = from_utf8(name).map_err(|_| StellarSdkError::InvalidContractSpec)?;
+    Ok(if RESERVED_IDENTIFIERS.contains(&name) {
+        format!("{}_", name)
+    } else if RUST_KEYWORDS.contains(&name) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    })
+}
+
+/// Return the unescaped name, to be used in string and byte string literals
+fn to_literal<const N: i32>(name: &LimitedString<N>) -> Result<&str, StellarSdkError> {
+    to_identifier(name.get_vec())?;
+    from_utf8(name.get_vec()).map_err(|_| StellarSdkError::InvalidContractSpec)
+}
+
+struct Generator<'a> {
+    spec: &'a ContractSpec,
+    /// The name under which the generated code imports the module `support`
+    support: String,
+    code: String,
+}
+
+impl<'a> Generator<'a> {
+    fn line(&mut self, indent: usize, line: &str) {
+        for _ in 0..indent {
+            self.code.push_str("    ");
+        }
+        self.code.push_str(line);
+        self.code.push('\n');
+    }
+
+    fn doc<const N: i32>(&mut self, indent: usize, doc: &LimitedString<N>) -> Result<(), StellarSdkError> {
+        let doc = from_utf8(doc.get_vec()).map_err(|_| StellarSdkError::InvalidContractSpec)?;
+        for line in doc.lines() {
+            match line.trim_end() {
+                "" => self.line(indent, "///"),
+                line => self.line(indent, &format!("/// {}", line.replace('\r', ""))),
+            }
+        }
+        Ok(())
+    }
+
+    fn rust_type(&self, type_def: &ScSpecTypeDef) -> Result<String, StellarSdkError> {
+        let support = &self.support;
+        Ok(match type_def {
+            ScSpecTypeDef::ScSpecTypeBool => "bool".to_string(),
+            ScSpecTypeDef::ScSpecTypeVoid => "()".to_string(),
+            ScSpecTypeDef::ScSpecTypeU32 => "u32".to_string(),
+            ScSpecTypeDef::ScSpecTypeI32 => "i32".to_string(),
+            ScSpecTypeDef::ScSpecTypeU64 => "u64".to_string(),
+            ScSpecTypeDef::ScSpecTypeI64 => "i64".to_string(),
+            ScSpecTypeDef::ScSpecTypeU128 => "u128".to_string(),
+            ScSpecTypeDef::ScSpecTypeI128 => "i128".to_string(),
+            ScSpecTypeDef::ScSpecTypeU256 => format!("{}::UInt256Parts", support),
+            ScSpecTypeDef::ScSpecTypeI256 => format!("{}::Int256Parts", support),
+            ScSpecTypeDef::ScSpecTypeBytes => format!("{0}::AsScBytes<{0}::Vec<u8>>", support),
+            ScSpecTypeDef::ScSpecTypeBytesN(bytes_n) => format!("[u8; {}]", bytes_n.n),
+            ScSpecTypeDef::ScSpecTypeString => format!("{}::String", support),
+            ScSpecTypeDef::ScSpecTypeSymbol => format!("{0}::AsScSymbol<{0}::Vec<u8>>", support),
+            ScSpecTypeDef::ScSpecTypeAddress | ScSpecTypeDef::ScSpecTypeMuxedAddress =>
+                format!("{}::ScAddress", support),
+            ScSpecTypeDef::ScSpecTypeOption(option) => format!("Option<{}>", self.rust_type(&option.value_type)?),
+            ScSpecTypeDef::ScSpecTypeVec(vec) => format!("{}::Vec<{}>", support, self.rust_type(&vec.element_type)?),
+            ScSpecTypeDef::ScSpecTypeMap(map) if is_ord(&map.key_type) => format!(
+                "{}::BTreeMap<{}, {}>",
+                support,
+                self.rust_type(&map.key_type)?,
+                self.rust_type(&map.value_type)?
+            ),
+            ScSpecTypeDef::ScSpecTypeTuple(tuple) if tuple.value_types.len() <= MAX_TUPLE_LENGTH => {
+                let types = tuple
+                    .value_types
+                    .get_vec()
+                    .iter()
+                    .map(|type_def| self.rust_type(type_def))
+                    .collect::<Result<Vec<_>, _>>()?;
+                match types.len() {
+                    1 => format!("({},)", types[0]),
+                    _ => format!("({})", types.join(", ")),
+                }
+            },
+            ScSpecTypeDef::ScSpecTypeUdt(udt) => {
+                self.spec.get_type(udt.name.get_vec())?;
+                to_identifier(udt.name.get_vec())?
+            },
+            ScSpecTypeDef::ScSpecTypeVal |
+            ScSpecTypeDef::ScSpecTypeError |
+            ScSpecTypeDef::ScSpecTypeTimepoint |
+            ScSpecTypeDef::ScSpecTypeDuration |
+            ScSpecTypeDef::ScSpecTypeResult(_) |
+            ScSpecTypeDef::ScSpecTypeMap(_) |
+            ScSpecTypeDef::ScSpecTypeTuple(_) => format!("{}::ScVal", support),
+        })
+    }
+
+    fn generate_conversions(&mut self, name: &str, into_sc_val: &[String], try_from_sc_val: &[String]) {
+        let support = self.support.clone();
+        self.line(0, &format!("impl {}::IntoScVal for {} {{", support, name));
+        self.line(1, &format!("fn into_sc_val(self) -> Result<{0}::ScVal, {0}::StellarSdkError> {{", support));
+        into_sc_val.iter().for_each(|line| self.line(2, line));
+        self.line(1, "}");
+        self.line(0, "}");
+        self.line(0, "");
+        self.line(0, &format!("impl {}::TryFromScVal for {} {{", support, name));
+        self.line(
+            1,
+            &format!("fn try_from_sc_val(value: {0}::ScVal) -> Result<Self, {0}::StellarSdkError> {{", support),
+        );
+        try_from_sc_val.iter().for_each(|line| self.line(2, line));
+        self.line(1, "}");
+        self.line(0, "}");
+        self.line(0, "");
+    }
+
+    fn generate_udt(&mut self, entry: &ScSpecEntry) -> Result<(), StellarSdkError> {
+        let support = self.support.clone();
+        let try_from = format!("{}::TryFromScVal::try_from_sc_val", support);
+        let into = format!("{}::IntoScVal::into_sc_val", support);
+
+        match entry {
+            ScSpecEntry::ScSpecEntryUdtStructV0(udt) => {
+                let name = to_identifier(udt.name.get_vec())?;
+                let fields = udt.fields.get_vec();
+                self.doc(0, &udt.doc)?;
+                self.line(0, "#[derive(Debug, Clone, PartialEq, Eq)]");
+
+                if is_tuple_struct(fields.iter().map(|field| field.name.get_vec())) {
+                    let types = fields
+                        .iter()
+                        .map(|field| Ok(format!("pub {}", self.rust_type(&field.type_)?)))
+                        .collect::<Result<Vec<_>, StellarSdkError>>()?;
+                    let variables: Vec<String> = (0..fields.len()).map(|index| format!("v{}", index)).collect();
+                    self.line(0, &format!("pub struct {}({});", name, types.join(", ")));
+                    self.line(0, "");
+
+                    let values: Vec<String> =
+                        (0..fields.len()).map(|index| format!("{}(self.{})?", into, index)).collect();
+                    let elements: Vec<String> = variables
+                        .iter()
+                        .map(|variable| format!("{}({})?", try_from, variable))
+                        .collect();
+                    self.generate_conversions(
+                        &name,
+                        &[format!("{}({}::Vec::from([{}]))", into, support, values.join(", "))],
+                        &[
+                            format!("let [{}] = {}::get_vec_elements(value)?;", variables.join(", "), support),
+                            format!("Ok({}({}))", name, elements.join(", ")),
+                        ],
+                    );
+                    return Ok(())
+                }
+
+                self.line(0, &format!("pub struct {} {{", name));
+                for field in fields {
+                    self.doc(1, &field.doc)?;
+                    let line =
+                        format!("pub {}: {},", to_identifier(field.name.get_vec())?, self.rust_type(&field.type_)?);
+                    self.line(1, &line);
+                }
+                self.line(0, "}");
+                self.line(0, "");
+
+                let mut into_sc_val = vec![format!("{}::new_struct({}::Vec::from([", support, support)];
+                let mut names = Vec::new();
+                let mut variables = Vec::new();
+                let mut values = Vec::new();
+                for field in fields {
+                    let variable = to_identifier(field.name.get_vec())?;
+                    into_sc_val.push(format!("    (\"{}\", {}(self.{})?),", to_literal(&field.name)?, into, variable));
+                    names.push(format!("\"{}\"", to_literal(&field.name)?));
+                    values.push(format!("    {0}: {1}({0})?,", variable, try_from));
+                    variables.push(variable);
+                }
+                into_sc_val.push("]))".to_string());
+
+                let mut try_from_sc_val = vec![
+                    format!(
+                        "let [{}] = {}::get_struct_fields(value, [{}])?;",
+                        variables.join(", "),
+                        support,
+                        names.join(", ")
+                    ),
+                    format!("Ok({} {{", name),
+                ];
+                try_from_sc_val.extend(values);
+                try_from_sc_val.push("})".to_string());
+                self.generate_conversions(&name, &into_sc_val, &try_from_sc_val);
+            },
+            ScSpecEntry::ScSpecEntryUdtUnionV0(udt) => {
+                let name = to_identifier(udt.name.get_vec())?;
+                self.doc(0, &udt.doc)?;
+                self.line(0, "#[derive(Debug, Clone, PartialEq, Eq)]");
+                self.line(0, &format!("pub enum {} {{", name));
+
+                let mut into_sc_val = vec!["match self {".to_string()];
+                let mut try_from_sc_val = vec![
+                    format!("let (case, values) = {}::get_union_case(value)?;", support),
+                    "match case.as_slice() {".to_string(),
+                ];
+                for case in udt.cases.get_vec() {
+                    let (doc, case_name, types) = match case {
+                        ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseVoidV0(case) => (&case.doc, &case.name, &[][..]),
+                        ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseTupleV0(case) =>
+                            (&case.doc, &case.name, &case.type_.get_vec()[..]),
+                    };
+                    let case_identifier = to_identifier(case_name.get_vec())?;
+                    let case_literal = to_literal(case_name)?;
+                    let variables: Vec<String> = (0..types.len()).map(|index| format!("v{}", index)).collect();
+                    let values: Vec<String> =
+                        variables.iter().map(|variable| format!("{}({})?", into, variable)).collect();
+                    let elements: Vec<String> = variables
+                        .iter()
+                        .map(|variable| format!("{}({})?", try_from, variable))
+                        .collect();
+
+                    self.doc(1, doc)?;
+                    let (pattern, constructor) = match types.is_empty() {
+                        true => {
+                            self.line(1, &format!("{},", case_identifier));
+                            (format!("{}::{}", name, case_identifier), format!("{}::{}", name, case_identifier))
+                        },
+                        false => {
+                            let types = types
+                                .iter()
+                                .map(|type_def| self.rust_type(type_def))
+                                .collect::<Result<Vec<_>, _>>()?;
+                            self.line(1, &format!("{}({}),", case_identifier, types.join(", ")));
+                            (
+                                format!("{}::{}({})", name, case_identifier, variables.join(", ")),
+                                format!("{}::{}({})", name, case_identifier, elements.join(", ")),
+                            )
+                        },
+                    };
+
+                    into_sc_val.push(format!(
+                        "    {} => {}::new_union_case(\"{}\", {}::Vec::from([{}])),",
+                        pattern,
+                        support,
+                        case_literal,
+                        support,
+                        values.join(", ")
+                    ));
+                    try_from_sc_val.extend([
+                        format!("    b\"{}\" => {{", case_literal),
+                        format!("        let [{}] = {}::into_array(values)?;", variables.join(", "), support),
+                        format!("        Ok({})", constructor),
+                        "    },".to_string(),
+                    ]);
+                }
+                self.line(0, "}");
+                self.line(0, "");
+
+                into_sc_val.push("}".to_string());
+                try_from_sc_val.push(format!("    _ => Err({}::StellarSdkError::InvalidScVal),", support));
+                try_from_sc_val.push("}".to_string());
+                self.generate_conversions(&name, &into_sc_val, &try_from_sc_val);
+            },
+            ScSpecEntry::ScSpecEntryUdtEnumV0(udt) => {
+                let cases: Vec<_> = udt
+                    .cases
+                    .get_vec()
+                    .iter()
+                    .map(|case| (&case.doc, &case.name, case.value))
+                    .collect();
+                let name = to_identifier(udt.name.get_vec())?;
+                self.doc(0, &udt.doc)?;
+                self.generate_enum(&name, &cases, |value| format!("{}::ScVal::ScvU32({})", support, value))?;
+            },
+            ScSpecEntry::ScSpecEntryUdtErrorEnumV0(udt) => {
+                let cases: Vec<_> = udt
+                    .cases
+                    .get_vec()
+                    .iter()
+                    .map(|case| (&case.doc, &case.name, case.value))
+                    .collect();
+                let name = to_identifier(udt.name.get_vec())?;
+                self.doc(0, &udt.doc)?;
+                self.generate_enum(&name, &cases, |value| {
+                    format!("{0}::ScVal::ScvError({0}::ScError::SceContract({1}))", support, value)
+                })?;
+            },
+            ScSpecEntry::ScSpecEntryFunctionV0(_) | ScSpecEntry::ScSpecEntryEventV0(_) => (),
+        }
+        Ok(())
+    }
+
+    /// Generate an enum of `u32` values, `to_sc_val` returns the expression or pattern of the
+    /// `ScVal` of a value
+    fn generate_enum<const D: i32, const N: i32>(
+        &mut self,
+        name: &str,
+        cases: &[(&LimitedString<D>, &LimitedString<N>, u32)],
+        to_sc_val: impl Fn(&str) -> String,
+    ) -> Result<(), StellarSdkError> {
+        self.line(0, "#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]");
+        self.line(0, "#[repr(u32)]");
+        self.line(0, &format!("pub enum {} {{", name));
+        let mut into_sc_val = vec!["let value = match self {".to_string()];
+        let mut try_from_sc_val = vec!["match value {".to_string()];
+        for (doc, case_name, value) in cases {
+            let case_identifier = to_identifier(case_name.get_vec())?;
+            self.doc(1, doc)?;
+            self.line(1, &format!("{} = {},", case_identifier, value));
+            into_sc_val.push(format!("    {}::{} => {},", name, case_identifier, value));
+            try_from_sc_val.push(format!(
+                "    {} => Ok({}::{}),",
+                to_sc_val(&value.to_string()),
+                name,
+                case_identifier
+            ));
+        }
+        self.line(0, "}");
+        self.line(0, "");
+
+        into_sc_val.push("};".to_string());
+        into_sc_val.push(format!("Ok({})", to_sc_val("value")));
+        try_from_sc_val.push(format!("    _ => Err({}::StellarSdkError::InvalidScVal),", self.support));
+        try_from_sc_val.push("}".to_string());
+        self.generate_conversions(name, &into_sc_val, &try_from_sc_val);
+        Ok(())
+    }
+
+    fn generate_function(&mut self, function: &ScSpecFunctionV0) -> Result<(), StellarSdkError> {
+        let support = self.support.clone();
+        let name = to_identifier(function.name.get_vec())?;
+        let literal = to_literal(&function.name)?;
+
+        let mut parameters = vec!["&self".to_string()];
+        let mut arguments = Vec::new();
+        for input in function.inputs.get_vec() {
+            let input_name = to_identifier(input.name.get_vec())?;
+            parameters.push(format!("{}: {}", input_name, self.rust_type(&input.type_)?));
+            arguments.push(format!("{}::IntoScVal::into_sc_val({})?", support, input_name));
+        }
+
+        self.doc(1, &function.doc)?;
+        self.line(
+            1,
+            &format!(
+                "pub fn {}({}) -> Result<{}::Operation, {}::StellarSdkError> {{",
+                name,
+                parameters.join(", "),
+                support,
+                support
+            ),
+        );
+        self.line(2, &format!("let args = {}::Vec::from([{}]);", support, arguments.join(", ")));
+        self.line(
+            2,
+            &format!(
+                "{}::Operation::new_invoke_contract(self.contract_address.clone(), \"{}\", args)",
+                support, literal
+            ),
+        );
+        self.line(1, "}");
+        self.line(0, "");
+
+        let (return_type, decode) = match function.outputs.get_vec().first() {
+            Some(ScSpecTypeDef::ScSpecTypeResult(result)) => (
+                format!("Result<{}, {}>", self.rust_type(&result.ok_type)?, self.rust_type(&result.error_type)?),
+                format!("{}::decode_result(value)", support),
+            ),
+            Some(output) => (self.rust_type(output)?, format!("{}::TryFromScVal::try_from_sc_val(value)", support)),
+            None => ("()".to_string(), format!("{}::TryFromScVal::try_from_sc_val(value)", support)),
+        };
+        self.line(1, &format!("/// Decode the return value of `{}`", literal));
+        self.line(
+            1,
+            &format!(
+                "pub fn decode_{}_result(value: {}::ScVal) -> Result<{}, {}::StellarSdkError> {{",
+                literal, support, return_type, support
+            ),
+        );
+        self.line(2, &decode);
+        self.line(1, "}");
+        Ok(())
+    }
+}
+
+/// Whether the Rust type of `type_def` implements `Ord`, as needed for the keys of a `BTreeMap`
+fn is_ord(type_def: &ScSpecTypeDef) -> bool {
+    matches!(
+        type_def,
+        ScSpecTypeDef::ScSpecTypeBool |
+            ScSpecTypeDef::ScSpecTypeU32 |
+            ScSpecTypeDef::ScSpecTypeI32 |
+            ScSpecTypeDef::ScSpecTypeU64 |
+            ScSpecTypeDef::ScSpecTypeI64 |
+            ScSpecTypeDef::ScSpecTypeU128 |
+            ScSpecTypeDef::ScSpecTypeI128 |
+            ScSpecTypeDef::ScSpecTypeBytes |
+            ScSpecTypeDef::ScSpecTypeBytesN(_) |
+            ScSpecTypeDef::ScSpecTypeString |
+            ScSpecTypeDef::ScSpecTypeSymbol
+    )
+}
+
+impl ContractSpec {
+    /// Generate Rust bindings for the contract
+    ///
+    /// `client_name` is the name of the generated client, `sdk_path` the path of this crate
+    /// as seen from the code that includes the bindings, usually `substrate_stellar_sdk`.
+    /// Functions whose names start with `__`, like constructors, are not part of the client.
+    pub fn generate_rust_bindings(&self, client_name: &str, sdk_path: &str) -> Result<String, StellarSdkError> {
+        let client_name = to_identifier(client_name.as_bytes())?;
+        let mut generator = Generator { spec: self, support: "contract_bindings".to_string(), code: String::new() };
+        generator.line(0, "// Generated from the contract spec, do not edit");
+        generator.line(0, "");
+        generator.line(0, &format!("use {}::__contract_bindings as contract_bindings;", sdk_path));
+        generator.line(0, "");
+
+        for entry in self.get_entries() {
+            generator.generate_udt(entry)?;
+        }
+
+        let support = generator.support.clone();
+        generator.line(0, "/// Builds the operations that invoke the functions of the contract");
+        generator.line(0, "#[derive(Debug, Clone, PartialEq, Eq)]");
+        generator.line(0, &format!("pub struct {} {{", client_name));
+        generator.line(1, &format!("pub contract_address: {}::ScAddress,", support));
+        generator.line(0, "}");
+        generator.line(0, "");
+        generator.line(0, &format!("impl {} {{", client_name));
+        let functions = self
+            .get_functions()
+            .filter(|function| !function.name.get_vec().starts_with(b"__"));
+        for (index, function) in functions.enumerate() {
+            if index > 0 {
+                generator.line(0, "");
+            }
+            generator.generate_function(function)?;
+        }
+        generator.line(0, "}");
+
+        Ok(generator.code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_std::boxed::Box;
+
+    use super::to_identifier;
+    use crate::{
+        compound_types::LimitedVarArray,
+        types::{
+            ScSpecEntry, ScSpecFunctionV0, ScSpecTypeBytesN, ScSpecTypeDef, ScSpecTypeMap, ScSpecTypeTuple,
+            ScSpecUdtEnumCaseV0, ScSpecUdtEnumV0, ScSpecUdtStructV0,
+        },
+        xdr::impls::contract_spec::tests::{field, input, string, udt},
+        ContractSpec, StellarSdkError,
+    };
+
+    /// The bindings of the spec of `contract_spec::tests`, generated with the SDK path `crate`
+    mod bindings {
+        include!("contract_bindings_test_data.rs");
+    }
+
+    /// The bindings of the spec of `shapes_entries`, generated with the SDK path `crate`
+    mod shapes_bindings {
+        include!("contract_bindings_shapes_test_data.rs");
+    }
+
+    fn map(key_type: ScSpecTypeDef, value_type: ScSpecTypeDef) -> ScSpecTypeDef {
+        ScSpecTypeDef::ScSpecTypeMap(Box::new(ScSpecTypeMap { key_type: Box::new(key_type), value_type }))
+    }
+
+    fn tuple(value_types: Vec<ScSpecTypeDef>) -> ScSpecTypeDef {
+        ScSpecTypeDef::ScSpecTypeTuple(Box::new(ScSpecTypeTuple {
+            value_types: Box::new(LimitedVarArray::new(value_types).unwrap()),
+        }))
+    }
+
+    fn bytes_n(n: u32) -> ScSpecTypeDef {
+        ScSpecTypeDef::ScSpecTypeBytesN(Box::new(ScSpecTypeBytesN { n: Box::new(n) }))
+    }
+
+    /// A spec with the types that `contract_spec::tests::entries` does not have: an enum, a
+    /// tuple struct, maps, tuples and fixed length bytes
+    fn shapes_entries() -> Vec<ScSpecEntry> {
+        let case = |name: &str, value: u32| ScSpecUdtEnumCaseV0 { doc: string(""), name: string(name), value };
+        vec![
+            ScSpecEntry::ScSpecEntryUdtEnumV0(ScSpecUdtEnumV0 {
+                doc: string("The state of an account"),
+                lib: string(""),
+                name: string("Status"),
+                cases: LimitedVarArray::new(vec![case("Active", 1), case("Frozen", 2)]).unwrap(),
+            }),
+            ScSpecEntry::ScSpecEntryUdtStructV0(ScSpecUdtStructV0 {
+                doc: string(""),
+                lib: string(""),
+                name: string("Pair"),
+                fields: LimitedVarArray::new(vec![
+                    field("0", ScSpecTypeDef::ScSpecTypeU32),
+                    field("1", ScSpecTypeDef::ScSpecTypeI64),
+                ])
+                .unwrap(),
+            }),
+            ScSpecEntry::ScSpecEntryFunctionV0(ScSpecFunctionV0 {
+                doc: string(""),
+                name: string("configure"),
+                inputs: LimitedVarArray::new(vec![
+                    input("status", udt("Status")),
+                    input("pair", udt("Pair")),
+                    input("limits", map(ScSpecTypeDef::ScSpecTypeSymbol, ScSpecTypeDef::ScSpecTypeU128)),
+                    input("entry", tuple(vec![ScSpecTypeDef::ScSpecTypeU32, ScSpecTypeDef::ScSpecTypeBytes])),
+                    input("flag", tuple(vec![ScSpecTypeDef::ScSpecTypeBool])),
+                    input("hash", bytes_n(32)),
+                ])
+                .unwrap(),
+                outputs: LimitedVarArray::new(vec![tuple(vec![
+                    udt("Status"),
+                    map(ScSpecTypeDef::ScSpecTypeU32, bytes_n(4)),
+                ])])
+                .unwrap(),
+            }),
+        ]
+    }
+
+    #[test]
+    fn identifiers() {
+        assert_eq!(to_identifier(b"amount"), Ok("amount".into()));
+        assert_eq!(to_identifier(b"type"), Ok("r#type".into()));
+        assert_eq!(to_identifier(b"self"), Ok("self_".into()));
+        assert_eq!(to_identifier(b"0"), Err(StellarSdkError::InvalidContractSpec));
+        assert_eq!(to_identifier(b"a}"), Err(StellarSdkError::InvalidContractSpec));
+    }
+
+    #[test]
+    fn generate_rust_bindings() {
+        let spec = ContractSpec::new(crate::xdr::impls::contract_spec::tests::entries());
+        assert_eq!(
+            spec.generate_rust_bindings("PaymentClient", "crate").unwrap(),
+            include_str!("contract_bindings_test_data.rs")
+        );
+
+        let entries: Vec<ScSpecEntry> = Vec::new();
+        assert_eq!(
+            ContractSpec::new(entries).generate_rust_bindings("Client;", "crate"),
+            Err(StellarSdkError::InvalidContractSpec)
+        );
+    }
+
+    #[test]
+    fn generate_shapes_bindings() {
+        let spec = ContractSpec::new(shapes_entries());
+        assert_eq!(
+            spec.generate_rust_bindings("ShapesClient", "crate").unwrap(),
+            include_str!("contract_bindings_shapes_test_data.rs")
+        );
+    }
+
+    #[test]
+    fn generated_client() {
+        use super::support::{AsScBytes, IntoScVal, ScAddress, ScError, ScVal, TryFromScVal};
+        use bindings::{Asset, Error, Payment, PaymentClient};
+
+        const ACCOUNT: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        const CONTRACT: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+
+        let spec = ContractSpec::new(crate::xdr::impls::contract_spec::tests::entries());
+        let payment = Payment { to: ScAddress::from_encoding(ACCOUNT).unwrap(), amount: -5, memo: Some("memo".into()) };
+        let asset = Asset::Token(ScAddress::from_encoding(CONTRACT).unwrap());
+
+        let client = PaymentClient { contract_address: ScAddress::from_encoding(CONTRACT).unwrap() };
+        let operation = client.pay(asset.clone(), vec![payment.clone()]).unwrap();
+        let arguments = crate::SpecValue::Array(vec![
+            crate::SpecValue::object(vec![(
+                "Token",
+                crate::SpecValue::Array(vec![crate::SpecValue::string(CONTRACT)]),
+            )]),
+            crate::SpecValue::Array(vec![crate::SpecValue::object(vec![
+                ("to", crate::SpecValue::string(ACCOUNT)),
+                ("amount", crate::SpecValue::Integer(-5)),
+                ("memo", crate::SpecValue::string("memo")),
+            ])]),
+        ]);
+        let expected = crate::types::Operation::new_invoke_contract(
+            CONTRACT,
+            "pay",
+            spec.encode_arguments("pay", &arguments).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(operation, expected);
+
+        assert_eq!(Payment::try_from_sc_val(payment.clone().into_sc_val().unwrap()), Ok(payment));
+        assert_eq!(Asset::try_from_sc_val(asset.clone().into_sc_val().unwrap()), Ok(asset));
+        assert_eq!(Asset::try_from_sc_val(Asset::Native.into_sc_val().unwrap()), Ok(Asset::Native));
+        assert!(Asset::try_from_sc_val(AsScBytes(b"Native").into_sc_val().unwrap()).is_err());
+
+        let error = ScVal::ScvError(ScError::SceContract(1));
+        assert_eq!(PaymentClient::decode_pay_result(error), Ok(Err(Error::InsufficientBalance)));
+        assert_eq!(
+            PaymentClient::decode_pay_result(ScVal::ScvError(ScError::SceContract(2))),
+            Err(StellarSdkError::InvalidScVal)
+        );
+    }
+
+    #[test]
+    fn generated_shapes_client() {
+        use super::support::{AsScBytes, AsScSymbol, BTreeMap, IntoScVal, ScAddress, ScVal, TryFromScVal};
+        use crate::SpecValue;
+        use shapes_bindings::{Pair, ShapesClient, Status};
+
+        const CONTRACT: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+
+        let spec = ContractSpec::new(shapes_entries());
+        let client = ShapesClient { contract_address: ScAddress::from_encoding(CONTRACT).unwrap() };
+        let limits = BTreeMap::from([(AsScSymbol(b"daily".to_vec()), u128::MAX), (AsScSymbol(b"hourly".to_vec()), 7)]);
+        let operation = client
+            .configure(Status::Frozen, Pair(3, -4), limits.clone(), (5, AsScBytes(vec![0xab])), (true,), [0x11; 32])
+            .unwrap();
+
+        let arguments = SpecValue::Array(vec![
+            SpecValue::string("Frozen"),
+            SpecValue::Array(vec![SpecValue::Integer(3), SpecValue::Integer(-4)]),
+            SpecValue::object(vec![
+                ("daily", SpecValue::string("340282366920938463463374607431768211455")),
+                ("hourly", SpecValue::Integer(7)),
+            ]),
+            SpecValue::Array(vec![SpecValue::Integer(5), SpecValue::string("ab")]),
+            SpecValue::Array(vec![SpecValue::Bool(true)]),
+            SpecValue::String("11".repeat(32)),
+        ]);
+        let expected = crate::types::Operation::new_invoke_contract(
+            CONTRACT,
+            "configure",
+            spec.encode_arguments("configure", &arguments).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(operation, expected);
+
+        assert_eq!(Status::try_from_sc_val(Status::Active.into_sc_val().unwrap()), Ok(Status::Active));
+        assert_eq!(Status::try_from_sc_val(ScVal::ScvU32(3)), Err(StellarSdkError::InvalidScVal));
+        assert_eq!(Pair::try_from_sc_val(Pair(3, -4).into_sc_val().unwrap()), Ok(Pair(3, -4)));
+        assert_eq!(BTreeMap::try_from_sc_val(limits.clone().into_sc_val().unwrap()), Ok(limits));
+
+        let result = SpecValue::Array(vec![
+            SpecValue::Integer(1),
+            SpecValue::Array(vec![SpecValue::Array(vec![SpecValue::Integer(9), SpecValue::string("01020304")])]),
+        ]);
+        let result = spec
+            .encode_value(&spec.get_function("configure").unwrap().outputs.get_vec()[0], &result)
+            .unwrap();
+        assert_eq!(
+            ShapesClient::decode_configure_result(result),
+            Ok((Status::Active, BTreeMap::from([(9, [1, 2, 3, 4])])))
+        );
+    }
+}
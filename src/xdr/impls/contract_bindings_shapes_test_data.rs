@@ -0,0 +1,65 @@
+// Generated from the contract spec, do not edit
+
+use crate::__contract_bindings as contract_bindings;
+
+/// The state of an account
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Status {
+    Active = 1,
+    Frozen = 2,
+}
+
+impl contract_bindings::IntoScVal for Status {
+    fn into_sc_val(self) -> Result<contract_bindings::ScVal, contract_bindings::StellarSdkError> {
+        let value = match self {
+            Status::Active => 1,
+            Status::Frozen => 2,
+        };
+        Ok(contract_bindings::ScVal::ScvU32(value))
+    }
+}
+
+impl contract_bindings::TryFromScVal for Status {
+    fn try_from_sc_val(value: contract_bindings::ScVal) -> Result<Self, contract_bindings::StellarSdkError> {
+        match value {
+            contract_bindings::ScVal::ScvU32(1) => Ok(Status::Active),
+            contract_bindings::ScVal::ScvU32(2) => Ok(Status::Frozen),
+            _ => Err(contract_bindings::StellarSdkError::InvalidScVal),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pair(pub u32, pub i64);
+
+impl contract_bindings::IntoScVal for Pair {
+    fn into_sc_val(self) -> Result<contract_bindings::ScVal, contract_bindings::StellarSdkError> {
+        contract_bindings::IntoScVal::into_sc_val(contract_bindings::Vec::from([contract_bindings::IntoScVal::into_sc_val(self.0)?, contract_bindings::IntoScVal::into_sc_val(self.1)?]))
+    }
+}
+
+impl contract_bindings::TryFromScVal for Pair {
+    fn try_from_sc_val(value: contract_bindings::ScVal) -> Result<Self, contract_bindings::StellarSdkError> {
+        let [v0, v1] = contract_bindings::get_vec_elements(value)?;
+        Ok(Pair(contract_bindings::TryFromScVal::try_from_sc_val(v0)?, contract_bindings::TryFromScVal::try_from_sc_val(v1)?))
+    }
+}
+
+/// Builds the operations that invoke the functions of the contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShapesClient {
+    pub contract_address: contract_bindings::ScAddress,
+}
+
+impl ShapesClient {
+    pub fn configure(&self, status: Status, pair: Pair, limits: contract_bindings::BTreeMap<contract_bindings::AsScSymbol<contract_bindings::Vec<u8>>, u128>, entry: (u32, contract_bindings::AsScBytes<contract_bindings::Vec<u8>>), flag: (bool,), hash: [u8; 32]) -> Result<contract_bindings::Operation, contract_bindings::StellarSdkError> {
+        let args = contract_bindings::Vec::from([contract_bindings::IntoScVal::into_sc_val(status)?, contract_bindings::IntoScVal::into_sc_val(pair)?, contract_bindings::IntoScVal::into_sc_val(limits)?, contract_bindings::IntoScVal::into_sc_val(entry)?, contract_bindings::IntoScVal::into_sc_val(flag)?, contract_bindings::IntoScVal::into_sc_val(hash)?]);
+        contract_bindings::Operation::new_invoke_contract(self.contract_address.clone(), "configure", args)
+    }
+
+    /// Decode the return value of `configure`
+    pub fn decode_configure_result(value: contract_bindings::ScVal) -> Result<(Status, contract_bindings::BTreeMap<u32, [u8; 4]>), contract_bindings::StellarSdkError> {
+        contract_bindings::TryFromScVal::try_from_sc_val(value)
+    }
+}
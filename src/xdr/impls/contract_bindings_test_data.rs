@@ -0,0 +1,105 @@
+// Generated from the contract spec, do not edit
+
+use crate::__contract_bindings as contract_bindings;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Payment {
+    pub to: contract_bindings::ScAddress,
+    pub amount: i128,
+    pub memo: Option<contract_bindings::String>,
+}
+
+impl contract_bindings::IntoScVal for Payment {
+    fn into_sc_val(self) -> Result<contract_bindings::ScVal, contract_bindings::StellarSdkError> {
+        contract_bindings::new_struct(contract_bindings::Vec::from([
+            ("to", contract_bindings::IntoScVal::into_sc_val(self.to)?),
+            ("amount", contract_bindings::IntoScVal::into_sc_val(self.amount)?),
+            ("memo", contract_bindings::IntoScVal::into_sc_val(self.memo)?),
+        ]))
+    }
+}
+
+impl contract_bindings::TryFromScVal for Payment {
+    fn try_from_sc_val(value: contract_bindings::ScVal) -> Result<Self, contract_bindings::StellarSdkError> {
+        let [to, amount, memo] = contract_bindings::get_struct_fields(value, ["to", "amount", "memo"])?;
+        Ok(Payment {
+            to: contract_bindings::TryFromScVal::try_from_sc_val(to)?,
+            amount: contract_bindings::TryFromScVal::try_from_sc_val(amount)?,
+            memo: contract_bindings::TryFromScVal::try_from_sc_val(memo)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Asset {
+    Native,
+    Token(contract_bindings::ScAddress),
+}
+
+impl contract_bindings::IntoScVal for Asset {
+    fn into_sc_val(self) -> Result<contract_bindings::ScVal, contract_bindings::StellarSdkError> {
+        match self {
+            Asset::Native => contract_bindings::new_union_case("Native", contract_bindings::Vec::from([])),
+            Asset::Token(v0) => contract_bindings::new_union_case("Token", contract_bindings::Vec::from([contract_bindings::IntoScVal::into_sc_val(v0)?])),
+        }
+    }
+}
+
+impl contract_bindings::TryFromScVal for Asset {
+    fn try_from_sc_val(value: contract_bindings::ScVal) -> Result<Self, contract_bindings::StellarSdkError> {
+        let (case, values) = contract_bindings::get_union_case(value)?;
+        match case.as_slice() {
+            b"Native" => {
+                let [] = contract_bindings::into_array(values)?;
+                Ok(Asset::Native)
+            },
+            b"Token" => {
+                let [v0] = contract_bindings::into_array(values)?;
+                Ok(Asset::Token(contract_bindings::TryFromScVal::try_from_sc_val(v0)?))
+            },
+            _ => Err(contract_bindings::StellarSdkError::InvalidScVal),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum Error {
+    InsufficientBalance = 1,
+}
+
+impl contract_bindings::IntoScVal for Error {
+    fn into_sc_val(self) -> Result<contract_bindings::ScVal, contract_bindings::StellarSdkError> {
+        let value = match self {
+            Error::InsufficientBalance => 1,
+        };
+        Ok(contract_bindings::ScVal::ScvError(contract_bindings::ScError::SceContract(value)))
+    }
+}
+
+impl contract_bindings::TryFromScVal for Error {
+    fn try_from_sc_val(value: contract_bindings::ScVal) -> Result<Self, contract_bindings::StellarSdkError> {
+        match value {
+            contract_bindings::ScVal::ScvError(contract_bindings::ScError::SceContract(1)) => Ok(Error::InsufficientBalance),
+            _ => Err(contract_bindings::StellarSdkError::InvalidScVal),
+        }
+    }
+}
+
+/// Builds the operations that invoke the functions of the contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentClient {
+    pub contract_address: contract_bindings::ScAddress,
+}
+
+impl PaymentClient {
+    pub fn pay(&self, asset: Asset, payments: contract_bindings::Vec<Payment>) -> Result<contract_bindings::Operation, contract_bindings::StellarSdkError> {
+        let args = contract_bindings::Vec::from([contract_bindings::IntoScVal::into_sc_val(asset)?, contract_bindings::IntoScVal::into_sc_val(payments)?]);
+        contract_bindings::Operation::new_invoke_contract(self.contract_address.clone(), "pay", args)
+    }
+
+    /// Decode the return value of `pay`
+    pub fn decode_pay_result(value: contract_bindings::ScVal) -> Result<Result<contract_bindings::UInt256Parts, Error>, contract_bindings::StellarSdkError> {
+        contract_bindings::decode_result(value)
+    }
+}
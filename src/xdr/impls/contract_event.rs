@@ -0,0 +1,360 @@
+//! Contract events and the token events of SEP-41
+//!
+//! Events are found in `TransactionMeta::V3` (all events of the single Soroban operation in
+//! `SorobanTransactionMeta`) and in `TransactionMeta::V4` (per operation, plus transaction
+//! level events such as fee events). Topics and data are decoded with `TryFromScVal`.
+
+use sp_std::vec::Vec;
+
+use crate::{
+    compound_types::LimitedVarArray,
+    lib::String,
+    types::{
+        ContractEvent, ContractEventBody, ContractEventType, ContractEventV0, ContractId, DiagnosticEvent, ScAddress,
+        ScVal, TransactionEvent, TransactionMeta,
+    },
+    AsScSymbol, StellarSdkError, TryFromScVal,
+};
+
+/// The name of a token event, the first topic
+const TRANSFER: &[u8] = b"transfer";
+const MINT: &[u8] = b"mint";
+const BURN: &[u8] = b"burn";
+const CLAWBACK: &[u8] = b"clawback";
+const APPROVE: &[u8] = b"approve";
+const SET_ADMIN: &[u8] = b"set_admin";
+const SET_AUTHORIZED: &[u8] = b"set_authorized";
+
+/// A token event as specified by SEP-41
+///
+/// Before protocol 23 the Stellar Asset Contract emitted the admin as second topic of
+/// `mint`, `clawback` and `set_authorized` events; `admin` is `None` for events without it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenEvent {
+    /// The contract that emitted the event; any contract can emit token events, so check
+    /// this before trusting the event
+    pub contract_id: Option<ContractId>,
+    /// The SEP-11 name of the asset (`native` or `CODE:ISSUER`), only emitted by the Stellar
+    /// Asset Contract
+    pub asset: Option<String>,
+    pub kind: TokenEventKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenEventKind {
+    Transfer { from: ScAddress, to: ScAddress, amount: i128, to_muxed_id: Option<ScVal> },
+    Mint { admin: Option<ScAddress>, to: ScAddress, amount: i128, to_muxed_id: Option<ScVal> },
+    Burn { from: ScAddress, amount: i128 },
+    Clawback { admin: Option<ScAddress>, from: ScAddress, amount: i128 },
+    Approve { from: ScAddress, spender: ScAddress, amount: i128, expiration_ledger: u32 },
+    SetAdmin { admin: ScAddress, new_admin: ScAddress },
+    SetAuthorized { admin: Option<ScAddress>, id: ScAddress, authorize: bool },
+}
+
+/// Decode the data of `transfer` and `mint` events: the amount or, if the recipient is a
+/// muxed account, a map of the amount and the muxed id
+fn decode_amount(data: &ScVal) -> Option<(i128, Option<ScVal>)> {
+    match data {
+        ScVal::ScvMap(Some(entries)) => {
+            let get_field = |name: &[u8]| {
+                entries.get_vec().iter().find_map(|entry| match &entry.key {
+                    ScVal::ScvSymbol(key) if key.get_vec() == name => Some(entry.val.clone()),
+                    _ => None,
+                })
+            };
+            if entries.len() != 2 {
+                return None
+            }
+            Some((i128::try_from_sc_val(get_field(b"amount")?).ok()?, Some(get_field(b"to_muxed_id")?)))
+        },
+        data => Some((i128::try_from_sc_val(data.clone()).ok()?, None)),
+    }
+}
+
+impl ContractEvent {
+    pub fn get_body(&self) -> Result<&ContractEventV0, StellarSdkError> {
+        match &self.body {
+            ContractEventBody::V0(body) => Ok(body),
+            ContractEventBody::Default(_) => Err(StellarSdkError::InvalidContractEvent),
+        }
+    }
+
+    pub fn get_topics(&self) -> Result<&Vec<ScVal>, StellarSdkError> {
+        Ok(self.get_body()?.topics.get_vec())
+    }
+
+    /// Decode the topics like a vector, e.g., as tuple of the event name and addresses
+    pub fn decode_topics<T: TryFromScVal>(&self) -> Result<T, StellarSdkError> {
+        let topics = LimitedVarArray::new(self.get_topics()?.clone())?;
+        T::try_from_sc_val(ScVal::ScvVec(Some(topics)))
+    }
+
+    pub fn decode_data<T: TryFromScVal>(&self) -> Result<T, StellarSdkError> {
+        T::try_from_sc_val(self.get_body()?.data.clone())
+    }
+
+    /// Return the name of the event, i.e., the first topic if it is a symbol
+    pub fn get_name(&self) -> Option<&[u8]> {
+        match self.get_topics().ok()?.first()? {
+            ScVal::ScvSymbol(name) => Some(name.get_vec()),
+            _ => None,
+        }
+    }
+
+    /// Decode a SEP-41 token event, return `None` if this is no well-formed token event
+    pub fn get_token_event(&self) -> Option<TokenEvent> {
+        if self.type_ != ContractEventType::Contract {
+            return None
+        }
+
+        let body = self.get_body().ok()?;
+        let mut topics = body.topics.get_vec().clone();
+        let asset = match topics.last() {
+            Some(ScVal::ScvString(_)) => Some(String::try_from_sc_val(topics.pop()?).ok()?),
+            _ => None,
+        };
+        let mut topics = topics.into_iter();
+        let AsScSymbol(name) = AsScSymbol::<Vec<u8>>::try_from_sc_val(topics.next()?).ok()?;
+        let addresses = topics.map(ScAddress::try_from_sc_val).collect::<Result<Vec<_>, _>>().ok()?;
+        let data = &body.data;
+
+        let kind = match (name.as_slice(), addresses.as_slice()) {
+            (TRANSFER, [from, to]) => {
+                let (amount, to_muxed_id) = decode_amount(data)?;
+                TokenEventKind::Transfer { from: from.clone(), to: to.clone(), amount, to_muxed_id }
+            },
+            (MINT, [admin @ .., to]) if admin.len() <= 1 => {
+                let (amount, to_muxed_id) = decode_amount(data)?;
+                TokenEventKind::Mint { admin: admin.first().cloned(), to: to.clone(), amount, to_muxed_id }
+            },
+            (BURN, [from]) =>
+                TokenEventKind::Burn { from: from.clone(), amount: i128::try_from_sc_val(data.clone()).ok()? },
+            (CLAWBACK, [admin @ .., from]) if admin.len() <= 1 => TokenEventKind::Clawback {
+                admin: admin.first().cloned(),
+                from: from.clone(),
+                amount: i128::try_from_sc_val(data.clone()).ok()?,
+            },
+            (APPROVE, [from, spender]) => {
+                let (amount, expiration_ledger) = <(i128, u32)>::try_from_sc_val(data.clone()).ok()?;
+                TokenEventKind::Approve { from: from.clone(), spender: spender.clone(), amount, expiration_ledger }
+            },
+            (SET_ADMIN, [admin]) => TokenEventKind::SetAdmin {
+                admin: admin.clone(),
+                new_admin: ScAddress::try_from_sc_val(data.clone()).ok()?,
+            },
+            (SET_AUTHORIZED, [admin @ .., id]) if admin.len() <= 1 => TokenEventKind::SetAuthorized {
+                admin: admin.first().cloned(),
+                id: id.clone(),
+                authorize: bool::try_from_sc_val(data.clone()).ok()?,
+            },
+            _ => return None,
+        };
+
+        Some(TokenEvent { contract_id: self.contract_id, asset, kind })
+    }
+}
+
+impl TransactionMeta {
+    /// Return the contract events emitted by the operations together with the index of the
+    /// emitting operation
+    ///
+    /// Transaction level events of `V4` are not included, see `get_transaction_events`.
+    pub fn get_contract_events(&self) -> Vec<(usize, &ContractEvent)> {
+        match self {
+            TransactionMeta::V3(meta) => match &meta.soroban_meta {
+                Some(soroban_meta) => soroban_meta.events.get_vec().iter().map(|event| (0, event)).collect(),
+                None => Vec::new(),
+            },
+            TransactionMeta::V4(meta) => meta
+                .operations
+                .get_vec()
+                .iter()
+                .enumerate()
+                .flat_map(|(index, operation)| operation.events.get_vec().iter().map(move |event| (index, event)))
+                .collect(),
+            TransactionMeta::V0(_) | TransactionMeta::V1(_) | TransactionMeta::V2(_) | TransactionMeta::Default(_) =>
+                Vec::new(),
+        }
+    }
+
+    /// Return the events that are not emitted by an operation, like fee events (only `V4`)
+    pub fn get_transaction_events(&self) -> &[TransactionEvent] {
+        match self {
+            TransactionMeta::V4(meta) => meta.events.get_vec(),
+            _ => &[],
+        }
+    }
+
+    pub fn get_diagnostic_events(&self) -> &[DiagnosticEvent] {
+        match self {
+            TransactionMeta::V3(meta) => match &meta.soroban_meta {
+                Some(soroban_meta) => soroban_meta.diagnostic_events.get_vec(),
+                None => &[],
+            },
+            TransactionMeta::V4(meta) => meta.diagnostic_events.get_vec(),
+            _ => &[],
+        }
+    }
+
+    /// Return the SEP-41 token events emitted by the operations together with the index of
+    /// the emitting operation
+    pub fn get_token_events(&self) -> Vec<(usize, TokenEvent)> {
+        self.get_contract_events()
+            .into_iter()
+            .filter_map(|(index, event)| Some((index, event.get_token_event()?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TokenEvent, TokenEventKind};
+    use crate::{
+        compound_types::LimitedVarArray,
+        types::{
+            ContractEvent, ContractEventBody, ContractEventType, ContractEventV0, ExtensionPoint, OperationMetaV2,
+            ScAddress, ScVal, SorobanTransactionMeta, SorobanTransactionMetaExt, TransactionEvent,
+            TransactionEventStage, TransactionMeta, TransactionMetaV3, TransactionMetaV4,
+        },
+        AsScSymbol, IntoScVal,
+    };
+
+    const ACCOUNT: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+    const ISSUER: &str = "GAPIIRF3JXQAY63NSD6ALZ6JVUYDXCQIQIH5MZA3XYMR7LADTFKRBXZB";
+    const CONTRACT: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+
+    fn address(encoding: &str) -> ScAddress {
+        ScAddress::from_encoding(encoding).unwrap()
+    }
+
+    fn event(topics: Vec<ScVal>, data: ScVal) -> ContractEvent {
+        ContractEvent {
+            ext: ExtensionPoint::V0,
+            contract_id: Some([7; 32]),
+            type_: ContractEventType::Contract,
+            body: ContractEventBody::V0(ContractEventV0 { topics: LimitedVarArray::new(topics).unwrap(), data }),
+        }
+    }
+
+    fn transfer_event(amount: i128) -> ContractEvent {
+        event(
+            vec![
+                ScVal::new_symbol("transfer").unwrap(),
+                address(ACCOUNT).into_sc_val().unwrap(),
+                address(CONTRACT).into_sc_val().unwrap(),
+                format!("USD:{}", ISSUER).into_sc_val().unwrap(),
+            ],
+            amount.into_sc_val().unwrap(),
+        )
+    }
+
+    #[test]
+    fn token_events() {
+        assert_eq!(
+            transfer_event(-1).get_token_event(),
+            Some(TokenEvent {
+                contract_id: Some([7; 32]),
+                asset: Some(format!("USD:{}", ISSUER)),
+                kind: TokenEventKind::Transfer {
+                    from: address(ACCOUNT),
+                    to: address(CONTRACT),
+                    amount: -1,
+                    to_muxed_id: None
+                },
+            })
+        );
+
+        let (name, from): (AsScSymbol<Vec<u8>>, ScAddress) =
+            event(vec![ScVal::new_symbol("burn").unwrap(), address(ACCOUNT).into_sc_val().unwrap()], ScVal::ScvVoid)
+                .decode_topics()
+                .unwrap();
+        assert_eq!((name.0.as_slice(), from), (&b"burn"[..], address(ACCOUNT)));
+
+        let mint = |topics: Vec<ScVal>| event(topics, 5i128.into_sc_val().unwrap()).get_token_event().unwrap().kind;
+        let mint_topics = vec![ScVal::new_symbol("mint").unwrap(), address(ACCOUNT).into_sc_val().unwrap()];
+        assert_eq!(
+            mint(mint_topics.clone()),
+            TokenEventKind::Mint { admin: None, to: address(ACCOUNT), amount: 5, to_muxed_id: None }
+        );
+        let mut mint_topics_with_admin = mint_topics.clone();
+        mint_topics_with_admin.insert(1, address(ISSUER).into_sc_val().unwrap());
+        assert_eq!(
+            mint(mint_topics_with_admin),
+            TokenEventKind::Mint { admin: Some(address(ISSUER)), to: address(ACCOUNT), amount: 5, to_muxed_id: None }
+        );
+
+        let approve = event(
+            vec![
+                ScVal::new_symbol("approve").unwrap(),
+                address(ACCOUNT).into_sc_val().unwrap(),
+                address(CONTRACT).into_sc_val().unwrap(),
+            ],
+            (10i128, 1000u32).into_sc_val().unwrap(),
+        );
+        assert_eq!(
+            approve.get_token_event().unwrap().kind,
+            TokenEventKind::Approve {
+                from: address(ACCOUNT),
+                spender: address(CONTRACT),
+                amount: 10,
+                expiration_ledger: 1000
+            }
+        );
+
+        // wrong data type, too many addresses and an unknown name
+        assert_eq!(event(transfer_event(1).get_topics().unwrap().clone(), ScVal::ScvVoid).get_token_event(), None);
+        assert_eq!(event(vec![ScVal::new_symbol("burn").unwrap()], ScVal::ScvVoid).get_token_event(), None);
+        assert_eq!(
+            event(vec![ScVal::new_symbol("swap").unwrap()], 1i128.into_sc_val().unwrap()).get_token_event(),
+            None
+        );
+    }
+
+    #[test]
+    fn transaction_meta_events() {
+        let meta_v3 = TransactionMeta::V3(TransactionMetaV3 {
+            ext: ExtensionPoint::V0,
+            tx_changes_before: LimitedVarArray::new_empty(),
+            operations: LimitedVarArray::new_empty(),
+            tx_changes_after: LimitedVarArray::new_empty(),
+            soroban_meta: Some(SorobanTransactionMeta {
+                ext: SorobanTransactionMetaExt::V0,
+                events: LimitedVarArray::new(vec![transfer_event(1)]).unwrap(),
+                return_value: ScVal::ScvVoid,
+                diagnostic_events: LimitedVarArray::new_empty(),
+            }),
+        });
+        assert_eq!(meta_v3.get_contract_events(), vec![(0, &transfer_event(1))]);
+        assert_eq!(meta_v3.get_token_events().len(), 1);
+        assert!(meta_v3.get_transaction_events().is_empty());
+
+        let operation = |events| OperationMetaV2 {
+            ext: ExtensionPoint::V0,
+            changes: LimitedVarArray::new_empty(),
+            events: LimitedVarArray::new(events).unwrap(),
+        };
+        let fee_event = TransactionEvent {
+            stage: TransactionEventStage::TransactionEventStageBeforeAllTxes,
+            event: transfer_event(100),
+        };
+        let meta_v4 = TransactionMeta::V4(TransactionMetaV4 {
+            ext: ExtensionPoint::V0,
+            tx_changes_before: LimitedVarArray::new_empty(),
+            operations: LimitedVarArray::new(vec![
+                operation(vec![]),
+                operation(vec![transfer_event(2), event(vec![], ScVal::ScvVoid)]),
+            ])
+            .unwrap(),
+            tx_changes_after: LimitedVarArray::new_empty(),
+            soroban_meta: None,
+            events: LimitedVarArray::new(vec![fee_event.clone()]).unwrap(),
+            diagnostic_events: LimitedVarArray::new_empty(),
+        });
+        assert_eq!(meta_v4.get_contract_events().len(), 2);
+        let token_events = meta_v4.get_token_events();
+        assert_eq!(token_events.len(), 1);
+        assert_eq!(token_events[0].0, 1);
+        assert!(matches!(token_events[0].1.kind, TokenEventKind::Transfer { amount: 2, .. }));
+        assert_eq!(meta_v4.get_transaction_events(), &[fee_event]);
+    }
+}
@@ -0,0 +1,984 @@
+//! Contract specifications and the conversion of loosely typed values to `ScVal`s
+//!
+//! Soroban contracts describe their functions and types in the custom Wasm section
+//! `contractspecv0`, which is a sequence of XDR encoded `ScSpecEntry`s. A `ContractSpec`
+//! type-checks `SpecValue`s, a JSON like representation of values, against these types and
+//! converts them to `ScVal`s and back:
+//!
+//! - integers are `SpecValue::Integer` or decimal strings, 128 and 256 bit integers are
+//!   returned as decimal strings
+//! - bytes are hexadecimal strings, addresses are strkeys
+//! - vectors and tuples are arrays, maps with string or symbol keys are objects and other maps
+//!   arrays of `[key, value]` pairs
+//! - structs are objects (tuple structs arrays), void union cases are the case name, other
+//!   union cases objects `{ case: [values] }`, enums the case value or name
+//! - a failed result is returned as object `{ error: value }`
+
+use core::{
+    convert::{AsRef, TryFrom, TryInto},
+    str::from_utf8,
+};
+use sp_std::{vec, vec::Vec};
+
+use crate::{
+    compound_types::{LimitedString, LimitedVarArray, LimitedVarOpaque},
+    lib::{String, ToString},
+    types::{
+        Int128Parts, Int256Parts, ScAddress, ScError, ScMapEntry, ScSpecEntry, ScSpecFunctionV0, ScSpecTypeDef,
+        ScSpecUdtUnionCaseV0, ScVal, UInt128Parts, UInt256Parts,
+    },
+    xdr::streams::ReadStream,
+    StellarSdkError, XdrCodec,
+};
+
+const WASM_MAGIC: &[u8] = b"\0asm";
+const WASM_VERSION: &[u8] = &[1, 0, 0, 0];
+const WASM_CUSTOM_SECTION_ID: u8 = 0;
+const CONTRACT_SPEC_SECTION_NAME: &[u8] = b"contractspecv0";
+
+const RESULT_ERROR_FIELD: &str = "error";
+
+/// A loosely typed value that is converted to an `ScVal` according to a contract spec
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecValue {
+    Null,
+    Bool(bool),
+    Integer(i128),
+    String(String),
+    Array(Vec<SpecValue>),
+    /// The fields of an object in the order of their definition
+    Object(Vec<(String, SpecValue)>),
+}
+
+impl SpecValue {
+    pub fn string<T: AsRef<str>>(value: T) -> Self {
+        SpecValue::String(value.as_ref().to_string())
+    }
+
+    pub fn object<T: AsRef<str>>(fields: Vec<(T, SpecValue)>) -> Self {
+        SpecValue::Object(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name.as_ref().to_string(), value))
+                .collect(),
+        )
+    }
+
+    fn get_field(&self, name: &[u8]) -> Option<&SpecValue> {
+        match self {
+            SpecValue::Object(fields) => fields
+                .iter()
+                .find(|(field_name, _)| field_name.as_bytes() == name)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+/// The entries of the specification of a contract
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractSpec {
+    entries: Vec<ScSpecEntry>,
+}
+
+fn read_leb128_u32(bytes: &[u8], position: &mut usize) -> Result<u32, StellarSdkError> {
+    let mut result: u32 = 0;
+    for shift in (0..35).step_by(7) {
+        let byte = *bytes.get(*position).ok_or(StellarSdkError::InvalidContractSpec)?;
+        *position += 1;
+        result |= ((byte & 0x7f) as u32)
+            .checked_shl(shift)
+            .ok_or(StellarSdkError::InvalidContractSpec)?;
+        if byte & 0x80 == 0 {
+            return Ok(result)
+        }
+    }
+    Err(StellarSdkError::InvalidContractSpec)
+}
+
+fn mismatch<T>() -> Result<T, StellarSdkError> {
+    Err(StellarSdkError::ContractSpecMismatch)
+}
+
+fn get_name<const N: i32>(name: &LimitedString<N>) -> Result<String, StellarSdkError> {
+    from_utf8(name.get_vec())
+        .map(|name| name.to_string())
+        .map_err(|_| StellarSdkError::InvalidContractSpec)
+}
+
+impl ContractSpec {
+    pub fn new(entries: Vec<ScSpecEntry>) -> Self {
+        ContractSpec { entries }
+    }
+
+    /// Decode a sequence of XDR encoded `ScSpecEntry`s as found in the `contractspecv0` section
+    pub fn from_xdr_entries<T: AsRef<[u8]>>(xdr: T) -> Result<Self, StellarSdkError> {
+        let mut read_stream = ReadStream::new(xdr.as_ref());
+        let mut entries = Vec::new();
+        while read_stream.no_of_bytes_left_to_read() > 0 {
+            entries.push(
+                ScSpecEntry::from_xdr_buffered(&mut read_stream).map_err(|_| StellarSdkError::InvalidContractSpec)?,
+            );
+        }
+        Ok(ContractSpec { entries })
+    }
+
+    /// Decode the base64 encoding of a sequence of XDR encoded `ScSpecEntry`s
+    pub fn from_base64_xdr_entries<T: AsRef<[u8]>>(input: T) -> Result<Self, StellarSdkError> {
+        let input = input.as_ref();
+        let mut xdr = vec![0; input.len() * 3 / 4 + 3];
+        let bytes_written = base64::decode_config_slice(input, base64::STANDARD, &mut xdr)
+            .map_err(|_| StellarSdkError::InvalidContractSpec)?;
+        xdr.truncate(bytes_written);
+        Self::from_xdr_entries(xdr)
+    }
+
+    /// Extract the specification from the custom section `contractspecv0` of a contract's Wasm
+    /// code
+    pub fn from_wasm<T: AsRef<[u8]>>(wasm: T) -> Result<Self, StellarSdkError> {
+        let wasm = wasm.as_ref();
+        if wasm.len() < 8 || &wasm[..4] != WASM_MAGIC || &wasm[4..8] != WASM_VERSION {
+            return Err(StellarSdkError::InvalidContractSpec)
+        }
+
+        let mut spec_xdr = Vec::new();
+        let mut position = 8;
+        while position < wasm.len() {
+            let section_id = wasm[position];
+            position += 1;
+            let section_length = read_leb128_u32(wasm, &mut position)? as usize;
+            let section_end = position.checked_add(section_length).filter(|end| *end <= wasm.len());
+            let section_end = section_end.ok_or(StellarSdkError::InvalidContractSpec)?;
+
+            if section_id == WASM_CUSTOM_SECTION_ID {
+                let name_length = read_leb128_u32(wasm, &mut position)? as usize;
+                let name_end = position.checked_add(name_length).filter(|end| *end <= section_end);
+                let name_end = name_end.ok_or(StellarSdkError::InvalidContractSpec)?;
+                if &wasm[position..name_end] == CONTRACT_SPEC_SECTION_NAME {
+                    spec_xdr.extend_from_slice(&wasm[name_end..section_end]);
+                }
+            }
+            position = section_end;
+        }
+
+        Self::from_xdr_entries(spec_xdr)
+    }
+
+    pub fn get_entries(&self) -> &Vec<ScSpecEntry> {
+        &self.entries
+    }
+
+    pub fn get_functions(&self) -> impl Iterator<Item = &ScSpecFunctionV0> {
+        self.entries.iter().filter_map(|entry| match entry {
+            ScSpecEntry::ScSpecEntryFunctionV0(function) => Some(function),
+            _ => None,
+        })
+    }
+
+    pub fn get_function<T: AsRef<[u8]>>(&self, name: T) -> Result<&ScSpecFunctionV0, StellarSdkError> {
+        let name = name.as_ref();
+        self.get_functions()
+            .find(|function| function.name.get_vec() == name)
+            .ok_or_else(|| StellarSdkError::UnknownContractSpecEntry { name: name.to_vec() })
+    }
+
+    /// Return the user defined type (struct, union, enum or error enum) with the name `name`
+    pub fn get_type<T: AsRef<[u8]>>(&self, name: T) -> Result<&ScSpecEntry, StellarSdkError> {
+        let name = name.as_ref();
+        self.entries
+            .iter()
+            .find(|entry| match entry {
+                ScSpecEntry::ScSpecEntryUdtStructV0(udt) => udt.name.get_vec() == name,
+                ScSpecEntry::ScSpecEntryUdtUnionV0(udt) => udt.name.get_vec() == name,
+                ScSpecEntry::ScSpecEntryUdtEnumV0(udt) => udt.name.get_vec() == name,
+                ScSpecEntry::ScSpecEntryUdtErrorEnumV0(udt) => udt.name.get_vec() == name,
+                ScSpecEntry::ScSpecEntryFunctionV0(_) | ScSpecEntry::ScSpecEntryEventV0(_) => false,
+            })
+            .ok_or_else(|| StellarSdkError::UnknownContractSpecEntry { name: name.to_vec() })
+    }
+
+    /// Convert the arguments of the function `function_name` to `ScVal`s
+    ///
+    /// `arguments` is either an array of the arguments in the order of the function's inputs
+    /// or an object with the argument names as keys.
+    pub fn encode_arguments<T: AsRef<[u8]>>(
+        &self,
+        function_name: T,
+        arguments: &SpecValue,
+    ) -> Result<Vec<ScVal>, StellarSdkError> {
+        let inputs = self.get_function(function_name)?.inputs.get_vec();
+        match arguments {
+            SpecValue::Array(arguments) if arguments.len() == inputs.len() => inputs
+                .iter()
+                .zip(arguments.iter())
+                .map(|(input, argument)| self.encode_value(&input.type_, argument))
+                .collect(),
+            SpecValue::Object(fields) if fields.len() == inputs.len() => inputs
+                .iter()
+                .map(|input| match arguments.get_field(input.name.get_vec()) {
+                    Some(argument) => self.encode_value(&input.type_, argument),
+                    None => mismatch(),
+                })
+                .collect(),
+            _ => mismatch(),
+        }
+    }
+
+    /// Convert the return value of the function `function_name`
+    pub fn decode_result<T: AsRef<[u8]>>(&self, function_name: T, value: ScVal) -> Result<SpecValue, StellarSdkError> {
+        match self.get_function(function_name)?.outputs.get_vec().first() {
+            Some(output) => self.decode_value(output, value),
+            None => self.decode_value(&ScSpecTypeDef::ScSpecTypeVoid, value),
+        }
+    }
+
+    /// Convert `value` to an `ScVal` of the type `type_def`
+    pub fn encode_value(&self, type_def: &ScSpecTypeDef, value: &SpecValue) -> Result<ScVal, StellarSdkError> {
+        Ok(match (type_def, value) {
+            (ScSpecTypeDef::ScSpecTypeBool, SpecValue::Bool(value)) => ScVal::ScvBool(*value),
+            (ScSpecTypeDef::ScSpecTypeVoid, SpecValue::Null) => ScVal::ScvVoid,
+            (ScSpecTypeDef::ScSpecTypeError, value) => ScVal::ScvError(ScError::SceContract(to_integer(value)?)),
+            (ScSpecTypeDef::ScSpecTypeU32, value) => ScVal::ScvU32(to_integer(value)?),
+            (ScSpecTypeDef::ScSpecTypeI32, value) => ScVal::ScvI32(to_integer(value)?),
+            (ScSpecTypeDef::ScSpecTypeU64, value) => ScVal::ScvU64(to_integer(value)?),
+            (ScSpecTypeDef::ScSpecTypeI64, value) => ScVal::ScvI64(to_integer(value)?),
+            (ScSpecTypeDef::ScSpecTypeTimepoint, value) => ScVal::ScvTimepoint(to_integer(value)?),
+            (ScSpecTypeDef::ScSpecTypeDuration, value) => ScVal::ScvDuration(to_integer(value)?),
+            (ScSpecTypeDef::ScSpecTypeU128, value) => {
+                let (negative, words) = to_256_bit_integer(value)?;
+                if words[..2] != [0, 0] || negative && words != [0; 4] {
+                    return mismatch()
+                }
+                ScVal::ScvU128(UInt128Parts { hi: words[2], lo: words[3] })
+            },
+            (ScSpecTypeDef::ScSpecTypeI128, value) => {
+                let value: i128 = to_integer(value)?;
+                ScVal::ScvI128(Int128Parts { hi: (value >> 64) as i64, lo: value as u64 })
+            },
+            (ScSpecTypeDef::ScSpecTypeU256, value) => {
+                let (negative, words) = to_256_bit_integer(value)?;
+                if negative && words != [0; 4] {
+                    return mismatch()
+                }
+                ScVal::ScvU256(UInt256Parts::from_be_bytes(words_to_bytes(words)))
+            },
+            (ScSpecTypeDef::ScSpecTypeI256, value) => {
+                let (negative, words) = to_256_bit_integer(value)?;
+                let limit = [1 << 63, 0, 0, 0];
+                let words = match negative {
+                    true if words <= limit => negate(words),
+                    false if words < limit => words,
+                    _ => return mismatch(),
+                };
+                ScVal::ScvI256(Int256Parts::from_be_bytes(words_to_bytes(words)))
+            },
+            (ScSpecTypeDef::ScSpecTypeBytes, SpecValue::String(value)) => ScVal::ScvBytes(LimitedVarOpaque::new(
+                hex::decode(value).map_err(StellarSdkError::InvalidHexEncoding)?,
+            )?),
+            (ScSpecTypeDef::ScSpecTypeBytesN(bytes_n), SpecValue::String(value)) => {
+                let bytes = hex::decode(value).map_err(StellarSdkError::InvalidHexEncoding)?;
+                if bytes.len() != *bytes_n.n as usize {
+                    return mismatch()
+                }
+                ScVal::ScvBytes(LimitedVarOpaque::new(bytes)?)
+            },
+            (ScSpecTypeDef::ScSpecTypeString, SpecValue::String(value)) =>
+                ScVal::ScvString(LimitedString::new(value.as_bytes().to_vec())?),
+            (ScSpecTypeDef::ScSpecTypeSymbol, SpecValue::String(value)) => ScVal::new_symbol(value)?,
+            (ScSpecTypeDef::ScSpecTypeAddress, SpecValue::String(value)) |
+            (ScSpecTypeDef::ScSpecTypeMuxedAddress, SpecValue::String(value)) =>
+                ScVal::ScvAddress(ScAddress::from_encoding(value)?),
+            (ScSpecTypeDef::ScSpecTypeOption(_), SpecValue::Null) => ScVal::ScvVoid,
+            (ScSpecTypeDef::ScSpecTypeOption(option), value) => self.encode_value(&option.value_type, value)?,
+            (ScSpecTypeDef::ScSpecTypeVec(vec), SpecValue::Array(elements)) => new_vec(
+                elements
+                    .iter()
+                    .map(|element| self.encode_value(&vec.element_type, element))
+                    .collect::<Result<_, _>>()?,
+            )?,
+            (ScSpecTypeDef::ScSpecTypeMap(map), SpecValue::Object(fields)) => ScVal::new_map(
+                fields
+                    .iter()
+                    .map(|(key, val)| {
+                        Ok(ScMapEntry {
+                            key: self.encode_value(&map.key_type, &SpecValue::String(key.clone()))?,
+                            val: self.encode_value(&map.value_type, val)?,
+                        })
+                    })
+                    .collect::<Result<_, StellarSdkError>>()?,
+            )?,
+            (ScSpecTypeDef::ScSpecTypeMap(map), SpecValue::Array(pairs)) => ScVal::new_map(
+                pairs
+                    .iter()
+                    .map(|pair| match pair {
+                        SpecValue::Array(pair) if pair.len() == 2 => Ok(ScMapEntry {
+                            key: self.encode_value(&map.key_type, &pair[0])?,
+                            val: self.encode_value(&map.value_type, &pair[1])?,
+                        }),
+                        _ => mismatch(),
+                    })
+                    .collect::<Result<_, StellarSdkError>>()?,
+            )?,
+            (ScSpecTypeDef::ScSpecTypeTuple(tuple), SpecValue::Array(elements)) =>
+                new_vec(self.encode_values(tuple.value_types.get_vec(), elements)?)?,
+            (ScSpecTypeDef::ScSpecTypeUdt(udt), value) => self.encode_udt(self.get_type(udt.name.get_vec())?, value)?,
+            _ => return mismatch(),
+        })
+    }
+
+    fn encode_values(&self, type_defs: &[ScSpecTypeDef], values: &[SpecValue]) -> Result<Vec<ScVal>, StellarSdkError> {
+        if type_defs.len() != values.len() {
+            return mismatch()
+        }
+        type_defs
+            .iter()
+            .zip(values.iter())
+            .map(|(type_def, value)| self.encode_value(type_def, value))
+            .collect()
+    }
+
+    fn encode_udt(&self, entry: &ScSpecEntry, value: &SpecValue) -> Result<ScVal, StellarSdkError> {
+        match (entry, value) {
+            (ScSpecEntry::ScSpecEntryUdtStructV0(udt), value) => {
+                let fields = udt.fields.get_vec();
+                if is_tuple_struct(udt.fields.get_vec().iter().map(|field| field.name.get_vec())) {
+                    let types: Vec<ScSpecTypeDef> = fields.iter().map(|field| field.type_.clone()).collect();
+                    return match value {
+                        SpecValue::Array(elements) => new_vec(self.encode_values(&types, elements)?),
+                        _ => mismatch(),
+                    }
+                }
+
+                match value {
+                    SpecValue::Object(values) if values.len() == fields.len() => ScVal::new_map(
+                        fields
+                            .iter()
+                            .map(|field| match value.get_field(field.name.get_vec()) {
+                                Some(field_value) => Ok(ScMapEntry {
+                                    key: ScVal::new_symbol(field.name.get_vec())?,
+                                    val: self.encode_value(&field.type_, field_value)?,
+                                }),
+                                None => mismatch(),
+                            })
+                            .collect::<Result<_, StellarSdkError>>()?,
+                    ),
+                    _ => mismatch(),
+                }
+            },
+            (ScSpecEntry::ScSpecEntryUdtUnionV0(udt), value) => {
+                let (case_name, case_values) = match value {
+                    SpecValue::String(case_name) => (case_name, None),
+                    SpecValue::Object(fields) if fields.len() == 1 => match &fields[0] {
+                        (case_name, SpecValue::Array(case_values)) => (case_name, Some(case_values)),
+                        _ => return mismatch(),
+                    },
+                    _ => return mismatch(),
+                };
+
+                let case = udt.cases.get_vec().iter().find(|case| match case {
+                    ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseVoidV0(case) => case.name.get_vec() == case_name.as_bytes(),
+                    ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseTupleV0(case) =>
+                        case.name.get_vec() == case_name.as_bytes(),
+                });
+                let mut elements = vec![ScVal::new_symbol(case_name)?];
+                match (case, case_values) {
+                    (Some(ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseVoidV0(_)), None) => (),
+                    (Some(ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseTupleV0(case)), Some(case_values)) =>
+                        elements.extend(self.encode_values(case.type_.get_vec(), case_values)?),
+                    _ => return mismatch(),
+                }
+                new_vec(elements)
+            },
+            (ScSpecEntry::ScSpecEntryUdtEnumV0(udt), value) => {
+                let case = udt.cases.get_vec().iter().find(|case| match value {
+                    SpecValue::String(name) => case.name.get_vec() == name.as_bytes(),
+                    value => to_integer::<u32>(value).ok() == Some(case.value),
+                });
+                case.map(|case| ScVal::ScvU32(case.value))
+                    .ok_or(StellarSdkError::ContractSpecMismatch)
+            },
+            (ScSpecEntry::ScSpecEntryUdtErrorEnumV0(udt), value) => {
+                let case = udt.cases.get_vec().iter().find(|case| match value {
+                    SpecValue::String(name) => case.name.get_vec() == name.as_bytes(),
+                    value => to_integer::<u32>(value).ok() == Some(case.value),
+                });
+                case.map(|case| ScVal::ScvError(ScError::SceContract(case.value)))
+                    .ok_or(StellarSdkError::ContractSpecMismatch)
+            },
+            _ => mismatch(),
+        }
+    }
+
+    /// Convert the `ScVal` `value` of the type `type_def`
+    pub fn decode_value(&self, type_def: &ScSpecTypeDef, value: ScVal) -> Result<SpecValue, StellarSdkError> {
+        Ok(match (type_def, value) {
+            (ScSpecTypeDef::ScSpecTypeVal, value) => decode_untyped(value)?,
+            (ScSpecTypeDef::ScSpecTypeBool, ScVal::ScvBool(value)) => SpecValue::Bool(value),
+            (ScSpecTypeDef::ScSpecTypeVoid, ScVal::ScvVoid) => SpecValue::Null,
+            (ScSpecTypeDef::ScSpecTypeError, ScVal::ScvError(ScError::SceContract(code))) =>
+                SpecValue::Integer(code.into()),
+            (ScSpecTypeDef::ScSpecTypeU32, ScVal::ScvU32(value)) => SpecValue::Integer(value.into()),
+            (ScSpecTypeDef::ScSpecTypeI32, ScVal::ScvI32(value)) => SpecValue::Integer(value.into()),
+            (ScSpecTypeDef::ScSpecTypeU64, ScVal::ScvU64(value)) |
+            (ScSpecTypeDef::ScSpecTypeTimepoint, ScVal::ScvTimepoint(value)) |
+            (ScSpecTypeDef::ScSpecTypeDuration, ScVal::ScvDuration(value)) => SpecValue::Integer(value.into()),
+            (ScSpecTypeDef::ScSpecTypeI64, ScVal::ScvI64(value)) => SpecValue::Integer(value.into()),
+            (ScSpecTypeDef::ScSpecTypeU128, value @ ScVal::ScvU128(_)) |
+            (ScSpecTypeDef::ScSpecTypeI128, value @ ScVal::ScvI128(_)) |
+            (ScSpecTypeDef::ScSpecTypeU256, value @ ScVal::ScvU256(_)) |
+            (ScSpecTypeDef::ScSpecTypeI256, value @ ScVal::ScvI256(_)) => decode_untyped(value)?,
+            (ScSpecTypeDef::ScSpecTypeBytes, value @ ScVal::ScvBytes(_)) => decode_untyped(value)?,
+            (ScSpecTypeDef::ScSpecTypeBytesN(bytes_n), ScVal::ScvBytes(bytes))
+                if bytes.len() == *bytes_n.n as usize =>
+                SpecValue::String(hex::encode(bytes.get_vec())),
+            (ScSpecTypeDef::ScSpecTypeString, value @ ScVal::ScvString(_)) |
+            (ScSpecTypeDef::ScSpecTypeSymbol, value @ ScVal::ScvSymbol(_)) |
+            (ScSpecTypeDef::ScSpecTypeAddress, value @ ScVal::ScvAddress(_)) |
+            (ScSpecTypeDef::ScSpecTypeMuxedAddress, value @ ScVal::ScvAddress(_)) => decode_untyped(value)?,
+            (ScSpecTypeDef::ScSpecTypeOption(_), ScVal::ScvVoid) => SpecValue::Null,
+            (ScSpecTypeDef::ScSpecTypeOption(option), value) => self.decode_value(&option.value_type, value)?,
+            (ScSpecTypeDef::ScSpecTypeResult(result), ScVal::ScvError(error)) => SpecValue::object(vec![(
+                RESULT_ERROR_FIELD,
+                self.decode_value(&result.error_type, ScVal::ScvError(error))?,
+            )]),
+            (ScSpecTypeDef::ScSpecTypeResult(result), value) => self.decode_value(&result.ok_type, value)?,
+            (ScSpecTypeDef::ScSpecTypeVec(vec), ScVal::ScvVec(Some(elements))) => SpecValue::Array(
+                elements
+                    .into_vec()
+                    .into_iter()
+                    .map(|element| self.decode_value(&vec.element_type, element))
+                    .collect::<Result<_, _>>()?,
+            ),
+            (ScSpecTypeDef::ScSpecTypeMap(map), ScVal::ScvMap(Some(entries))) => {
+                let entries = entries.into_vec().into_iter().map(|entry| {
+                    Ok((self.decode_value(&map.key_type, entry.key)?, self.decode_value(&map.value_type, entry.val)?))
+                });
+                match *map.key_type {
+                    ScSpecTypeDef::ScSpecTypeString | ScSpecTypeDef::ScSpecTypeSymbol => SpecValue::Object(
+                        entries
+                            .map(|entry| match entry? {
+                                (SpecValue::String(key), val) => Ok((key, val)),
+                                _ => mismatch(),
+                            })
+                            .collect::<Result<_, StellarSdkError>>()?,
+                    ),
+                    _ => SpecValue::Array(
+                        entries
+                            .map(|entry| entry.map(|(key, val)| SpecValue::Array(vec![key, val])))
+                            .collect::<Result<_, StellarSdkError>>()?,
+                    ),
+                }
+            },
+            (ScSpecTypeDef::ScSpecTypeTuple(tuple), ScVal::ScvVec(Some(elements))) =>
+                SpecValue::Array(self.decode_values(tuple.value_types.get_vec(), elements.into_vec())?),
+            (ScSpecTypeDef::ScSpecTypeUdt(udt), value) => self.decode_udt(self.get_type(udt.name.get_vec())?, value)?,
+            _ => return mismatch(),
+        })
+    }
+
+    fn decode_values(
+        &self,
+        type_defs: &[ScSpecTypeDef],
+        values: Vec<ScVal>,
+    ) -> Result<Vec<SpecValue>, StellarSdkError> {
+        if type_defs.len() != values.len() {
+            return mismatch()
+        }
+        type_defs
+            .iter()
+            .zip(values)
+            .map(|(type_def, value)| self.decode_value(type_def, value))
+            .collect()
+    }
+
+    fn decode_udt(&self, entry: &ScSpecEntry, value: ScVal) -> Result<SpecValue, StellarSdkError> {
+        match (entry, value) {
+            (ScSpecEntry::ScSpecEntryUdtStructV0(udt), ScVal::ScvVec(Some(elements)))
+                if is_tuple_struct(udt.fields.get_vec().iter().map(|field| field.name.get_vec())) =>
+            {
+                let types: Vec<ScSpecTypeDef> = udt.fields.get_vec().iter().map(|field| field.type_.clone()).collect();
+                Ok(SpecValue::Array(self.decode_values(&types, elements.into_vec())?))
+            },
+            (ScSpecEntry::ScSpecEntryUdtStructV0(udt), ScVal::ScvMap(Some(entries))) => {
+                let fields = udt.fields.get_vec();
+                let entries = entries.into_vec();
+                if entries.len() != fields.len() {
+                    return mismatch()
+                }
+                fields
+                    .iter()
+                    .map(|field| {
+                        let entry = entries.iter().find(|entry| match &entry.key {
+                            ScVal::ScvSymbol(key) => key.get_vec() == field.name.get_vec(),
+                            _ => false,
+                        });
+                        match entry {
+                            Some(entry) =>
+                                Ok((get_name(&field.name)?, self.decode_value(&field.type_, entry.val.clone())?)),
+                            None => mismatch(),
+                        }
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(SpecValue::Object)
+            },
+            (ScSpecEntry::ScSpecEntryUdtUnionV0(udt), ScVal::ScvVec(Some(elements))) => {
+                let mut elements = elements.into_vec().into_iter();
+                let case_name = match elements.next() {
+                    Some(ScVal::ScvSymbol(case_name)) => case_name,
+                    _ => return mismatch(),
+                };
+                let case = udt.cases.get_vec().iter().find(|case| match case {
+                    ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseVoidV0(case) => case.name.get_vec() == case_name.get_vec(),
+                    ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseTupleV0(case) => case.name.get_vec() == case_name.get_vec(),
+                });
+                let case_name = get_name(&case_name)?;
+                match case {
+                    Some(ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseVoidV0(_)) if elements.len() == 0 =>
+                        Ok(SpecValue::String(case_name)),
+                    Some(ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseTupleV0(case)) => Ok(SpecValue::Object(vec![(
+                        case_name,
+                        SpecValue::Array(self.decode_values(case.type_.get_vec(), elements.collect())?),
+                    )])),
+                    _ => mismatch(),
+                }
+            },
+            (ScSpecEntry::ScSpecEntryUdtEnumV0(udt), ScVal::ScvU32(value))
+                if udt.cases.get_vec().iter().any(|case| case.value == value) =>
+                Ok(SpecValue::Integer(value.into())),
+            (ScSpecEntry::ScSpecEntryUdtErrorEnumV0(udt), ScVal::ScvError(ScError::SceContract(value))) =>
+                match udt.cases.get_vec().iter().find(|case| case.value == value) {
+                    Some(case) => Ok(SpecValue::String(get_name(&case.name)?)),
+                    None => Ok(SpecValue::Integer(value.into())),
+                },
+            _ => mismatch(),
+        }
+    }
+}
+
+fn new_vec(elements: Vec<ScVal>) -> Result<ScVal, StellarSdkError> {
+    Ok(ScVal::ScvVec(Some(LimitedVarArray::new(elements)?)))
+}
+
+/// Structs with the fields `0`, `1`, ... are tuple structs, encoded as vectors
+pub(crate) fn is_tuple_struct<'a>(names: impl ExactSizeIterator<Item = &'a Vec<u8>>) -> bool {
+    names.len() > 0 && names.enumerate().all(|(index, name)| name == index.to_string().as_bytes())
+}
+
+/// Convert a value without type information
+fn decode_untyped(value: ScVal) -> Result<SpecValue, StellarSdkError> {
+    Ok(match value {
+        ScVal::ScvBool(value) => SpecValue::Bool(value),
+        ScVal::ScvVoid => SpecValue::Null,
+        ScVal::ScvU32(value) => SpecValue::Integer(value.into()),
+        ScVal::ScvI32(value) => SpecValue::Integer(value.into()),
+        ScVal::ScvU64(value) | ScVal::ScvTimepoint(value) | ScVal::ScvDuration(value) =>
+            SpecValue::Integer(value.into()),
+        ScVal::ScvI64(value) => SpecValue::Integer(value.into()),
+        ScVal::ScvU128(UInt128Parts { hi, lo }) => SpecValue::String(((hi as u128) << 64 | lo as u128).to_string()),
+        ScVal::ScvI128(Int128Parts { hi, lo }) => SpecValue::String(((hi as i128) << 64 | lo as i128).to_string()),
+        ScVal::ScvU256(value) => SpecValue::String(format_256_bit_integer(false, bytes_to_words(value.to_be_bytes()))),
+        ScVal::ScvI256(value) => {
+            let words = bytes_to_words(value.to_be_bytes());
+            SpecValue::String(match value.hi_hi < 0 {
+                true => format_256_bit_integer(true, negate(words)),
+                false => format_256_bit_integer(false, words),
+            })
+        },
+        ScVal::ScvBytes(bytes) => SpecValue::String(hex::encode(bytes.get_vec())),
+        ScVal::ScvString(string) =>
+            SpecValue::String(String::from_utf8(string.into_vec()).map_err(|_| StellarSdkError::InvalidScVal)?),
+        ScVal::ScvSymbol(symbol) => SpecValue::String(get_name(&symbol)?),
+        ScVal::ScvAddress(address) =>
+            SpecValue::String(String::from_utf8(address.to_encoding()).map_err(|_| StellarSdkError::InvalidScVal)?),
+        ScVal::ScvVec(Some(elements)) =>
+            SpecValue::Array(elements.into_vec().into_iter().map(decode_untyped).collect::<Result<_, _>>()?),
+        ScVal::ScvMap(Some(entries)) => SpecValue::Array(
+            entries
+                .into_vec()
+                .into_iter()
+                .map(|entry| Ok(SpecValue::Array(vec![decode_untyped(entry.key)?, decode_untyped(entry.val)?])))
+                .collect::<Result<_, StellarSdkError>>()?,
+        ),
+        _ => return mismatch(),
+    })
+}
+
+fn to_integer<T: TryFrom<i128>>(value: &SpecValue) -> Result<T, StellarSdkError> {
+    let value = match value {
+        SpecValue::Integer(value) => *value,
+        SpecValue::String(value) => value.parse::<i128>().map_err(|_| StellarSdkError::ContractSpecMismatch)?,
+        _ => return mismatch(),
+    };
+    T::try_from(value).map_err(|_| StellarSdkError::ContractSpecMismatch)
+}
+
+/// Return the sign and the absolute value (as big endian 64 bit words) of a 256 bit integer
+fn to_256_bit_integer(value: &SpecValue) -> Result<(bool, [u64; 4]), StellarSdkError> {
+    let digits = match value {
+        SpecValue::Integer(value) => {
+            let absolute_value = value.unsigned_abs();
+            return Ok((*value < 0, [0, 0, (absolute_value >> 64) as u64, absolute_value as u64]))
+        },
+        SpecValue::String(digits) => digits.as_bytes(),
+        _ => return mismatch(),
+    };
+
+    let (negative, digits) = match digits.first() {
+        Some(b'-') => (true, &digits[1..]),
+        _ => (false, digits),
+    };
+    if digits.is_empty() {
+        return mismatch()
+    }
+
+    let mut words = [0u64; 4];
+    for digit in digits {
+        if !digit.is_ascii_digit() {
+            return mismatch()
+        }
+        let mut carry = (digit - b'0') as u128;
+        for word in words.iter_mut().rev() {
+            let product = *word as u128 * 10 + carry;
+            *word = product as u64;
+            carry = product >> 64;
+        }
+        if carry != 0 {
+            return mismatch()
+        }
+    }
+    Ok((negative, words))
+}
+
+fn format_256_bit_integer(negative: bool, mut words: [u64; 4]) -> String {
+    let mut digits = Vec::new();
+    loop {
+        let mut remainder = 0u128;
+        for word in words.iter_mut() {
+            let dividend = remainder << 64 | *word as u128;
+            *word = (dividend / 10) as u64;
+            remainder = dividend % 10;
+        }
+        digits.push(b'0' + remainder as u8);
+        if words == [0; 4] {
+            break
+        }
+    }
+    if negative {
+        digits.push(b'-');
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("Digits are ASCII")
+}
+
+/// Return the two's complement of a 256 bit integer
+fn negate(words: [u64; 4]) -> [u64; 4] {
+    let mut result = [0u64; 4];
+    let mut carry = true;
+    for (result, word) in result.iter_mut().zip(words.iter()).rev() {
+        let (sum, overflow) = (!word).overflowing_add(carry as u64);
+        *result = sum;
+        carry = overflow;
+    }
+    result
+}
+
+fn words_to_bytes(words: [u64; 4]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, word) in bytes.chunks_exact_mut(8).zip(words.iter()) {
+        chunk.copy_from_slice(&word.to_be_bytes());
+    }
+    bytes
+}
+
+fn bytes_to_words(bytes: [u8; 32]) -> [u64; 4] {
+    let mut words = [0u64; 4];
+    for (word, chunk) in words.iter_mut().zip(bytes.chunks_exact(8)) {
+        *word = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    words
+}
+
+#[cfg(feature = "offchain")]
+impl SpecValue {
+    /// Convert a JSON value, numbers must be integers
+    pub fn from_json_value(value: serde_json::Value) -> Result<Self, StellarSdkError> {
+        use serde_json::Value;
+
+        Ok(match value {
+            Value::Null => SpecValue::Null,
+            Value::Bool(value) => SpecValue::Bool(value),
+            Value::Number(number) => match (number.as_i64(), number.as_u64()) {
+                (Some(value), _) => SpecValue::Integer(value.into()),
+                (None, Some(value)) => SpecValue::Integer(value.into()),
+                (None, None) => return mismatch(),
+            },
+            Value::String(value) => SpecValue::String(value),
+            Value::Array(elements) =>
+                SpecValue::Array(elements.into_iter().map(Self::from_json_value).collect::<Result<_, _>>()?),
+            Value::Object(fields) => SpecValue::Object(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| Ok((name, Self::from_json_value(value)?)))
+                    .collect::<Result<_, StellarSdkError>>()?,
+            ),
+        })
+    }
+
+    /// Convert to a JSON value, integers that don't fit into 64 bits are converted to strings
+    pub fn into_json_value(self) -> serde_json::Value {
+        use serde_json::Value;
+
+        match self {
+            SpecValue::Null => Value::Null,
+            SpecValue::Bool(value) => Value::Bool(value),
+            SpecValue::Integer(value) => match (i64::try_from(value), u64::try_from(value)) {
+                (Ok(value), _) => Value::from(value),
+                (_, Ok(value)) => Value::from(value),
+                _ => Value::String(value.to_string()),
+            },
+            SpecValue::String(value) => Value::String(value),
+            SpecValue::Array(elements) => Value::Array(elements.into_iter().map(Self::into_json_value).collect()),
+            SpecValue::Object(fields) => Value::Object(
+                fields
+                    .into_iter()
+                    .map(|(name, value)| (name, value.into_json_value()))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use sp_std::{boxed::Box, vec::Vec};
+
+    use super::{ContractSpec, SpecValue};
+    use crate::{
+        compound_types::{LimitedString, LimitedVarArray},
+        types::{
+            ScSpecEntry, ScSpecFunctionInputV0, ScSpecFunctionV0, ScSpecTypeDef, ScSpecTypeOption, ScSpecTypeResult,
+            ScSpecTypeUdt, ScSpecTypeVec, ScSpecUdtErrorEnumCaseV0, ScSpecUdtErrorEnumV0, ScSpecUdtStructFieldV0,
+            ScSpecUdtStructV0, ScSpecUdtUnionCaseTupleV0, ScSpecUdtUnionCaseV0, ScSpecUdtUnionCaseVoidV0,
+            ScSpecUdtUnionV0, ScVal,
+        },
+        IntoScVal, StellarSdkError, XdrCodec,
+    };
+
+    pub(crate) fn string<const N: i32>(value: &str) -> LimitedString<N> {
+        LimitedString::new(value.as_bytes().to_vec()).unwrap()
+    }
+
+    pub(crate) fn udt(name: &str) -> ScSpecTypeDef {
+        ScSpecTypeDef::ScSpecTypeUdt(Box::new(ScSpecTypeUdt { name: Box::new(string(name)) }))
+    }
+
+    pub(crate) fn input(name: &str, type_: ScSpecTypeDef) -> ScSpecFunctionInputV0 {
+        ScSpecFunctionInputV0 { doc: string(""), name: string(name), type_ }
+    }
+
+    pub(crate) fn field(name: &str, type_: ScSpecTypeDef) -> ScSpecUdtStructFieldV0 {
+        ScSpecUdtStructFieldV0 { doc: string(""), name: string(name), type_ }
+    }
+
+    pub(crate) fn entries() -> Vec<ScSpecEntry> {
+        vec![
+            ScSpecEntry::ScSpecEntryUdtStructV0(ScSpecUdtStructV0 {
+                doc: string(""),
+                lib: string(""),
+                name: string("Payment"),
+                fields: LimitedVarArray::new(vec![
+                    field("to", ScSpecTypeDef::ScSpecTypeAddress),
+                    field("amount", ScSpecTypeDef::ScSpecTypeI128),
+                    field(
+                        "memo",
+                        ScSpecTypeDef::ScSpecTypeOption(Box::new(ScSpecTypeOption {
+                            value_type: Box::new(ScSpecTypeDef::ScSpecTypeString),
+                        })),
+                    ),
+                ])
+                .unwrap(),
+            }),
+            ScSpecEntry::ScSpecEntryUdtUnionV0(ScSpecUdtUnionV0 {
+                doc: string(""),
+                lib: string(""),
+                name: string("Asset"),
+                cases: LimitedVarArray::new(vec![
+                    ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseVoidV0(ScSpecUdtUnionCaseVoidV0 {
+                        doc: string(""),
+                        name: string("Native"),
+                    }),
+                    ScSpecUdtUnionCaseV0::ScSpecUdtUnionCaseTupleV0(ScSpecUdtUnionCaseTupleV0 {
+                        doc: string(""),
+                        name: string("Token"),
+                        type_: LimitedVarArray::new(vec![ScSpecTypeDef::ScSpecTypeAddress]).unwrap(),
+                    }),
+                ])
+                .unwrap(),
+            }),
+            ScSpecEntry::ScSpecEntryUdtErrorEnumV0(ScSpecUdtErrorEnumV0 {
+                doc: string(""),
+                lib: string(""),
+                name: string("Error"),
+                cases: LimitedVarArray::new(vec![ScSpecUdtErrorEnumCaseV0 {
+                    doc: string(""),
+                    name: string("InsufficientBalance"),
+                    value: 1,
+                }])
+                .unwrap(),
+            }),
+            ScSpecEntry::ScSpecEntryFunctionV0(ScSpecFunctionV0 {
+                doc: string(""),
+                name: string("pay"),
+                inputs: LimitedVarArray::new(vec![
+                    input("asset", udt("Asset")),
+                    input(
+                        "payments",
+                        ScSpecTypeDef::ScSpecTypeVec(Box::new(ScSpecTypeVec {
+                            element_type: Box::new(udt("Payment")),
+                        })),
+                    ),
+                ])
+                .unwrap(),
+                outputs: LimitedVarArray::new(vec![ScSpecTypeDef::ScSpecTypeResult(Box::new(ScSpecTypeResult {
+                    ok_type: Box::new(ScSpecTypeDef::ScSpecTypeU256),
+                    error_type: udt("Error"),
+                }))])
+                .unwrap(),
+            }),
+        ]
+    }
+
+    fn wasm(entries: &[ScSpecEntry]) -> Vec<u8> {
+        let mut payload = vec![14];
+        payload.extend_from_slice(b"contractspecv0");
+        for entry in entries {
+            payload.extend(entry.to_xdr());
+        }
+
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        // an empty type section and the custom section with a two byte LEB128 length
+        wasm.extend_from_slice(&[1, 1, 0]);
+        wasm.extend_from_slice(&[0, 0x80 | (payload.len() & 0x7f) as u8, (payload.len() >> 7) as u8]);
+        wasm.extend(payload);
+        wasm
+    }
+
+    #[test]
+    fn from_wasm() {
+        let spec = ContractSpec::from_wasm(wasm(&entries())).unwrap();
+        assert_eq!(spec.get_entries(), &entries());
+        assert!(spec.get_function("pay").is_ok());
+        assert_eq!(
+            spec.get_function("transfer"),
+            Err(StellarSdkError::UnknownContractSpecEntry { name: b"transfer".to_vec() })
+        );
+        assert_eq!(ContractSpec::from_wasm(b"\0asm\x02\0\0\0"), Err(StellarSdkError::InvalidContractSpec));
+
+        let xdr: Vec<u8> = entries().iter().flat_map(|entry| entry.to_xdr()).collect();
+        assert_eq!(ContractSpec::from_base64_xdr_entries(base64::encode(xdr)), Ok(spec));
+    }
+
+    #[test]
+    fn encode_arguments() {
+        const ACCOUNT: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        const CONTRACT: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+        let spec = ContractSpec::new(entries());
+
+        let payment = SpecValue::object(vec![
+            ("amount", SpecValue::string("-170141183460469231731687303715884105728")),
+            ("to", SpecValue::string(ACCOUNT)),
+            ("memo", SpecValue::Null),
+        ]);
+        let arguments = SpecValue::Array(vec![
+            SpecValue::object(vec![("Token", SpecValue::Array(vec![SpecValue::string(CONTRACT)]))]),
+            SpecValue::Array(vec![payment.clone()]),
+        ]);
+        let encoded = spec.encode_arguments("pay", &arguments).unwrap();
+
+        let expected_payment = ScVal::new_map(vec![
+            crate::types::ScMapEntry {
+                key: ScVal::new_symbol("to").unwrap(),
+                val: crate::types::ScAddress::from_encoding(ACCOUNT).unwrap().into_sc_val().unwrap(),
+            },
+            crate::types::ScMapEntry { key: ScVal::new_symbol("memo").unwrap(), val: ScVal::ScvVoid },
+            crate::types::ScMapEntry {
+                key: ScVal::new_symbol("amount").unwrap(),
+                val: i128::MIN.into_sc_val().unwrap(),
+            },
+        ])
+        .unwrap();
+        assert_eq!(encoded[1], vec![expected_payment.clone()].into_sc_val().unwrap());
+        assert_eq!(
+            encoded[0],
+            vec![
+                ScVal::new_symbol("Token").unwrap(),
+                crate::types::ScAddress::from_encoding(CONTRACT).unwrap().into_sc_val().unwrap()
+            ]
+            .into_sc_val()
+            .unwrap()
+        );
+
+        let payments_type = &spec.get_function("pay").unwrap().inputs.get_vec()[1].type_;
+        let decoded = spec.decode_value(payments_type, encoded[1].clone()).unwrap();
+        assert_eq!(
+            decoded,
+            SpecValue::Array(vec![SpecValue::object(vec![
+                ("to", SpecValue::string(ACCOUNT)),
+                ("amount", SpecValue::string("-170141183460469231731687303715884105728")),
+                ("memo", SpecValue::Null),
+            ])])
+        );
+
+        let named_arguments =
+            SpecValue::object(vec![("payments", SpecValue::Array(vec![])), ("asset", SpecValue::string("Native"))]);
+        assert_eq!(
+            spec.encode_arguments("pay", &named_arguments).unwrap()[0],
+            vec![ScVal::new_symbol("Native").unwrap()].into_sc_val().unwrap()
+        );
+
+        let wrong_type = SpecValue::Array(vec![SpecValue::string("Native"), SpecValue::Integer(1)]);
+        assert_eq!(spec.encode_arguments("pay", &wrong_type), Err(StellarSdkError::ContractSpecMismatch));
+    }
+
+    #[test]
+    fn decode_result() {
+        let spec = ContractSpec::new(entries());
+
+        let value = SpecValue::string("115792089237316195423570985008687907853269984665640564039457584007913129639935");
+        let encoded = spec.encode_value(&ScSpecTypeDef::ScSpecTypeU256, &value).unwrap();
+        assert_eq!(spec.decode_result("pay", encoded), Ok(value));
+
+        let error = ScVal::ScvError(crate::types::ScError::SceContract(1));
+        assert_eq!(
+            spec.decode_result("pay", error),
+            Ok(SpecValue::object(vec![("error", SpecValue::string("InsufficientBalance"))]))
+        );
+
+        for value in ["-57896044618658097711785492504343953926634992332820282019728792003956564819968", "-1", "0", "12"]
+        {
+            let encoded = spec
+                .encode_value(&ScSpecTypeDef::ScSpecTypeI256, &SpecValue::string(value))
+                .unwrap();
+            assert_eq!(spec.decode_value(&ScSpecTypeDef::ScSpecTypeI256, encoded), Ok(SpecValue::string(value)));
+        }
+        for value in ["340282366920938463463374607431768211455", "170141183460469231731687303715884105728", "0"] {
+            let encoded = spec
+                .encode_value(&ScSpecTypeDef::ScSpecTypeU128, &SpecValue::string(value))
+                .unwrap();
+            assert_eq!(spec.decode_value(&ScSpecTypeDef::ScSpecTypeU128, encoded), Ok(SpecValue::string(value)));
+        }
+        for value in ["340282366920938463463374607431768211456", "-1"] {
+            assert_eq!(
+                spec.encode_value(&ScSpecTypeDef::ScSpecTypeU128, &SpecValue::string(value)),
+                Err(StellarSdkError::ContractSpecMismatch)
+            );
+        }
+        assert_eq!(
+            spec.encode_value(
+                &ScSpecTypeDef::ScSpecTypeI256,
+                &SpecValue::string("57896044618658097711785492504343953926634992332820282019728792003956564819968")
+            ),
+            Err(StellarSdkError::ContractSpecMismatch)
+        );
+    }
+}
@@ -0,0 +1,125 @@
+use core::cmp::Ordering;
+
+use crate::{
+    types::{
+        ChangeTrustAsset, LiquidityPoolConstantProductParameters, LiquidityPoolParameters, PoolId, TrustLineAsset,
+    },
+    utils::sha256::sha256,
+    Asset, StellarSdkError, XdrCodec,
+};
+
+pub use crate::types::LIQUIDITY_POOL_FEE_V18;
+
+/// Compare two assets in the order that the ledger requires for the assets of a liquidity pool
+///
+/// The native asset comes first, then the 4 character assets and then the 12 character assets.
+/// Assets of the same type are ordered by asset code and then by issuer.
+fn compare_assets(asset_a: &Asset, asset_b: &Asset) -> Ordering {
+    match (asset_a, asset_b) {
+        (Asset::AssetTypeNative, Asset::AssetTypeNative) => Ordering::Equal,
+        (Asset::AssetTypeNative, _) => Ordering::Less,
+        (_, Asset::AssetTypeNative) => Ordering::Greater,
+        (Asset::AssetTypeCreditAlphanum4(a), Asset::AssetTypeCreditAlphanum4(b)) =>
+            (a.asset_code, a.issuer.as_binary()).cmp(&(b.asset_code, b.issuer.as_binary())),
+        (Asset::AssetTypeCreditAlphanum4(_), _) => Ordering::Less,
+        (_, Asset::AssetTypeCreditAlphanum4(_)) => Ordering::Greater,
+        (Asset::AssetTypeCreditAlphanum12(a), Asset::AssetTypeCreditAlphanum12(b)) =>
+            (a.asset_code, a.issuer.as_binary()).cmp(&(b.asset_code, b.issuer.as_binary())),
+        (Asset::Default(_), _) | (_, Asset::Default(_)) => unreachable!(),
+    }
+}
+
+impl LiquidityPoolParameters {
+    /// Create the parameters of a constant product pool of two assets
+    ///
+    /// The assets can be given in any order, they are sorted as required by the ledger. The fee
+    /// is `LIQUIDITY_POOL_FEE_V18`.
+    pub fn constant_product(asset_a: Asset, asset_b: Asset) -> Result<Self, StellarSdkError> {
+        let (asset_a, asset_b) = match compare_assets(&asset_a, &asset_b) {
+            Ordering::Less => (asset_a, asset_b),
+            Ordering::Greater => (asset_b, asset_a),
+            Ordering::Equal => return Err(StellarSdkError::IdenticalLiquidityPoolAssets),
+        };
+
+        Ok(LiquidityPoolParameters::LiquidityPoolConstantProduct(LiquidityPoolConstantProductParameters {
+            asset_a,
+            asset_b,
+            fee: LIQUIDITY_POOL_FEE_V18,
+        }))
+    }
+
+    /// Return the id of the liquidity pool, the SHA-256 hash of the XDR encoded parameters
+    pub fn get_pool_id(&self) -> PoolId {
+        sha256(self.to_xdr())
+    }
+}
+
+impl ChangeTrustAsset {
+    /// Create the pool share asset of the constant product pool of two assets
+    pub fn pool_share(asset_a: Asset, asset_b: Asset) -> Result<Self, StellarSdkError> {
+        LiquidityPoolParameters::constant_product(asset_a, asset_b).map(ChangeTrustAsset::AssetTypePoolShare)
+    }
+}
+
+impl TrustLineAsset {
+    /// Create the asset of the trust line to the pool shares of the liquidity pool `parameters`
+    pub fn pool_share(parameters: &LiquidityPoolParameters) -> Self {
+        TrustLineAsset::AssetTypePoolShare(parameters.get_pool_id())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        types::{ChangeTrustAsset, LiquidityPoolParameters},
+        Asset, LedgerKey, StellarSdkError,
+    };
+
+    const ISSUER: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+
+    #[test]
+    fn constant_product_pool() {
+        let usdc = Asset::from_asset_code("USDC", ISSUER).unwrap();
+        let long = Asset::from_asset_code("LONGASSET", ISSUER).unwrap();
+
+        let parameters = LiquidityPoolParameters::constant_product(long.clone(), usdc.clone()).unwrap();
+        assert_eq!(parameters, LiquidityPoolParameters::constant_product(usdc.clone(), long).unwrap());
+        match &parameters {
+            LiquidityPoolParameters::LiquidityPoolConstantProduct(parameters) => {
+                assert_eq!(parameters.asset_a, usdc);
+                assert_eq!(parameters.fee, 30);
+            },
+        }
+
+        let native_pool = LiquidityPoolParameters::constant_product(usdc.clone(), Asset::native()).unwrap();
+        assert_ne!(native_pool.get_pool_id(), parameters.get_pool_id());
+        assert!(LedgerKey::from_liquidity_pool_id(native_pool.get_pool_id()).is_ok());
+
+        assert_eq!(
+            ChangeTrustAsset::pool_share(usdc.clone(), usdc),
+            Err(StellarSdkError::IdenticalLiquidityPoolAssets)
+        );
+    }
+
+    #[test]
+    fn known_pool_ids() {
+        // the test vector of stellar-base's `getLiquidityPoolId`
+        let arst = Asset::from_asset_code("ARST", "GB7TAYRUZGE6TVT7NHP5SMIZRNQA6PLM423EYISAOAP3MKYIQMVYP2JO").unwrap();
+        let usd = Asset::from_asset_code("USD", "GCEZWKCA5VLDNRLN3RPRJMRZOX3Z6G5CHCGSNFHEYVXM3XOJMDS674JZ").unwrap();
+        assert_eq!(
+            hex::encode(LiquidityPoolParameters::constant_product(usd, arst).unwrap().get_pool_id()),
+            "dd7b1ab831c273310ddbec6f97870aa83c2fbd78ce22aded37ecbf4f3380fac7"
+        );
+
+        // the XLM/USDC pool on the public network
+        let usdc = Asset::from_asset_code("USDC", "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN").unwrap();
+        assert_eq!(
+            hex::encode(
+                LiquidityPoolParameters::constant_product(usdc, Asset::native())
+                    .unwrap()
+                    .get_pool_id()
+            ),
+            "a468d41d8e9b8f3c7209651608b74b7db7ac9952dcae0cdf24871d1d9c7b0088"
+        );
+    }
+}
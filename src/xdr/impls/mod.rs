@@ -1,6 +1,14 @@
 pub mod operations;
 pub mod transaction;
+pub mod transaction_builder;
 pub mod transaction_envelope;
+pub mod validation;
+
+#[cfg(feature = "all-types")]
+pub mod contract_bindings;
+
+#[cfg(feature = "all-types")]
+pub mod contract_spec;
 
 #[cfg(feature = "all-types")]
 pub mod error;
@@ -19,13 +27,21 @@ pub mod asset;
 pub mod asset_code;
 pub mod claimable_balance_id;
 pub mod claimant;
+pub mod constant_product;
+pub mod contract_event;
 pub mod data_value;
 pub mod hash;
 pub mod ledger_key;
+pub mod liquidity_pool;
 pub mod memo;
 pub mod muxed_account;
+pub mod order_book;
+pub mod path_finding;
+pub mod preconditions;
 pub mod price;
+pub mod sc_address;
+pub mod sc_val;
 pub mod signer;
 pub mod signer_key;
+pub mod soroban_authorization;
 pub mod time_bounds;
-
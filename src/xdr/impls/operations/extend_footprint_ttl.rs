@@ -0,0 +1,33 @@
+use crate::{
+    types::{ExtendFootprintTtlOp, ExtensionPoint, OperationBody},
+    Operation, StellarSdkError,
+};
+
+impl Operation {
+    /// Extend the time to live of the entries in the read-only footprint of the transaction to
+    /// `extend_to` ledgers from the current ledger
+    pub fn new_extend_footprint_ttl(extend_to: u32) -> Result<Operation, StellarSdkError> {
+        Ok(Operation {
+            source_account: None,
+            body: OperationBody::ExtendFootprintTtl(ExtendFootprintTtlOp { ext: ExtensionPoint::V0, extend_to }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        types::{ExtendFootprintTtlOp, ExtensionPoint, OperationBody},
+        Operation, XdrCodec,
+    };
+
+    #[test]
+    fn extend_footprint_ttl() {
+        let operation = Operation::new_extend_footprint_ttl(535679).unwrap();
+        assert_eq!(
+            operation.body,
+            OperationBody::ExtendFootprintTtl(ExtendFootprintTtlOp { ext: ExtensionPoint::V0, extend_to: 535679 })
+        );
+        assert_eq!(Operation::from_xdr(operation.to_xdr()).unwrap(), operation);
+    }
+}
@@ -0,0 +1,200 @@
+use core::convert::AsRef;
+use sp_std::vec::Vec;
+
+use crate::{
+    compound_types::{LimitedString, LimitedVarArray, LimitedVarOpaque},
+    types::{
+        ContractExecutable, ContractIdPreimage, ContractIdPreimageFromAddress, CreateContractArgs,
+        CreateContractArgsV2, HostFunction, InvokeContractArgs, InvokeHostFunctionOp, OperationBody, ScVal,
+        SorobanAuthorizationEntry,
+    },
+    Asset, IntoHash, IntoScAddress, Operation, StellarSdkError,
+};
+
+impl Operation {
+    pub fn new_invoke_host_function(
+        host_function: HostFunction,
+        auth: Vec<SorobanAuthorizationEntry>,
+    ) -> Result<Operation, StellarSdkError> {
+        Ok(Operation {
+            source_account: None,
+            body: OperationBody::InvokeHostFunction(InvokeHostFunctionOp {
+                host_function,
+                auth: LimitedVarArray::new(auth)?,
+            }),
+        })
+    }
+
+    /// Call the function `function_name` of the contract `contract_address` with `args`
+    ///
+    /// The operation does not contain any authorization entries, they can be added with
+    /// `set_authorization` after simulating the transaction.
+    pub fn new_invoke_contract<T: IntoScAddress, S: AsRef<[u8]>>(
+        contract_address: T,
+        function_name: S,
+        args: Vec<ScVal>,
+    ) -> Result<Operation, StellarSdkError> {
+        Self::new_invoke_host_function(
+            HostFunction::HostFunctionTypeInvokeContract(InvokeContractArgs {
+                contract_address: contract_address.into_sc_address()?,
+                function_name: LimitedString::new(function_name.as_ref().to_vec())?,
+                args: LimitedVarArray::new(args)?,
+            }),
+            Vec::new(),
+        )
+    }
+
+    /// Upload the Wasm code of a contract
+    pub fn new_upload_contract_wasm<T: AsRef<[u8]>>(wasm: T) -> Result<Operation, StellarSdkError> {
+        Self::new_invoke_host_function(
+            HostFunction::HostFunctionTypeUploadContractWasm(LimitedVarOpaque::new(wasm.as_ref().to_vec())?),
+            Vec::new(),
+        )
+    }
+
+    /// Create a contract that runs the uploaded Wasm code with hash `wasm_hash`
+    ///
+    /// The contract id is derived from `deployer` and `salt`.
+    pub fn new_create_contract<T: IntoScAddress, S: IntoHash>(
+        deployer: T,
+        salt: [u8; 32],
+        wasm_hash: S,
+    ) -> Result<Operation, StellarSdkError> {
+        Self::new_invoke_host_function(
+            HostFunction::HostFunctionTypeCreateContract(CreateContractArgs {
+                contract_id_preimage: ContractIdPreimage::ContractIdPreimageFromAddress(
+                    ContractIdPreimageFromAddress { address: deployer.into_sc_address()?, salt },
+                ),
+                executable: ContractExecutable::ContractExecutableWasm(wasm_hash.into_hash()?),
+            }),
+            Vec::new(),
+        )
+    }
+
+    /// Create a contract like `new_create_contract` and call its constructor with
+    /// `constructor_args`
+    pub fn new_create_contract_with_constructor<T: IntoScAddress, S: IntoHash>(
+        deployer: T,
+        salt: [u8; 32],
+        wasm_hash: S,
+        constructor_args: Vec<ScVal>,
+    ) -> Result<Operation, StellarSdkError> {
+        Self::new_invoke_host_function(
+            HostFunction::HostFunctionTypeCreateContractV2(CreateContractArgsV2 {
+                contract_id_preimage: ContractIdPreimage::ContractIdPreimageFromAddress(
+                    ContractIdPreimageFromAddress { address: deployer.into_sc_address()?, salt },
+                ),
+                executable: ContractExecutable::ContractExecutableWasm(wasm_hash.into_hash()?),
+                constructor_args: LimitedVarArray::new(constructor_args)?,
+            }),
+            Vec::new(),
+        )
+    }
+
+    /// Deploy the Stellar asset contract of `asset`
+    pub fn new_create_stellar_asset_contract(asset: Asset) -> Result<Operation, StellarSdkError> {
+        Self::new_invoke_host_function(
+            HostFunction::HostFunctionTypeCreateContract(CreateContractArgs {
+                contract_id_preimage: ContractIdPreimage::ContractIdPreimageFromAsset(asset),
+                executable: ContractExecutable::ContractExecutableStellarAsset,
+            }),
+            Vec::new(),
+        )
+    }
+
+    /// Replace the authorization entries of an `InvokeHostFunction` operation
+    pub fn set_authorization(mut self, auth: Vec<SorobanAuthorizationEntry>) -> Result<Self, StellarSdkError> {
+        match &mut self.body {
+            OperationBody::InvokeHostFunction(operation) => operation.auth = LimitedVarArray::new(auth)?,
+            _ => return Err(StellarSdkError::NoInvokeHostFunctionOperation),
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        types::{
+            ContractExecutable, ContractIdPreimage, ContractIdPreimageFromAddress, HostFunction, OperationBody,
+            ScAddress, ScVal,
+        },
+        AsBinary, Asset, Operation, PublicKey, StellarSdkError, XdrCodec,
+    };
+
+    const DEPLOYER: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+    const WASM_HASH: [u8; 32] = [5; 32];
+
+    fn host_function(operation: &Operation) -> &HostFunction {
+        assert_eq!(&Operation::from_xdr(operation.to_xdr()).unwrap(), operation);
+        match &operation.body {
+            OperationBody::InvokeHostFunction(operation) => {
+                assert!(operation.auth.get_vec().is_empty());
+                &operation.host_function
+            },
+            _ => panic!("Expected an InvokeHostFunction operation"),
+        }
+    }
+
+    fn deployer_preimage() -> ContractIdPreimage {
+        ContractIdPreimage::ContractIdPreimageFromAddress(ContractIdPreimageFromAddress {
+            address: ScAddress::ScAddressTypeAccount(PublicKey::from_encoding(DEPLOYER).unwrap()),
+            salt: [3; 32],
+        })
+    }
+
+    #[test]
+    fn upload_contract_wasm() {
+        let operation = Operation::new_upload_contract_wasm(b"\0asm\x01\0\0\0").unwrap();
+        match host_function(&operation) {
+            HostFunction::HostFunctionTypeUploadContractWasm(wasm) => assert_eq!(wasm.get_vec(), b"\0asm\x01\0\0\0"),
+            host_function => panic!("Unexpected host function {:?}", host_function),
+        }
+    }
+
+    #[test]
+    fn create_contract() {
+        let operation = Operation::new_create_contract(DEPLOYER, [3; 32], WASM_HASH).unwrap();
+        match host_function(&operation) {
+            HostFunction::HostFunctionTypeCreateContract(args) => {
+                assert_eq!(args.contract_id_preimage, deployer_preimage());
+                assert_eq!(args.executable, ContractExecutable::ContractExecutableWasm(WASM_HASH));
+            },
+            host_function => panic!("Unexpected host function {:?}", host_function),
+        }
+
+        assert_eq!(
+            Operation::new_create_contract(DEPLOYER, [3; 32], AsBinary::Binary([5; 3])).err(),
+            Some(StellarSdkError::InvalidBinaryLength { found_length: 3, expected_length: 32 })
+        );
+    }
+
+    #[test]
+    fn create_contract_with_constructor() {
+        let constructor_args = vec![ScVal::ScvU32(7), ScVal::new_symbol("admin").unwrap()];
+        let operation =
+            Operation::new_create_contract_with_constructor(DEPLOYER, [3; 32], WASM_HASH, constructor_args.clone())
+                .unwrap();
+        match host_function(&operation) {
+            HostFunction::HostFunctionTypeCreateContractV2(args) => {
+                assert_eq!(args.contract_id_preimage, deployer_preimage());
+                assert_eq!(args.executable, ContractExecutable::ContractExecutableWasm(WASM_HASH));
+                assert_eq!(args.constructor_args.get_vec(), &constructor_args);
+            },
+            host_function => panic!("Unexpected host function {:?}", host_function),
+        }
+    }
+
+    #[test]
+    fn create_stellar_asset_contract() {
+        let asset = Asset::from_asset_code("USD", DEPLOYER).unwrap();
+        let operation = Operation::new_create_stellar_asset_contract(asset.clone()).unwrap();
+        match host_function(&operation) {
+            HostFunction::HostFunctionTypeCreateContract(args) => {
+                assert_eq!(args.contract_id_preimage, ContractIdPreimage::ContractIdPreimageFromAsset(asset));
+                assert_eq!(args.executable, ContractExecutable::ContractExecutableStellarAsset);
+            },
+            host_function => panic!("Unexpected host function {:?}", host_function),
+        }
+    }
+}
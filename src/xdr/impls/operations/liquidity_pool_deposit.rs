@@ -0,0 +1,78 @@
+use crate::{
+    types::{LiquidityPoolDepositOp, OperationBody},
+    IntoAmount, IntoHash, Operation, Price, StellarSdkError,
+};
+
+impl Operation {
+    pub fn new_liquidity_pool_deposit<T: IntoHash, S: IntoAmount, U: IntoAmount>(
+        liquidity_pool_id: T,
+        max_amount_a: S,
+        max_amount_b: U,
+        min_price: Price,
+        max_price: Price,
+    ) -> Result<Operation, StellarSdkError> {
+        Ok(Operation {
+            source_account: None,
+            body: OperationBody::LiquidityPoolDeposit(LiquidityPoolDepositOp {
+                liquidity_pool_id: liquidity_pool_id.into_hash()?,
+                max_amount_a: max_amount_a.into_stroop_amount(false)?,
+                max_amount_b: max_amount_b.into_stroop_amount(false)?,
+                min_price,
+                max_price,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        types::{LiquidityPoolDepositOp, OperationBody},
+        Operation, Price, StellarSdkError, StroopAmount, XdrCodec,
+    };
+
+    const POOL_ID: [u8; 32] = [9; 32];
+
+    #[test]
+    fn liquidity_pool_deposit() {
+        let min_price = Price::from_fraction(2, 1).unwrap();
+        let max_price = Price::from_fraction(1, 2).unwrap();
+        let operation = Operation::new_liquidity_pool_deposit(
+            POOL_ID,
+            "12.5",
+            StroopAmount(300),
+            min_price.clone(),
+            max_price.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            operation.body,
+            OperationBody::LiquidityPoolDeposit(LiquidityPoolDepositOp {
+                liquidity_pool_id: POOL_ID,
+                max_amount_a: 125_000_000,
+                max_amount_b: 300,
+                min_price,
+                max_price,
+            })
+        );
+        assert_eq!(Operation::from_xdr(operation.to_xdr()).unwrap(), operation);
+    }
+
+    #[test]
+    fn invalid_deposit_amounts() {
+        let price = Price::from_fraction(1, 1).unwrap();
+        assert_eq!(
+            Operation::new_liquidity_pool_deposit(POOL_ID, "0", "1", price.clone(), price.clone()).err(),
+            Some(StellarSdkError::AmountNonPositive)
+        );
+        assert_eq!(
+            Operation::new_liquidity_pool_deposit(POOL_ID, "1", StroopAmount(-1), price.clone(), price.clone()).err(),
+            Some(StellarSdkError::AmountNonPositive)
+        );
+        assert_eq!(
+            Operation::new_liquidity_pool_deposit(POOL_ID, "1.00000001", "1", price.clone(), price).err(),
+            Some(StellarSdkError::InvalidAmountString)
+        );
+        assert_eq!(Price::from_fraction(0, 1), Err(StellarSdkError::InvalidPrice));
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    types::{LiquidityPoolWithdrawOp, OperationBody},
+    IntoAmount, IntoHash, Operation, StellarSdkError,
+};
+
+impl Operation {
+    pub fn new_liquidity_pool_withdraw<T: IntoHash, S: IntoAmount, U: IntoAmount, V: IntoAmount>(
+        liquidity_pool_id: T,
+        amount: S,
+        min_amount_a: U,
+        min_amount_b: V,
+    ) -> Result<Operation, StellarSdkError> {
+        Ok(Operation {
+            source_account: None,
+            body: OperationBody::LiquidityPoolWithdraw(LiquidityPoolWithdrawOp {
+                liquidity_pool_id: liquidity_pool_id.into_hash()?,
+                amount: amount.into_stroop_amount(false)?,
+                min_amount_a: min_amount_a.into_stroop_amount(true)?,
+                min_amount_b: min_amount_b.into_stroop_amount(true)?,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        types::{LiquidityPoolWithdrawOp, OperationBody},
+        Operation, StellarSdkError, StroopAmount, XdrCodec,
+    };
+
+    const POOL_ID: [u8; 32] = [9; 32];
+
+    #[test]
+    fn liquidity_pool_withdraw() {
+        let operation = Operation::new_liquidity_pool_withdraw(POOL_ID, "3", StroopAmount(0), "0.0000001").unwrap();
+        assert_eq!(
+            operation.body,
+            OperationBody::LiquidityPoolWithdraw(LiquidityPoolWithdrawOp {
+                liquidity_pool_id: POOL_ID,
+                amount: 30_000_000,
+                min_amount_a: 0,
+                min_amount_b: 1,
+            })
+        );
+        assert_eq!(Operation::from_xdr(operation.to_xdr()).unwrap(), operation);
+    }
+
+    #[test]
+    fn invalid_withdraw_amounts() {
+        assert_eq!(
+            Operation::new_liquidity_pool_withdraw(POOL_ID, "0", "0", "0").err(),
+            Some(StellarSdkError::AmountNonPositive)
+        );
+        assert_eq!(
+            Operation::new_liquidity_pool_withdraw(POOL_ID, "1", StroopAmount(-1), "0").err(),
+            Some(StellarSdkError::AmountNegative)
+        );
+        assert_eq!(
+            Operation::new_liquidity_pool_withdraw(POOL_ID, "1", "0", StroopAmount(-1)).err(),
+            Some(StellarSdkError::AmountNegative)
+        );
+    }
+}
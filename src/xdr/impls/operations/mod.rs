@@ -12,13 +12,18 @@ pub mod create_account;
 pub mod create_claimable_balance;
 pub mod create_passive_sell_offer;
 pub mod end_sponsoring_future_reserves;
+pub mod extend_footprint_ttl;
 pub mod inflation;
+pub mod invoke_host_function;
+pub mod liquidity_pool_deposit;
+pub mod liquidity_pool_withdraw;
 pub mod manage_buy_offer;
 pub mod manage_data;
 pub mod manage_sell_offer;
 pub mod path_payment_strict_receive;
 pub mod path_payment_strict_send;
 pub mod payment;
+pub mod restore_footprint;
 pub mod revoke_sponsorship;
 pub mod set_options;
 pub mod set_trust_line_flags;
@@ -0,0 +1,29 @@
+use crate::{
+    types::{ExtensionPoint, OperationBody, RestoreFootprintOp},
+    Operation, StellarSdkError,
+};
+
+impl Operation {
+    /// Restore the archived entries in the read-write footprint of the transaction
+    pub fn new_restore_footprint() -> Result<Operation, StellarSdkError> {
+        Ok(Operation {
+            source_account: None,
+            body: OperationBody::RestoreFootprint(RestoreFootprintOp { ext: ExtensionPoint::V0 }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        types::{ExtensionPoint, OperationBody, RestoreFootprintOp},
+        Operation, XdrCodec,
+    };
+
+    #[test]
+    fn restore_footprint() {
+        let operation = Operation::new_restore_footprint().unwrap();
+        assert_eq!(operation.body, OperationBody::RestoreFootprint(RestoreFootprintOp { ext: ExtensionPoint::V0 }));
+        assert_eq!(Operation::from_xdr(operation.to_xdr()).unwrap(), operation);
+    }
+}
@@ -0,0 +1,963 @@
+//! Simulation of offers and path payments crossing the order book
+//!
+//! The exchange of stellar-core is reproduced including its rounding: offers are crossed in
+//! order of price and then offer id, each exchange is rounded in favor of the offer that stays
+//! in the book and exchanges of offers that deviate more than 1% from the offer price are
+//! skipped. Offers only cross the order book, path payments use a liquidity pool of the asset
+//! pair instead if its price is at least as good as the price of the order book.
+//!
+//! The balances and trust line limits of the accounts are not taken into account, every
+//! account is assumed to be able to send and receive any amount.
+
+use core::cmp::Ordering;
+use sp_std::{vec, vec::Vec};
+
+use crate::{
+    types::{
+        ClaimAtom, ClaimLiquidityAtom, ClaimOfferAtom, CreatePassiveSellOfferOp, LiquidityPoolEntry,
+        LiquidityPoolEntryBody, ManageBuyOfferOp, ManageSellOfferOp, OfferEntry, OfferEntryExt,
+        PathPaymentStrictReceiveOp, PathPaymentStrictSendOp,
+    },
+    AccountId, Asset, Price, SwapDirection,
+};
+
+/// The maximal number of offers that an operation can cross
+pub(crate) const MAX_OFFERS_TO_CROSS: usize = 1000;
+
+/// The flag of `OfferEntryFlags` that marks a passive offer
+const PASSIVE_FLAG: u32 = 1;
+
+/// An exchange is skipped if its price deviates more than 1/MAX_PRICE_ERROR from the offer price
+const MAX_PRICE_ERROR: u128 = 100;
+
+/// The book of offers and liquidity pools that operations are simulated against
+///
+/// The simulation updates the book: crossed offers are reduced or removed, new offers are
+/// added and the reserves of liquidity pools change.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct OrderBook {
+    pub offers: Vec<OfferEntry>,
+    pub liquidity_pools: Vec<LiquidityPoolEntry>,
+}
+
+/// The result of a simulated `ManageSellOffer`, `ManageBuyOffer` or `CreatePassiveSellOffer`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OfferOutcome {
+    pub offers_claimed: Vec<ClaimAtom>,
+    /// The amount of the selling asset that has been sold while crossing the book
+    pub amount_sold: i64,
+    /// The amount of the buying asset that has been bought while crossing the book
+    pub amount_bought: i64,
+    /// The offer that remains in the book, if any
+    pub offer: Option<OfferEntry>,
+}
+
+/// The result of a simulated path payment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPaymentOutcome {
+    pub offers_claimed: Vec<ClaimAtom>,
+    pub amount_sent: i64,
+    pub amount_received: i64,
+}
+
+/// The reason why a simulated operation fails, corresponds to the result codes of stellar-core
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrderBookError {
+    /// The amounts, the price or the assets of the operation are invalid
+    Malformed,
+
+    /// The offer that should be updated does not exist
+    OfferNotFound,
+
+    /// The offer would cross an offer of the same account
+    CrossSelf,
+
+    /// The order book and the liquidity pools cannot take the full amount of a path payment
+    TooFewOffers,
+
+    /// The destination would receive less than `dest_min`
+    UnderDestinationMinimum,
+
+    /// The source would have to send more than `send_max`
+    OverSendMaximum,
+
+    /// The operation would cross more than 1000 offers
+    ExceededWorkLimit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rounding {
+    Normal,
+    PathPaymentStrictSend,
+    PathPaymentStrictReceive,
+}
+
+/// The result of an exchange between an offer (selling wheat) and a taker (selling sheep)
+struct Exchange {
+    wheat_received: i64,
+    sheep_send: i64,
+    wheat_stays: bool,
+}
+
+/// The amounts that a taker exchanged with the order book or a liquidity pool
+struct Conversion {
+    sheep_sent: i64,
+    wheat_received: i64,
+    claims: Vec<ClaimAtom>,
+    /// The index and the remaining amount of every crossed offer
+    offer_amounts: Vec<(usize, i64)>,
+}
+
+/// The offers that a conversion may cross
+struct OfferFilter<'a> {
+    /// The worst price accepted for wheat and whether offers at exactly that price are skipped
+    max_wheat_price: Option<(&'a Price, bool)>,
+    /// The account of the taker, crossing one of its own offers fails
+    taker: Option<&'a AccountId>,
+    /// The number of offers that may still be crossed in the current operation
+    max_offers_to_cross: usize,
+}
+
+/// The parameters of an offer operation from the perspective of the sold asset
+struct OfferParameters<'a> {
+    selling: &'a Asset,
+    buying: &'a Asset,
+    price: Price,
+    offer_id: i64,
+    passive: bool,
+    max_sheep_send: i64,
+    max_wheat_receive: i64,
+}
+
+fn divide(value: u128, divisor: u128, round_up: bool) -> i64 {
+    let mut result = value / divisor;
+    if round_up && value % divisor != 0 {
+        result += 1;
+    }
+    result.min(i64::MAX as u128) as i64
+}
+
+fn compare_prices(price_a: &Price, price_b: &Price) -> Ordering {
+    (price_a.n as i64 * price_b.d as i64).cmp(&(price_b.n as i64 * price_a.d as i64))
+}
+
+fn is_valid_price(price: &Price) -> bool {
+    price.n > 0 && price.d > 0
+}
+
+fn offer_value(price_n: i32, price_d: i32, max_send: i64, max_receive: i64) -> u128 {
+    (max_send as u128 * price_n as u128).min(max_receive as u128 * price_d as u128)
+}
+
+fn exchange_without_price_error_thresholds(
+    price: &Price,
+    max_wheat_send: i64,
+    max_wheat_receive: i64,
+    max_sheep_send: i64,
+    max_sheep_receive: i64,
+    rounding: Rounding,
+) -> Exchange {
+    let (n, d) = (price.n as u128, price.d as u128);
+    let wheat_value = offer_value(price.n, price.d, max_wheat_send, max_sheep_receive);
+    let sheep_value = offer_value(price.d, price.n, max_sheep_send, max_wheat_receive);
+    let wheat_stays = sheep_value < wheat_value;
+
+    let (wheat_received, sheep_send) = if wheat_stays {
+        if rounding == Rounding::PathPaymentStrictSend {
+            (divide(sheep_value, n, false), max_sheep_send.min(max_sheep_receive))
+        } else if n > d || rounding == Rounding::PathPaymentStrictReceive {
+            let wheat_received = divide(sheep_value, n, false);
+            (wheat_received, divide(wheat_received as u128 * n, d, true))
+        } else {
+            let sheep_send = divide(sheep_value, d, false);
+            (divide(sheep_send as u128 * d, n, false), sheep_send)
+        }
+    } else if n > d {
+        let wheat_received = divide(wheat_value, n, false);
+        (wheat_received, divide(wheat_received as u128 * n, d, false))
+    } else {
+        let sheep_send = divide(wheat_value, d, false);
+        (divide(sheep_send as u128 * d, n, true), sheep_send)
+    };
+
+    Exchange { wheat_received, sheep_send, wheat_stays }
+}
+
+fn check_price_error_bound(price: &Price, wheat_received: i64, sheep_send: i64, can_favor_wheat: bool) -> bool {
+    let wheat_value = wheat_received as u128 * price.n as u128;
+    let sheep_value = sheep_send as u128 * price.d as u128;
+    if can_favor_wheat && sheep_value > wheat_value {
+        return true
+    }
+
+    let difference = match sheep_value > wheat_value {
+        true => sheep_value - wheat_value,
+        false => wheat_value - sheep_value,
+    };
+    MAX_PRICE_ERROR * difference <= wheat_value
+}
+
+fn exchange(
+    price: &Price,
+    max_wheat_send: i64,
+    max_wheat_receive: i64,
+    max_sheep_send: i64,
+    max_sheep_receive: i64,
+    rounding: Rounding,
+) -> Exchange {
+    let mut exchange = exchange_without_price_error_thresholds(
+        price,
+        max_wheat_send,
+        max_wheat_receive,
+        max_sheep_send,
+        max_sheep_receive,
+        rounding,
+    );
+
+    let within_bounds = exchange.wheat_received > 0 &&
+        exchange.sheep_send > 0 &&
+        check_price_error_bound(price, exchange.wheat_received, exchange.sheep_send, rounding != Rounding::Normal);
+    if !within_bounds {
+        exchange.wheat_received = 0;
+        exchange.sheep_send = 0;
+    }
+    exchange
+}
+
+/// Return the amount of an offer that can actually be exchanged at its price
+fn adjust_offer(price: &Price, max_wheat_send: i64, max_sheep_receive: i64) -> i64 {
+    exchange(price, max_wheat_send, i64::MAX, i64::MAX, max_sheep_receive, Rounding::Normal).wheat_received
+}
+
+fn get_path(send_asset: &Asset, path: &[Asset], dest_asset: &Asset) -> Vec<Asset> {
+    let mut assets = vec![send_asset.clone()];
+    assets.extend(path.iter().cloned());
+    assets.push(dest_asset.clone());
+    assets
+}
+
+impl OrderBook {
+    pub fn new(offers: Vec<OfferEntry>, liquidity_pools: Vec<LiquidityPoolEntry>) -> Self {
+        OrderBook { offers, liquidity_pools }
+    }
+
+    /// Return the indices of the offers that sell `selling` for `buying`, best offer first
+    fn get_offers(&self, selling: &Asset, buying: &Asset) -> Vec<usize> {
+        let mut indices: Vec<usize> = self
+            .offers
+            .iter()
+            .enumerate()
+            .filter(|(_, offer)| &offer.selling == selling && &offer.buying == buying)
+            .map(|(index, _)| index)
+            .collect();
+        indices.sort_by(|index_a, index_b| {
+            let (offer_a, offer_b) = (&self.offers[*index_a], &self.offers[*index_b]);
+            compare_prices(&offer_a.price, &offer_b.price).then(offer_a.offer_id.cmp(&offer_b.offer_id))
+        });
+        indices
+    }
+
+    /// Update the offers crossed by `conversion`, offers without a remaining amount are removed
+    fn apply_conversion(&mut self, conversion: &Conversion) {
+        let mut removed = Vec::new();
+        for (index, amount) in conversion.offer_amounts.iter() {
+            self.offers[*index].amount = *amount;
+            if *amount == 0 {
+                removed.push(*index);
+            }
+        }
+
+        removed.sort_unstable();
+        for index in removed.into_iter().rev() {
+            self.offers.remove(index);
+        }
+    }
+
+    /// Cross the offers that sell `wheat` for `sheep` until the taker has sent `max_sheep_send`
+    /// or received `max_wheat_receive`
+    ///
+    /// Offers with a price above the `max_wheat_price` of the `filter` (or equal if passive) are
+    /// not crossed. The book is not modified, see `apply_conversion`.
+    fn convert_with_offers(
+        &self,
+        sheep: &Asset,
+        max_sheep_send: i64,
+        wheat: &Asset,
+        max_wheat_receive: i64,
+        rounding: Rounding,
+        filter: &OfferFilter,
+    ) -> Result<Conversion, OrderBookError> {
+        let mut conversion =
+            Conversion { sheep_sent: 0, wheat_received: 0, claims: Vec::new(), offer_amounts: Vec::new() };
+
+        for index in self.get_offers(wheat, sheep) {
+            if conversion.sheep_sent >= max_sheep_send || conversion.wheat_received >= max_wheat_receive {
+                break
+            }
+            let offer = &self.offers[index];
+
+            if let Some((max_wheat_price, passive)) = filter.max_wheat_price {
+                match compare_prices(&offer.price, max_wheat_price) {
+                    Ordering::Greater => break,
+                    Ordering::Equal if passive => break,
+                    _ => (),
+                }
+            }
+            if filter.taker == Some(&offer.seller_id) {
+                return Err(OrderBookError::CrossSelf)
+            }
+
+            if conversion.claims.len() >= filter.max_offers_to_cross {
+                return Err(OrderBookError::ExceededWorkLimit)
+            }
+
+            let exchange = exchange(
+                &offer.price,
+                offer.amount,
+                max_wheat_receive - conversion.wheat_received,
+                max_sheep_send - conversion.sheep_sent,
+                i64::MAX,
+                rounding,
+            );
+
+            conversion.claims.push(ClaimAtom::ClaimAtomTypeOrderBook(ClaimOfferAtom {
+                seller_id: offer.seller_id.clone(),
+                offer_id: offer.offer_id,
+                asset_sold: wheat.clone(),
+                amount_sold: exchange.wheat_received,
+                asset_bought: sheep.clone(),
+                amount_bought: exchange.sheep_send,
+            }));
+            conversion.wheat_received += exchange.wheat_received;
+            conversion.sheep_sent += exchange.sheep_send;
+
+            let remaining_amount = match exchange.wheat_stays {
+                true => adjust_offer(&offer.price, offer.amount - exchange.wheat_received, i64::MAX),
+                false => 0,
+            };
+            conversion.offer_amounts.push((index, remaining_amount));
+
+            if exchange.wheat_stays {
+                break
+            }
+        }
+
+        Ok(conversion)
+    }
+
+    /// Return the index of the liquidity pool of two assets and the direction of a swap
+    fn get_liquidity_pool(&self, sheep: &Asset, wheat: &Asset) -> Option<(usize, SwapDirection)> {
+        self.liquidity_pools.iter().enumerate().find_map(|(index, pool)| {
+            let LiquidityPoolEntryBody::LiquidityPoolConstantProduct(pool) = &pool.body;
+            let (asset_a, asset_b) = (&pool.params.asset_a, &pool.params.asset_b);
+            if asset_a == sheep && asset_b == wheat {
+                Some((index, SwapDirection::AToB))
+            } else if asset_a == wheat && asset_b == sheep {
+                Some((index, SwapDirection::BToA))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Exchange the full amount of a path payment hop with the order book or the liquidity pool,
+    /// whichever gives the better price
+    fn convert_with_offers_and_pools(
+        &mut self,
+        sheep: &Asset,
+        max_sheep_send: i64,
+        wheat: &Asset,
+        max_wheat_receive: i64,
+        rounding: Rounding,
+        filter: &OfferFilter,
+    ) -> Result<Conversion, OrderBookError> {
+        let book_conversion = self
+            .convert_with_offers(sheep, max_sheep_send, wheat, max_wheat_receive, rounding, filter)
+            .and_then(|conversion| {
+                let is_complete = match rounding {
+                    Rounding::PathPaymentStrictReceive => conversion.wheat_received == max_wheat_receive,
+                    _ => conversion.sheep_sent == max_sheep_send,
+                };
+                match is_complete {
+                    true => Ok(conversion),
+                    false => Err(OrderBookError::TooFewOffers),
+                }
+            });
+
+        let pool_exchange = self.get_liquidity_pool(sheep, wheat).and_then(|(index, direction)| {
+            let LiquidityPoolEntryBody::LiquidityPoolConstantProduct(pool) = &self.liquidity_pools[index].body;
+            let amounts = match rounding {
+                Rounding::PathPaymentStrictReceive => pool
+                    .swap_strict_receive(direction, max_wheat_receive)
+                    .map(|to_pool| (to_pool, max_wheat_receive)),
+                _ => pool
+                    .swap_strict_send(direction, max_sheep_send)
+                    .map(|from_pool| (max_sheep_send, from_pool)),
+            };
+            amounts.map(|(to_pool, from_pool)| (index, direction, to_pool, from_pool))
+        });
+
+        let (index, direction, to_pool, from_pool) = match (pool_exchange, &book_conversion) {
+            (Some(pool_exchange), Err(_)) => pool_exchange,
+            (Some((index, direction, to_pool, from_pool)), Ok(book_conversion))
+                if to_pool as i128 * book_conversion.wheat_received as i128 <=
+                    book_conversion.sheep_sent as i128 * from_pool as i128 =>
+                (index, direction, to_pool, from_pool),
+            _ => {
+                let book_conversion = book_conversion?;
+                self.apply_conversion(&book_conversion);
+                return Ok(book_conversion)
+            },
+        };
+
+        let liquidity_pool = &mut self.liquidity_pools[index];
+        let LiquidityPoolEntryBody::LiquidityPoolConstantProduct(pool) = &mut liquidity_pool.body;
+        let (reserve_to_pool, reserve_from_pool) = match direction {
+            SwapDirection::AToB => (&mut pool.reserve_a, &mut pool.reserve_b),
+            SwapDirection::BToA => (&mut pool.reserve_b, &mut pool.reserve_a),
+        };
+        *reserve_to_pool += to_pool;
+        *reserve_from_pool -= from_pool;
+
+        Ok(Conversion {
+            sheep_sent: to_pool,
+            wheat_received: from_pool,
+            claims: vec![ClaimAtom::ClaimAtomTypeLiquidityPool(ClaimLiquidityAtom {
+                liquidity_pool_id: liquidity_pool.liquidity_pool_id,
+                asset_sold: wheat.clone(),
+                amount_sold: from_pool,
+                asset_bought: sheep.clone(),
+                amount_bought: to_pool,
+            })],
+            offer_amounts: Vec::new(),
+        })
+    }
+
+    fn manage_offer(
+        &mut self,
+        seller_id: &AccountId,
+        parameters: OfferParameters,
+        new_offer_id: i64,
+    ) -> Result<OfferOutcome, OrderBookError> {
+        let OfferParameters { selling, buying, price, offer_id, passive, max_sheep_send, max_wheat_receive } =
+            parameters;
+        if selling == buying || !is_valid_price(&price) || max_sheep_send < 0 || max_wheat_receive < 0 || offer_id < 0 {
+            return Err(OrderBookError::Malformed)
+        }
+
+        let mut flags = if passive { PASSIVE_FLAG } else { 0 };
+        if offer_id != 0 {
+            let index = self
+                .offers
+                .iter()
+                .position(|offer| offer.offer_id == offer_id && &offer.seller_id == seller_id)
+                .ok_or(OrderBookError::OfferNotFound)?;
+            flags = self.offers.remove(index).flags;
+        }
+
+        let mut outcome = OfferOutcome { offers_claimed: Vec::new(), amount_sold: 0, amount_bought: 0, offer: None };
+        if max_sheep_send == 0 || max_wheat_receive == 0 {
+            return match offer_id {
+                0 => Err(OrderBookError::Malformed),
+                _ => Ok(outcome),
+            }
+        }
+
+        let max_wheat_price = Price { n: price.d, d: price.n };
+        let filter = OfferFilter {
+            max_wheat_price: Some((&max_wheat_price, flags & PASSIVE_FLAG != 0)),
+            taker: Some(seller_id),
+            max_offers_to_cross: MAX_OFFERS_TO_CROSS,
+        };
+        let conversion =
+            self.convert_with_offers(selling, max_sheep_send, buying, max_wheat_receive, Rounding::Normal, &filter)?;
+        self.apply_conversion(&conversion);
+
+        let amount =
+            adjust_offer(&price, max_sheep_send - conversion.sheep_sent, max_wheat_receive - conversion.wheat_received);
+        if amount > 0 {
+            let offer = OfferEntry {
+                seller_id: seller_id.clone(),
+                offer_id: if offer_id != 0 { offer_id } else { new_offer_id },
+                selling: selling.clone(),
+                buying: buying.clone(),
+                amount,
+                price,
+                flags,
+                ext: OfferEntryExt::V0,
+            };
+            self.offers.push(offer.clone());
+            outcome.offer = Some(offer);
+        }
+
+        outcome.offers_claimed = conversion.claims;
+        outcome.amount_sold = conversion.sheep_sent;
+        outcome.amount_bought = conversion.wheat_received;
+        Ok(outcome)
+    }
+
+    /// Simulate a `ManageSellOffer` operation of `seller_id`
+    ///
+    /// A new offer gets the id `new_offer_id`.
+    pub fn manage_sell_offer(
+        &mut self,
+        seller_id: &AccountId,
+        operation: &ManageSellOfferOp,
+        new_offer_id: i64,
+    ) -> Result<OfferOutcome, OrderBookError> {
+        let parameters = OfferParameters {
+            selling: &operation.selling,
+            buying: &operation.buying,
+            price: operation.price.clone(),
+            offer_id: operation.offer_id,
+            passive: false,
+            max_sheep_send: operation.amount,
+            max_wheat_receive: i64::MAX,
+        };
+        self.manage_offer(seller_id, parameters, new_offer_id)
+    }
+
+    /// Simulate a `ManageBuyOffer` operation of `seller_id`
+    ///
+    /// The offer that remains in the book is a sell offer as stored in the ledger. A new offer
+    /// gets the id `new_offer_id`.
+    pub fn manage_buy_offer(
+        &mut self,
+        seller_id: &AccountId,
+        operation: &ManageBuyOfferOp,
+        new_offer_id: i64,
+    ) -> Result<OfferOutcome, OrderBookError> {
+        let parameters = OfferParameters {
+            selling: &operation.selling,
+            buying: &operation.buying,
+            price: Price { n: operation.price.d, d: operation.price.n },
+            offer_id: operation.offer_id,
+            passive: false,
+            max_sheep_send: i64::MAX,
+            max_wheat_receive: operation.buy_amount,
+        };
+        self.manage_offer(seller_id, parameters, new_offer_id)
+    }
+
+    /// Simulate a `CreatePassiveSellOffer` operation of `seller_id`
+    pub fn create_passive_sell_offer(
+        &mut self,
+        seller_id: &AccountId,
+        operation: &CreatePassiveSellOfferOp,
+        new_offer_id: i64,
+    ) -> Result<OfferOutcome, OrderBookError> {
+        let parameters = OfferParameters {
+            selling: &operation.selling,
+            buying: &operation.buying,
+            price: operation.price.clone(),
+            offer_id: 0,
+            passive: true,
+            max_sheep_send: operation.amount,
+            max_wheat_receive: i64::MAX,
+        };
+        self.manage_offer(seller_id, parameters, new_offer_id)
+    }
+
+    /// Send `send_amount` of the first asset of `path` along the path (strict send)
+    ///
+    /// Offers of `source` are not crossed. Returns the offers claimed and the amount of the last
+    /// asset received.
+    pub(crate) fn convert_along_path_strict_send(
+        &mut self,
+        source: Option<&AccountId>,
+        path: &[Asset],
+        send_amount: i64,
+    ) -> Result<(Vec<ClaimAtom>, i64), OrderBookError> {
+        let mut offers_claimed = Vec::new();
+        let mut amount = send_amount;
+        for hop in path.windows(2) {
+            let (sheep, wheat) = (&hop[0], &hop[1]);
+            if sheep == wheat {
+                continue
+            }
+
+            let filter = OfferFilter {
+                max_wheat_price: None,
+                taker: source,
+                max_offers_to_cross: MAX_OFFERS_TO_CROSS.saturating_sub(offers_claimed.len()),
+            };
+            let conversion = self.convert_with_offers_and_pools(
+                sheep,
+                amount,
+                wheat,
+                i64::MAX,
+                Rounding::PathPaymentStrictSend,
+                &filter,
+            )?;
+            offers_claimed.extend(conversion.claims);
+            amount = conversion.wheat_received;
+        }
+        Ok((offers_claimed, amount))
+    }
+
+    /// Receive `dest_amount` of the last asset of `path` along the path (strict receive)
+    ///
+    /// Offers of `source` are not crossed. Returns the offers claimed and the amount of the first
+    /// asset sent.
+    pub(crate) fn convert_along_path_strict_receive(
+        &mut self,
+        source: Option<&AccountId>,
+        path: &[Asset],
+        dest_amount: i64,
+    ) -> Result<(Vec<ClaimAtom>, i64), OrderBookError> {
+        let mut hop_claims: Vec<Vec<ClaimAtom>> = Vec::new();
+        let mut offers_crossed = 0;
+        let mut amount = dest_amount;
+        for hop in path.windows(2).rev() {
+            let (sheep, wheat) = (&hop[0], &hop[1]);
+            if sheep == wheat {
+                continue
+            }
+
+            let filter = OfferFilter {
+                max_wheat_price: None,
+                taker: source,
+                max_offers_to_cross: MAX_OFFERS_TO_CROSS.saturating_sub(offers_crossed),
+            };
+            let conversion = self.convert_with_offers_and_pools(
+                sheep,
+                i64::MAX,
+                wheat,
+                amount,
+                Rounding::PathPaymentStrictReceive,
+                &filter,
+            )?;
+            offers_crossed += conversion.claims.len();
+            hop_claims.push(conversion.claims);
+            amount = conversion.sheep_sent;
+        }
+        Ok((hop_claims.into_iter().rev().flatten().collect(), amount))
+    }
+
+    /// Simulate a `PathPaymentStrictSend` operation of `source`
+    pub fn path_payment_strict_send(
+        &mut self,
+        source: &AccountId,
+        operation: &PathPaymentStrictSendOp,
+    ) -> Result<PathPaymentOutcome, OrderBookError> {
+        if operation.send_amount <= 0 || operation.dest_min <= 0 {
+            return Err(OrderBookError::Malformed)
+        }
+
+        let path = get_path(&operation.send_asset, operation.path.get_vec(), &operation.dest_asset);
+        let (offers_claimed, amount) =
+            self.convert_along_path_strict_send(Some(source), &path, operation.send_amount)?;
+
+        if amount < operation.dest_min {
+            return Err(OrderBookError::UnderDestinationMinimum)
+        }
+
+        Ok(PathPaymentOutcome { offers_claimed, amount_sent: operation.send_amount, amount_received: amount })
+    }
+
+    /// Simulate a `PathPaymentStrictReceive` operation of `source`
+    pub fn path_payment_strict_receive(
+        &mut self,
+        source: &AccountId,
+        operation: &PathPaymentStrictReceiveOp,
+    ) -> Result<PathPaymentOutcome, OrderBookError> {
+        if operation.send_max <= 0 || operation.dest_amount <= 0 {
+            return Err(OrderBookError::Malformed)
+        }
+
+        let path = get_path(&operation.send_asset, operation.path.get_vec(), &operation.dest_asset);
+        let (offers_claimed, amount) =
+            self.convert_along_path_strict_receive(Some(source), &path, operation.dest_amount)?;
+
+        if amount > operation.send_max {
+            return Err(OrderBookError::OverSendMaximum)
+        }
+
+        Ok(PathPaymentOutcome { offers_claimed, amount_sent: amount, amount_received: operation.dest_amount })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OfferOutcome, OrderBook, OrderBookError};
+    use crate::{
+        compound_types::LimitedVarArray,
+        types::{
+            ClaimAtom, ClaimOfferAtom, LiquidityPoolEntry, LiquidityPoolEntryBody, LiquidityPoolEntryConstantProduct,
+            LiquidityPoolParameters, ManageBuyOfferOp, ManageSellOfferOp, OfferEntry, OfferEntryExt,
+            PathPaymentStrictReceiveOp, PathPaymentStrictSendOp,
+        },
+        AccountId, Asset, MuxedAccount, Price, PublicKey,
+    };
+
+    const SELLER_1: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+    const SELLER_2: &str = "GAPIIRF3JXQAY63NSD6ALZ6JVUYDXCQIQIH5MZA3XYMR7LADTFKRBXZB";
+    const TAKER: &str = "GBBPS4JXB72222FPAHBGPUH3M6XIPG3CFDLIWDVPTAXJFH4WLYL46HQW";
+
+    fn usd() -> Asset {
+        Asset::from_asset_code("USD", SELLER_1).unwrap()
+    }
+
+    fn offer(seller: &str, offer_id: i64, amount: i64, price: Price) -> OfferEntry {
+        OfferEntry {
+            seller_id: PublicKey::from_encoding(seller).unwrap(),
+            offer_id,
+            selling: usd(),
+            buying: Asset::native(),
+            amount,
+            price,
+            flags: 0,
+            ext: OfferEntryExt::V0,
+        }
+    }
+
+    fn book(xlm_reserve: i64, usd_reserve: i64) -> OrderBook {
+        let LiquidityPoolParameters::LiquidityPoolConstantProduct(params) =
+            LiquidityPoolParameters::constant_product(Asset::native(), usd()).unwrap();
+        let pool = LiquidityPoolEntry {
+            liquidity_pool_id: [1; 32],
+            body: LiquidityPoolEntryBody::LiquidityPoolConstantProduct(LiquidityPoolEntryConstantProduct {
+                params,
+                reserve_a: xlm_reserve,
+                reserve_b: usd_reserve,
+                total_pool_shares: 1,
+                pool_shares_trust_line_count: 1,
+            }),
+        };
+        OrderBook::new(
+            vec![
+                offer(SELLER_2, 2, 500_000_000, Price { n: 3, d: 1 }),
+                offer(SELLER_1, 1, 1_000_000_000, Price { n: 2, d: 1 }),
+            ],
+            vec![pool],
+        )
+    }
+
+    fn taker() -> AccountId {
+        PublicKey::from_encoding(TAKER).unwrap()
+    }
+
+    #[test]
+    fn offers() {
+        let mut order_book = book(0, 0);
+        let operation = ManageSellOfferOp {
+            selling: Asset::native(),
+            buying: usd(),
+            amount: 3_000_000_000,
+            price: Price { n: 1, d: 3 },
+            offer_id: 0,
+        };
+        let claim = |offer_id: i64, seller: &str, amount_sold: i64, amount_bought: i64| {
+            ClaimAtom::ClaimAtomTypeOrderBook(ClaimOfferAtom {
+                seller_id: PublicKey::from_encoding(seller).unwrap(),
+                offer_id,
+                asset_sold: usd(),
+                amount_sold,
+                asset_bought: Asset::native(),
+                amount_bought,
+            })
+        };
+        assert_eq!(
+            order_book.manage_sell_offer(&taker(), &operation, 10),
+            Ok(OfferOutcome {
+                offers_claimed: vec![
+                    claim(1, SELLER_1, 1_000_000_000, 2_000_000_000),
+                    claim(2, SELLER_2, 333_333_333, 999_999_999)
+                ],
+                amount_sold: 2_999_999_999,
+                amount_bought: 1_333_333_333,
+                offer: None,
+            })
+        );
+        assert_eq!(order_book.offers, vec![offer(SELLER_2, 2, 166_666_667, Price { n: 3, d: 1 })]);
+
+        let mut order_book = book(0, 0);
+        let operation = ManageBuyOfferOp {
+            selling: Asset::native(),
+            buying: usd(),
+            buy_amount: 100_000_000,
+            price: Price { n: 2, d: 1 },
+            offer_id: 0,
+        };
+        let outcome = order_book.manage_buy_offer(&taker(), &operation, 10).unwrap();
+        assert_eq!((outcome.amount_sold, outcome.amount_bought, outcome.offer), (200_000_000, 100_000_000, None));
+        assert_eq!(order_book.offers[1], offer(SELLER_1, 1, 900_000_000, Price { n: 2, d: 1 }));
+
+        let operation = ManageSellOfferOp {
+            selling: Asset::native(),
+            buying: usd(),
+            amount: 100_000_000,
+            price: Price { n: 1, d: 1 },
+            offer_id: 0,
+        };
+        let outcome = order_book.manage_sell_offer(&taker(), &operation, 10).unwrap();
+        assert_eq!(outcome.offers_claimed, vec![]);
+        assert_eq!(outcome.offer.map(|offer| (offer.offer_id, offer.amount)), Some((10, 100_000_000)));
+        assert_eq!(order_book.offers.len(), 3);
+
+        let operation = ManageSellOfferOp {
+            selling: Asset::native(),
+            buying: usd(),
+            amount: 100_000_000,
+            price: Price { n: 1, d: 2 },
+            offer_id: 0,
+        };
+        let seller = PublicKey::from_encoding(SELLER_1).unwrap();
+        assert_eq!(order_book.manage_sell_offer(&seller, &operation, 11), Err(OrderBookError::CrossSelf));
+    }
+
+    #[test]
+    fn path_payments() {
+        let strict_send = PathPaymentStrictSendOp {
+            send_asset: Asset::native(),
+            send_amount: 100_000_000,
+            destination: MuxedAccount::KeyTypeEd25519(*taker().as_binary()),
+            dest_asset: usd(),
+            dest_min: 1,
+            path: LimitedVarArray::new_empty(),
+        };
+
+        let mut order_book = book(10_000_000_000, 10_000_000_000);
+        let outcome = order_book.path_payment_strict_send(&taker(), &strict_send).unwrap();
+        assert_eq!(outcome.amount_received, 98_715_803);
+        assert!(matches!(outcome.offers_claimed[..], [ClaimAtom::ClaimAtomTypeLiquidityPool(_)]));
+        let LiquidityPoolEntryBody::LiquidityPoolConstantProduct(pool) = &order_book.liquidity_pools[0].body;
+        assert_eq!((pool.reserve_a, pool.reserve_b), (10_100_000_000, 9_901_284_197));
+
+        let mut order_book = book(10_000_000_000, 100_000_000);
+        let outcome = order_book.path_payment_strict_send(&taker(), &strict_send).unwrap();
+        assert_eq!(outcome.amount_received, 50_000_000);
+        assert!(matches!(outcome.offers_claimed[..], [ClaimAtom::ClaimAtomTypeOrderBook(_)]));
+
+        let strict_receive = PathPaymentStrictReceiveOp {
+            send_asset: Asset::native(),
+            send_max: 4_000_000_000,
+            destination: MuxedAccount::KeyTypeEd25519(*taker().as_binary()),
+            dest_asset: usd(),
+            dest_amount: 1_200_000_000,
+            path: LimitedVarArray::new(vec![Asset::native()]).unwrap(),
+        };
+        let outcome = order_book
+            .clone()
+            .path_payment_strict_receive(&taker(), &strict_receive)
+            .unwrap();
+        assert_eq!((outcome.amount_sent, outcome.offers_claimed.len()), (2_650_000_000, 2));
+
+        let strict_receive = PathPaymentStrictReceiveOp { send_max: 2_500_000_000, ..strict_receive };
+        assert_eq!(
+            order_book.path_payment_strict_receive(&taker(), &strict_receive),
+            Err(OrderBookError::OverSendMaximum)
+        );
+
+        let seller = PublicKey::from_encoding(SELLER_1).unwrap();
+        assert_eq!(book(0, 0).path_payment_strict_send(&seller, &strict_send), Err(OrderBookError::CrossSelf));
+    }
+
+    #[test]
+    fn path_payment_work_limit() {
+        let eur = Asset::from_asset_code("EUR", SELLER_1).unwrap();
+        let mut offers = Vec::new();
+        for offer_id in 1..=600 {
+            offers.push(offer(SELLER_2, offer_id, 10_000_000, Price { n: 1, d: 1 }));
+            offers.push(OfferEntry {
+                selling: eur.clone(),
+                buying: usd(),
+                ..offer(SELLER_2, 1000 + offer_id, 10_000_000, Price { n: 1, d: 1 })
+            });
+        }
+        let order_book = OrderBook::new(offers, vec![]);
+
+        let strict_send = PathPaymentStrictSendOp {
+            send_asset: Asset::native(),
+            send_amount: 6_000_000_000,
+            destination: MuxedAccount::KeyTypeEd25519(*taker().as_binary()),
+            dest_asset: usd(),
+            dest_min: 1,
+            path: LimitedVarArray::new_empty(),
+        };
+        let outcome = order_book.clone().path_payment_strict_send(&taker(), &strict_send).unwrap();
+        assert_eq!((outcome.amount_received, outcome.offers_claimed.len()), (6_000_000_000, 600));
+
+        let strict_send = PathPaymentStrictSendOp {
+            dest_asset: eur.clone(),
+            path: LimitedVarArray::new(vec![usd()]).unwrap(),
+            ..strict_send
+        };
+        assert_eq!(
+            order_book.clone().path_payment_strict_send(&taker(), &strict_send),
+            Err(OrderBookError::ExceededWorkLimit)
+        );
+
+        let strict_receive = PathPaymentStrictReceiveOp {
+            send_asset: Asset::native(),
+            send_max: i64::MAX,
+            destination: MuxedAccount::KeyTypeEd25519(*taker().as_binary()),
+            dest_asset: eur,
+            dest_amount: 6_000_000_000,
+            path: LimitedVarArray::new(vec![usd()]).unwrap(),
+        };
+        assert_eq!(
+            order_book.clone().path_payment_strict_receive(&taker(), &strict_receive),
+            Err(OrderBookError::ExceededWorkLimit)
+        );
+    }
+
+    #[test]
+    fn path_payment_work_limit_with_pool() {
+        // the first hop crosses all 1000 offers, the second hop is served by a pool and the
+        // third hop needs another offer
+        let (eur, gbp) =
+            (Asset::from_asset_code("EUR", SELLER_1).unwrap(), Asset::from_asset_code("GBP", SELLER_1).unwrap());
+        let mut offers: Vec<OfferEntry> = (1..=1000)
+            .map(|offer_id| offer(SELLER_2, offer_id, 1_000_000, Price { n: 1, d: 1 }))
+            .collect();
+        offers.push(OfferEntry {
+            selling: gbp.clone(),
+            buying: eur.clone(),
+            ..offer(SELLER_2, 1001, 10_000_000_000, Price { n: 1, d: 1 })
+        });
+        let LiquidityPoolParameters::LiquidityPoolConstantProduct(params) =
+            LiquidityPoolParameters::constant_product(eur.clone(), usd()).unwrap();
+        let pool = LiquidityPoolEntry {
+            liquidity_pool_id: [2; 32],
+            body: LiquidityPoolEntryBody::LiquidityPoolConstantProduct(LiquidityPoolEntryConstantProduct {
+                params,
+                reserve_a: 100_000_000_000,
+                reserve_b: 100_000_000_000,
+                total_pool_shares: 1,
+                pool_shares_trust_line_count: 1,
+            }),
+        };
+        let order_book = OrderBook::new(offers, vec![pool]);
+
+        let strict_send = PathPaymentStrictSendOp {
+            send_asset: Asset::native(),
+            send_amount: 1_000_000_000,
+            destination: MuxedAccount::KeyTypeEd25519(*taker().as_binary()),
+            dest_asset: gbp.clone(),
+            dest_min: 1,
+            path: LimitedVarArray::new(vec![usd(), eur.clone()]).unwrap(),
+        };
+        assert_eq!(
+            order_book.clone().path_payment_strict_send(&taker(), &strict_send),
+            Err(OrderBookError::ExceededWorkLimit)
+        );
+
+        let strict_receive = PathPaymentStrictReceiveOp {
+            send_asset: gbp,
+            send_max: i64::MAX,
+            destination: MuxedAccount::KeyTypeEd25519(*taker().as_binary()),
+            dest_asset: Asset::native(),
+            dest_amount: 1_000_000_000,
+            path: LimitedVarArray::new(vec![eur, usd()]).unwrap(),
+        };
+        let mut order_book = order_book;
+        for offer in order_book.offers.iter_mut() {
+            core::mem::swap(&mut offer.selling, &mut offer.buying);
+        }
+        assert_eq!(
+            order_book.path_payment_strict_receive(&taker(), &strict_receive),
+            Err(OrderBookError::ExceededWorkLimit)
+        );
+    }
+}
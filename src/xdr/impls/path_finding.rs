@@ -0,0 +1,449 @@
+//! Path finding for path payments over a snapshot of the order book
+//!
+//! Paths of up to `MAX_PATH_LENGTH` intermediate assets are searched one hop at a time. Every
+//! partial path carries its own copy of the `OrderBook`, so the amounts account for offers that
+//! are consumed by earlier hops of the same path. A partial path is only extended if it reaches
+//! its last asset with a better amount than every other path of at most the same number of
+//! hops, which bounds the search by the number of assets times the path length.
+//!
+//! Assets are explored in the order of their XDR encoding, the results are therefore
+//! deterministic for a given snapshot.
+
+use sp_std::{vec, vec::Vec};
+
+use crate::{types::LiquidityPoolEntryBody, xdr::impls::order_book::MAX_OFFERS_TO_CROSS, Asset, OrderBook, XdrCodec};
+
+/// The maximal number of intermediate assets of a path payment
+pub const MAX_PATH_LENGTH: usize = 5;
+
+/// A path found for a path payment
+///
+/// The fields correspond to the arguments of `Operation::new_path_payment_strict_send` and
+/// `Operation::new_path_payment_strict_receive`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaymentPath {
+    pub source_asset: Asset,
+    pub source_amount: i64,
+    pub destination_asset: Asset,
+    pub destination_amount: i64,
+    /// The intermediate assets between the source asset and the destination asset
+    pub path: Vec<Asset>,
+}
+
+/// The assets of an order book and the single hops between them
+struct Graph {
+    /// The assets ordered by their XDR encoding
+    assets: Vec<(Vec<u8>, Asset)>,
+    /// The indices of the assets that can be reached from an asset in a single hop
+    neighbors: Vec<Vec<usize>>,
+}
+
+/// A partial path of the search
+#[derive(Clone)]
+struct Candidate {
+    /// The indices of the assets of the path, starting with the asset of the fixed amount
+    assets: Vec<usize>,
+    /// The order book after the simulation of the path
+    order_book: OrderBook,
+    /// The variable amount at the last asset of the path
+    amount: i64,
+    offers_crossed: usize,
+}
+
+struct Search<'a> {
+    /// The assets that a path may end with
+    targets: &'a [Asset],
+    /// The fixed amount: the source amount for strict send, the destination amount otherwise
+    amount: i64,
+    strict_send: bool,
+}
+
+impl Graph {
+    /// Collect the hops of the offers and liquidity pools of `order_book`
+    ///
+    /// A hop leads from the asset that is sent to the asset that is received, or the other way
+    /// round if `backwards`.
+    fn new(order_book: &OrderBook, backwards: bool) -> Self {
+        let mut hops: Vec<(&Asset, &Asset)> =
+            order_book.offers.iter().map(|offer| (&offer.buying, &offer.selling)).collect();
+        for pool in order_book.liquidity_pools.iter() {
+            let LiquidityPoolEntryBody::LiquidityPoolConstantProduct(pool) = &pool.body;
+            hops.push((&pool.params.asset_a, &pool.params.asset_b));
+            hops.push((&pool.params.asset_b, &pool.params.asset_a));
+        }
+
+        let mut assets: Vec<(Vec<u8>, Asset)> = hops
+            .iter()
+            .flat_map(|(from, to)| vec![*from, *to])
+            .map(|asset| (asset.to_xdr(), asset.clone()))
+            .collect();
+        assets.sort_by(|(encoding_a, _), (encoding_b, _)| encoding_a.cmp(encoding_b));
+        assets.dedup_by(|(encoding_a, _), (encoding_b, _)| encoding_a == encoding_b);
+
+        let mut graph = Graph { neighbors: vec![Vec::new(); assets.len()], assets };
+        for (from, to) in hops {
+            let (from, to) = match backwards {
+                false => (from, to),
+                true => (to, from),
+            };
+            let (from, to) =
+                (graph.get_index(from).expect("Asset is known"), graph.get_index(to).expect("Asset is known"));
+            graph.neighbors[from].push(to);
+        }
+        for neighbors in graph.neighbors.iter_mut() {
+            neighbors.sort_unstable();
+            neighbors.dedup();
+        }
+        graph
+    }
+
+    fn get_index(&self, asset: &Asset) -> Option<usize> {
+        let encoding = asset.to_xdr();
+        self.assets
+            .binary_search_by(|(asset_encoding, _)| asset_encoding.cmp(&encoding))
+            .ok()
+    }
+
+    fn get_asset(&self, index: usize) -> &Asset {
+        &self.assets[index].1
+    }
+}
+
+impl Search<'_> {
+    /// Return whether `amount_a` is a strictly better variable amount than `amount_b`
+    fn is_better(&self, amount_a: i64, amount_b: Option<i64>) -> bool {
+        match (amount_b, self.strict_send) {
+            (None, _) => true,
+            (Some(amount_b), true) => amount_a > amount_b,
+            (Some(amount_b), false) => amount_a < amount_b,
+        }
+    }
+
+    /// Simulate the hop from the last asset of `candidate` to `next_asset`
+    fn extend(&self, graph: &Graph, candidate: &Candidate, next_asset: usize) -> Option<Candidate> {
+        let last_asset = graph.get_asset(*candidate.assets.last().expect("Path is not empty"));
+        let next_asset_value = graph.get_asset(next_asset);
+        let mut order_book = candidate.order_book.clone();
+        let result = match self.strict_send {
+            true => order_book.convert_along_path_strict_send(
+                None,
+                &[last_asset.clone(), next_asset_value.clone()],
+                candidate.amount,
+            ),
+            false => order_book.convert_along_path_strict_receive(
+                None,
+                &[next_asset_value.clone(), last_asset.clone()],
+                candidate.amount,
+            ),
+        };
+
+        let (claims, amount) = result.ok().filter(|(_, amount)| *amount > 0)?;
+        let offers_crossed = candidate.offers_crossed + claims.len();
+        if offers_crossed > MAX_OFFERS_TO_CROSS {
+            return None
+        }
+
+        let mut assets = candidate.assets.clone();
+        assets.push(next_asset);
+        Some(Candidate { assets, order_book, amount, offers_crossed })
+    }
+
+    fn to_payment_path(&self, graph: &Graph, candidate: &Candidate) -> PaymentPath {
+        let assets: Vec<Asset> = candidate.assets.iter().map(|index| graph.get_asset(*index).clone()).collect();
+        let (first, last) = (assets[0].clone(), assets[assets.len() - 1].clone());
+        let mut path = assets[1..assets.len() - 1].to_vec();
+        match self.strict_send {
+            true => PaymentPath {
+                source_asset: first,
+                source_amount: self.amount,
+                destination_asset: last,
+                destination_amount: candidate.amount,
+                path,
+            },
+            false => {
+                path.reverse();
+                PaymentPath {
+                    source_asset: last,
+                    source_amount: candidate.amount,
+                    destination_asset: first,
+                    destination_amount: self.amount,
+                    path,
+                }
+            },
+        }
+    }
+
+    /// Return the paths from `start_asset` to one of the targets, at most one path for every
+    /// target and path length
+    fn run(&self, order_book: &OrderBook, start_asset: &Asset) -> Vec<PaymentPath> {
+        let graph = Graph::new(order_book, !self.strict_send);
+        let start_asset = match graph.get_index(start_asset) {
+            Some(start_asset) if self.amount > 0 => start_asset,
+            _ => return Vec::new(),
+        };
+
+        let mut results = Vec::new();
+        let mut best_amounts: Vec<Option<i64>> = vec![None; graph.assets.len()];
+        let mut candidates = vec![Candidate {
+            assets: vec![start_asset],
+            order_book: order_book.clone(),
+            amount: self.amount,
+            offers_crossed: 0,
+        }];
+        for _ in 0..MAX_PATH_LENGTH + 1 {
+            let mut next_candidates: Vec<Option<Candidate>> = vec![None; graph.assets.len()];
+            for candidate in candidates.iter() {
+                let last_asset = *candidate.assets.last().expect("Path is not empty");
+                for next_asset in graph.neighbors[last_asset].iter().copied() {
+                    if candidate.assets.contains(&next_asset) {
+                        continue
+                    }
+
+                    let next_candidate = match self.extend(&graph, candidate, next_asset) {
+                        Some(next_candidate) => next_candidate,
+                        None => continue,
+                    };
+                    let current = next_candidates[next_asset].as_ref().map(|candidate| candidate.amount);
+                    if self.is_better(next_candidate.amount, best_amounts[next_asset]) &&
+                        self.is_better(next_candidate.amount, current)
+                    {
+                        next_candidates[next_asset] = Some(next_candidate);
+                    }
+                }
+            }
+
+            candidates = next_candidates.into_iter().flatten().collect();
+            for candidate in candidates.iter() {
+                let last_asset = *candidate.assets.last().expect("Path is not empty");
+                best_amounts[last_asset] = Some(candidate.amount);
+                if self.targets.contains(graph.get_asset(last_asset)) {
+                    results.push(self.to_payment_path(&graph, candidate));
+                }
+            }
+        }
+        results
+    }
+}
+
+impl OrderBook {
+    /// Find paths to send `source_amount` of `source_asset` to one of the `destination_assets`
+    ///
+    /// Returns at most `max_paths` paths, the paths with the highest destination amount first
+    /// and shorter paths first among equal amounts.
+    pub fn find_strict_send_paths(
+        &self,
+        source_asset: &Asset,
+        source_amount: i64,
+        destination_assets: &[Asset],
+        max_paths: usize,
+    ) -> Vec<PaymentPath> {
+        let search = Search { targets: destination_assets, amount: source_amount, strict_send: true };
+        let mut results = search.run(self, source_asset);
+        results.sort_by(|path_a, path_b| {
+            path_b
+                .destination_amount
+                .cmp(&path_a.destination_amount)
+                .then(path_a.path.len().cmp(&path_b.path.len()))
+        });
+        results.truncate(max_paths);
+        results
+    }
+
+    /// Find paths to receive `destination_amount` of `destination_asset` by sending one of the
+    /// `source_assets`
+    ///
+    /// Returns at most `max_paths` paths, the paths with the lowest source amount first and
+    /// shorter paths first among equal amounts.
+    pub fn find_strict_receive_paths(
+        &self,
+        source_assets: &[Asset],
+        destination_asset: &Asset,
+        destination_amount: i64,
+        max_paths: usize,
+    ) -> Vec<PaymentPath> {
+        let search = Search { targets: source_assets, amount: destination_amount, strict_send: false };
+        let mut results = search.run(self, destination_asset);
+        results.sort_by(|path_a, path_b| {
+            path_a
+                .source_amount
+                .cmp(&path_b.source_amount)
+                .then(path_a.path.len().cmp(&path_b.path.len()))
+        });
+        results.truncate(max_paths);
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PaymentPath, MAX_PATH_LENGTH};
+    use crate::{
+        compound_types::LimitedVarArray,
+        types::{
+            LiquidityPoolEntry, LiquidityPoolEntryBody, LiquidityPoolEntryConstantProduct, LiquidityPoolParameters,
+            OfferEntry, OfferEntryExt, PathPaymentStrictReceiveOp, PathPaymentStrictSendOp,
+        },
+        Asset, MuxedAccount, OrderBook, Price, PublicKey,
+    };
+    use scale_info::prelude::format;
+
+    const ISSUER: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+    const SOURCE: &str = "GAPIIRF3JXQAY63NSD6ALZ6JVUYDXCQIQIH5MZA3XYMR7LADTFKRBXZB";
+
+    fn asset(code: &str) -> Asset {
+        Asset::from_asset_code(code, ISSUER).unwrap()
+    }
+
+    fn offer(offer_id: i64, selling: Asset, buying: Asset, amount: i64, price: Price) -> OfferEntry {
+        OfferEntry {
+            seller_id: PublicKey::from_encoding(ISSUER).unwrap(),
+            offer_id,
+            selling,
+            buying,
+            amount,
+            price,
+            flags: 0,
+            ext: OfferEntryExt::V0,
+        }
+    }
+
+    fn pool(asset_a: Asset, asset_b: Asset, reserve_a: i64, reserve_b: i64) -> LiquidityPoolEntry {
+        let LiquidityPoolParameters::LiquidityPoolConstantProduct(params) =
+            LiquidityPoolParameters::constant_product(asset_a, asset_b).unwrap();
+        LiquidityPoolEntry {
+            liquidity_pool_id: [1; 32],
+            body: LiquidityPoolEntryBody::LiquidityPoolConstantProduct(LiquidityPoolEntryConstantProduct {
+                params,
+                reserve_a,
+                reserve_b,
+                total_pool_shares: 1,
+                pool_shares_trust_line_count: 1,
+            }),
+        }
+    }
+
+    fn order_book() -> OrderBook {
+        let pool = pool(Asset::native(), asset("EUR"), 10_000_000_000, 1_000_000_000);
+
+        OrderBook::new(
+            vec![
+                offer(1, asset("USD"), Asset::native(), 1_000_000_000, Price { n: 5, d: 1 }),
+                offer(2, asset("EUR"), asset("USD"), 1_000_000_000, Price { n: 1, d: 1 }),
+                offer(3, asset("EUR"), asset("USD"), 1_000_000_000, Price { n: 2, d: 1 }),
+            ],
+            vec![pool],
+        )
+    }
+
+    #[test]
+    fn strict_send_paths() {
+        let paths = order_book().find_strict_send_paths(&Asset::native(), 100_000_000, &[asset("EUR")], 10);
+        assert_eq!(
+            paths,
+            vec![
+                PaymentPath {
+                    source_asset: Asset::native(),
+                    source_amount: 100_000_000,
+                    destination_asset: asset("EUR"),
+                    destination_amount: 20_000_000,
+                    path: vec![asset("USD")],
+                },
+                PaymentPath {
+                    source_asset: Asset::native(),
+                    source_amount: 100_000_000,
+                    destination_asset: asset("EUR"),
+                    destination_amount: 9_871_580,
+                    path: vec![],
+                },
+            ]
+        );
+        assert_eq!(
+            order_book()
+                .find_strict_send_paths(&Asset::native(), 100_000_000, &[asset("EUR")], 1)
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn strict_receive_paths() {
+        let paths =
+            order_book().find_strict_receive_paths(&[Asset::native(), asset("USD")], &asset("EUR"), 20_000_000, 10);
+        let summary: Vec<_> = paths
+            .iter()
+            .map(|path| (path.source_asset.clone(), path.source_amount, path.path.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(asset("USD"), 20_000_000, 0), (Asset::native(), 100_000_000, 1), (Asset::native(), 204_695_720, 0),]
+        );
+    }
+
+    /// An order book of 40 assets, every asset is traded against the next 8 assets in both
+    /// directions at pseudo-random prices and the first 10 assets have a pool with lumens
+    fn large_order_book() -> OrderBook {
+        let assets: Vec<Asset> = (0..40).map(|index| asset(&format!("A{}", index))).collect();
+        let mut seed: u64 = 1;
+        let mut next_price = || {
+            seed = seed
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            Price { n: ((seed >> 33) % 20) as i32 + 1, d: ((seed >> 43) % 20) as i32 + 1 }
+        };
+
+        let mut offers = Vec::new();
+        for index in 0..assets.len() {
+            for step in 1..=8 {
+                let other = &assets[(index + step) % assets.len()];
+                let offer_id = offers.len() as i64 + 1;
+                offers.push(offer(offer_id, assets[index].clone(), other.clone(), 1_000_000_000, next_price()));
+                offers.push(offer(offer_id + 1, other.clone(), assets[index].clone(), 1_000_000_000, next_price()));
+            }
+        }
+        let pools = assets[..10]
+            .iter()
+            .map(|asset| pool(Asset::native(), asset.clone(), 100_000_000_000, 10_000_000_000))
+            .collect();
+        OrderBook::new(offers, pools)
+    }
+
+    #[test]
+    fn large_order_book_paths() {
+        let order_book = large_order_book();
+        let source = PublicKey::from_encoding(SOURCE).unwrap();
+        let destination = MuxedAccount::KeyTypeEd25519(*source.as_binary());
+        let targets = [asset("A25"), asset("A30")];
+
+        let paths = order_book.find_strict_send_paths(&Asset::native(), 100_000_000, &targets, 5);
+        assert_eq!(paths.len(), 5);
+        for path in paths.iter() {
+            assert!(path.path.len() <= MAX_PATH_LENGTH);
+            let operation = PathPaymentStrictSendOp {
+                send_asset: path.source_asset.clone(),
+                send_amount: path.source_amount,
+                destination: destination.clone(),
+                dest_asset: path.destination_asset.clone(),
+                dest_min: 1,
+                path: LimitedVarArray::new(path.path.clone()).unwrap(),
+            };
+            let outcome = order_book.clone().path_payment_strict_send(&source, &operation).unwrap();
+            assert_eq!(outcome.amount_received, path.destination_amount);
+        }
+
+        let paths = order_book.find_strict_receive_paths(&targets, &Asset::native(), 100_000_000, 5);
+        assert_eq!(paths.len(), 5);
+        for path in paths.iter() {
+            assert!(path.path.len() <= MAX_PATH_LENGTH);
+            let operation = PathPaymentStrictReceiveOp {
+                send_asset: path.source_asset.clone(),
+                send_max: path.source_amount,
+                destination: destination.clone(),
+                dest_asset: path.destination_asset.clone(),
+                dest_amount: path.destination_amount,
+                path: LimitedVarArray::new(path.path.clone()).unwrap(),
+            };
+            let outcome = order_book.clone().path_payment_strict_receive(&source, &operation).unwrap();
+            assert_eq!(outcome.amount_sent, path.source_amount);
+        }
+    }
+}
@@ -0,0 +1,269 @@
+//! Offline evaluation of transaction preconditions
+
+use crate::{
+    types::{
+        AccountEntry, AccountEntryExt, AccountEntryExtensionV1Ext, AccountEntryExtensionV2Ext,
+        FeeBumpTransactionInnerTx, Preconditions,
+    },
+    TimeBounds, Transaction, TransactionEnvelope,
+};
+
+/// The chain state that is relevant for the preconditions of a transaction
+///
+/// `close_time` and `ledger_sequence` describe the ledger in which the transaction would be
+/// applied. The remaining fields describe the source account of the transaction: its current
+/// sequence number and the close time and ledger sequence of the ledger in which the
+/// sequence number was last changed (see `AccountEntryExtensionV3`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LedgerSnapshot {
+    pub close_time: u64,
+    pub ledger_sequence: u32,
+    pub account_sequence_number: i64,
+    pub account_sequence_time: u64,
+    pub account_sequence_ledger: u32,
+}
+
+impl LedgerSnapshot {
+    /// Create a snapshot from the ledger entry of the source account
+    ///
+    /// If the account entry does not have an `AccountEntryExtensionV3`, the sequence time and
+    /// the sequence ledger are 0.
+    pub fn from_account_entry(close_time: u64, ledger_sequence: u32, account: &AccountEntry) -> Self {
+        let (account_sequence_time, account_sequence_ledger) = match &account.ext {
+            AccountEntryExt::V1(extension_v1) => match &extension_v1.ext {
+                AccountEntryExtensionV1Ext::V2(extension_v2) => match &extension_v2.ext {
+                    AccountEntryExtensionV2Ext::V3(extension_v3) => (extension_v3.seq_time, extension_v3.seq_ledger),
+                    _ => (0, 0),
+                },
+                _ => (0, 0),
+            },
+            _ => (0, 0),
+        };
+
+        LedgerSnapshot {
+            close_time,
+            ledger_sequence,
+            account_sequence_number: account.seq_num,
+            account_sequence_time,
+            account_sequence_ledger,
+        }
+    }
+}
+
+/// The precondition of a transaction that is not satisfied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreconditionFailure {
+    /// The close time is before the lower time bound
+    TooEarly { min_time: u64, close_time: u64 },
+
+    /// The close time is after the upper time bound, the transaction will never become valid
+    TooLate { max_time: u64, close_time: u64 },
+
+    /// The ledger sequence is below the lower ledger bound
+    LedgerTooEarly { min_ledger: u32, ledger_sequence: u32 },
+
+    /// The ledger sequence is not below the upper ledger bound, the transaction will never
+    /// become valid
+    LedgerTooLate { max_ledger: u32, ledger_sequence: u32 },
+
+    /// The sequence number of the source account does not fit to the transaction
+    BadSequenceNumber { account_sequence_number: i64, transaction_sequence_number: i64 },
+
+    /// The sequence number of the source account has been changed too recently (in seconds)
+    MinSequenceAgeNotReached { min_sequence_age: u64, sequence_age: u64 },
+
+    /// The sequence number of the source account has been changed too recently (in ledgers)
+    MinSequenceLedgerGapNotReached { min_sequence_ledger_gap: u32, sequence_ledger_gap: u32 },
+}
+
+impl PreconditionFailure {
+    /// Return whether the transaction can never become valid again
+    ///
+    /// This is the case when the upper time or ledger bound has been passed or the
+    /// sequence number of the source account is already too high.
+    pub fn is_permanent(&self) -> bool {
+        match self {
+            PreconditionFailure::TooLate { .. } | PreconditionFailure::LedgerTooLate { .. } => true,
+            PreconditionFailure::BadSequenceNumber { account_sequence_number, transaction_sequence_number } =>
+                account_sequence_number >= transaction_sequence_number,
+            _ => false,
+        }
+    }
+}
+
+fn check_time_bounds(time_bounds: &TimeBounds, snapshot: &LedgerSnapshot) -> Result<(), PreconditionFailure> {
+    let close_time = snapshot.close_time;
+    if close_time < time_bounds.min_time {
+        return Err(PreconditionFailure::TooEarly { min_time: time_bounds.min_time, close_time })
+    }
+    if time_bounds.max_time != 0 && close_time > time_bounds.max_time {
+        return Err(PreconditionFailure::TooLate { max_time: time_bounds.max_time, close_time })
+    }
+    Ok(())
+}
+
+impl Transaction {
+    /// Check whether the preconditions of this transaction are satisfied for the `snapshot`
+    ///
+    /// The checks are performed in the order of stellar-core: time bounds, ledger bounds,
+    /// sequence number, minimal sequence age and minimal sequence ledger gap. The first failing
+    /// check is returned. Extra signers are not checked here, since they are a matter of the
+    /// signatures of the transaction envelope.
+    pub fn check_preconditions(&self, snapshot: &LedgerSnapshot) -> Result<(), PreconditionFailure> {
+        let mut min_sequence_number = None;
+
+        match &self.cond {
+            Preconditions::PrecondNone => (),
+            Preconditions::PrecondTime(time_bounds) => check_time_bounds(time_bounds, snapshot)?,
+            Preconditions::PrecondV2(preconditions) => {
+                if let Some(time_bounds) = &preconditions.time_bounds {
+                    check_time_bounds(time_bounds, snapshot)?;
+                }
+
+                if let Some(ledger_bounds) = &preconditions.ledger_bounds {
+                    let ledger_sequence = snapshot.ledger_sequence;
+                    if ledger_sequence < ledger_bounds.min_ledger {
+                        return Err(PreconditionFailure::LedgerTooEarly {
+                            min_ledger: ledger_bounds.min_ledger,
+                            ledger_sequence,
+                        })
+                    }
+                    if ledger_bounds.max_ledger != 0 && ledger_sequence >= ledger_bounds.max_ledger {
+                        return Err(PreconditionFailure::LedgerTooLate {
+                            max_ledger: ledger_bounds.max_ledger,
+                            ledger_sequence,
+                        })
+                    }
+                }
+
+                min_sequence_number = preconditions.min_seq_num;
+            },
+        }
+
+        let account_sequence_number = snapshot.account_sequence_number;
+        let valid_sequence_number = match min_sequence_number {
+            Some(min_sequence_number) =>
+                account_sequence_number >= min_sequence_number && account_sequence_number < self.seq_num,
+            None => account_sequence_number.checked_add(1) == Some(self.seq_num),
+        };
+        if !valid_sequence_number {
+            return Err(PreconditionFailure::BadSequenceNumber {
+                account_sequence_number,
+                transaction_sequence_number: self.seq_num,
+            })
+        }
+
+        if let Preconditions::PrecondV2(preconditions) = &self.cond {
+            let sequence_age = snapshot.close_time.saturating_sub(snapshot.account_sequence_time);
+            if sequence_age < preconditions.min_seq_age {
+                return Err(PreconditionFailure::MinSequenceAgeNotReached {
+                    min_sequence_age: preconditions.min_seq_age,
+                    sequence_age,
+                })
+            }
+
+            let sequence_ledger_gap = snapshot.ledger_sequence.saturating_sub(snapshot.account_sequence_ledger);
+            if sequence_ledger_gap < preconditions.min_seq_ledger_gap {
+                return Err(PreconditionFailure::MinSequenceLedgerGapNotReached {
+                    min_sequence_ledger_gap: preconditions.min_seq_ledger_gap,
+                    sequence_ledger_gap,
+                })
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl TransactionEnvelope {
+    /// Check whether the preconditions of the transaction in this envelope are satisfied
+    ///
+    /// For a fee bump transaction the preconditions of the inner transaction are checked.
+    /// See `Transaction::check_preconditions`.
+    pub fn check_preconditions(&self, snapshot: &LedgerSnapshot) -> Result<(), PreconditionFailure> {
+        match self {
+            TransactionEnvelope::EnvelopeTypeTxV0(envelope) =>
+                Transaction::from(envelope.tx.clone()).check_preconditions(snapshot),
+            TransactionEnvelope::EnvelopeTypeTx(envelope) => envelope.tx.check_preconditions(snapshot),
+            TransactionEnvelope::EnvelopeTypeTxFeeBump(envelope) => match &envelope.tx.inner_tx {
+                FeeBumpTransactionInnerTx::EnvelopeTypeTx(inner) => inner.tx.check_preconditions(snapshot),
+                FeeBumpTransactionInnerTx::Default(_) => unreachable!(),
+            },
+            TransactionEnvelope::Default(_) => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Asset, LedgerSnapshot, Operation, PreconditionFailure, SecondEpochTime, TimeBounds, TransactionBuilder,
+    };
+
+    const ACCOUNT_ID1: &str = "GDGRDTRINPF66FNC47H22NY6BNWMCD5Q4XZTVA2KG7PFZ64WHRIU62TQ";
+    const ACCOUNT_ID2: &str = "GBNKQVTFRP25TIQRODMU5GJGSXDKHCEUDN7LNMOS5PNM427LMR77NV4M";
+
+    fn snapshot() -> LedgerSnapshot {
+        LedgerSnapshot {
+            close_time: 1_000,
+            ledger_sequence: 500,
+            account_sequence_number: 99,
+            account_sequence_time: 900,
+            account_sequence_ledger: 490,
+        }
+    }
+
+    fn builder() -> TransactionBuilder {
+        TransactionBuilder::new(ACCOUNT_ID1, 100)
+            .unwrap()
+            .add_operation(Operation::new_payment(ACCOUNT_ID2, Asset::native(), "1").unwrap())
+    }
+
+    #[test]
+    fn check_preconditions() {
+        let transaction = builder().set_timeout(SecondEpochTime(900), 200).build().unwrap();
+        assert_eq!(transaction.check_preconditions(&snapshot()), Ok(()));
+        assert_eq!(
+            transaction.check_preconditions(&LedgerSnapshot { close_time: 1_101, ..snapshot() }),
+            Err(PreconditionFailure::TooLate { max_time: 1_100, close_time: 1_101 })
+        );
+
+        let failure = transaction
+            .check_preconditions(&LedgerSnapshot { account_sequence_number: 100, ..snapshot() })
+            .unwrap_err();
+        assert_eq!(
+            failure,
+            PreconditionFailure::BadSequenceNumber { account_sequence_number: 100, transaction_sequence_number: 100 }
+        );
+        assert!(failure.is_permanent());
+
+        let transaction = builder()
+            .set_time_bounds(TimeBounds::from_time_points(SecondEpochTime(500), ()))
+            .set_ledger_bounds(495, 510)
+            .set_min_sequence_number(90)
+            .set_min_sequence_age(60)
+            .set_min_sequence_ledger_gap(5)
+            .build()
+            .unwrap();
+        assert_eq!(transaction.check_preconditions(&snapshot()), Ok(()));
+        assert_eq!(
+            transaction.check_preconditions(&LedgerSnapshot { account_sequence_number: 95, ..snapshot() }),
+            Ok(())
+        );
+        assert_eq!(
+            transaction.check_preconditions(&LedgerSnapshot { ledger_sequence: 510, ..snapshot() }),
+            Err(PreconditionFailure::LedgerTooLate { max_ledger: 510, ledger_sequence: 510 })
+        );
+        assert_eq!(
+            transaction.check_preconditions(&LedgerSnapshot { account_sequence_time: 950, ..snapshot() }),
+            Err(PreconditionFailure::MinSequenceAgeNotReached { min_sequence_age: 60, sequence_age: 50 })
+        );
+        assert_eq!(
+            transaction.check_preconditions(&LedgerSnapshot { account_sequence_ledger: 497, ..snapshot() }),
+            Err(PreconditionFailure::MinSequenceLedgerGapNotReached {
+                min_sequence_ledger_gap: 5,
+                sequence_ledger_gap: 3
+            })
+        );
+    }
+}
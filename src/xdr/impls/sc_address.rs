@@ -0,0 +1,145 @@
+use core::convert::{AsRef, TryInto};
+use sp_std::vec::Vec;
+
+use crate::{
+    types::{MuxedAccountMed25519, MuxedEd25519Account, ScAddress},
+    utils::key_encoding::{
+        decode_stellar_key, encode_stellar_key, CLAIMABLE_BALANCE_VERSION_BYTE, CONTRACT_VERSION_BYTE,
+        LIQUIDITY_POOL_VERSION_BYTE,
+    },
+    AccountId, ClaimableBalanceId, IntoAccountId, MuxedAccount, PublicKey, StellarSdkError,
+};
+
+impl ScAddress {
+    pub fn from_account_id<T: IntoAccountId>(account_id: T) -> Result<Self, StellarSdkError> {
+        account_id.into_account_id().map(ScAddress::ScAddressTypeAccount)
+    }
+
+    /// Return the account address or, if `muxed_account` has a muxed id, the muxed account address
+    pub fn from_muxed_account(muxed_account: MuxedAccount) -> Result<Self, StellarSdkError> {
+        match muxed_account {
+            MuxedAccount::KeyTypeEd25519(ed25519) =>
+                Ok(ScAddress::ScAddressTypeAccount(PublicKey::PublicKeyTypeEd25519(ed25519))),
+            MuxedAccount::KeyTypeMuxedEd25519(MuxedAccountMed25519 { id, ed25519 }) =>
+                Ok(ScAddress::ScAddressTypeMuxedAccount(MuxedEd25519Account { id, ed25519 })),
+            MuxedAccount::Default(_) => Err(StellarSdkError::InvalidStellarKeyEncoding),
+        }
+    }
+
+    pub fn from_contract_id(contract_id: [u8; 32]) -> Self {
+        ScAddress::ScAddressTypeContract(contract_id)
+    }
+
+    /// Decode an address from its strkey encoding
+    ///
+    /// The type of the address is determined by the first character: `G` (account), `M` (muxed
+    /// account), `C` (contract), `B` (claimable balance) or `L` (liquidity pool).
+    pub fn from_encoding<T: AsRef<[u8]>>(encoded_key: T) -> Result<Self, StellarSdkError> {
+        let encoded_key = encoded_key.as_ref();
+        match encoded_key.first() {
+            Some(b'G') => PublicKey::from_encoding(encoded_key).map(ScAddress::ScAddressTypeAccount),
+            Some(b'M') => match MuxedAccount::from_encoding(encoded_key)? {
+                MuxedAccount::KeyTypeMuxedEd25519(MuxedAccountMed25519 { id, ed25519 }) =>
+                    Ok(ScAddress::ScAddressTypeMuxedAccount(MuxedEd25519Account { id, ed25519 })),
+                _ => Err(StellarSdkError::InvalidStellarKeyEncoding),
+            },
+            Some(b'C') => Ok(ScAddress::ScAddressTypeContract(decode_stellar_key(encoded_key, CONTRACT_VERSION_BYTE)?)),
+            Some(b'B') => {
+                let raw_bytes: [u8; 33] = decode_stellar_key(encoded_key, CLAIMABLE_BALANCE_VERSION_BYTE)?;
+                match raw_bytes[0] {
+                    0 => Ok(ScAddress::ScAddressTypeClaimableBalance(ClaimableBalanceId::ClaimableBalanceIdTypeV0(
+                        raw_bytes[1..].try_into().unwrap(),
+                    ))),
+                    _ => Err(StellarSdkError::InvalidBalanceId),
+                }
+            },
+            Some(b'L') =>
+                Ok(ScAddress::ScAddressTypeLiquidityPool(decode_stellar_key(encoded_key, LIQUIDITY_POOL_VERSION_BYTE)?)),
+            found_version => Err(StellarSdkError::InvalidStellarKeyEncodingVersion {
+                expected_version: 'C',
+                found_version: found_version.copied().unwrap_or(b' ') as char,
+            }),
+        }
+    }
+
+    /// Return the strkey encoding of the address as an ASCII string (given as `Vec<u8>`)
+    pub fn to_encoding(&self) -> Vec<u8> {
+        match self {
+            ScAddress::ScAddressTypeAccount(account_id) => account_id.to_encoding(),
+            ScAddress::ScAddressTypeMuxedAccount(MuxedEd25519Account { id, ed25519 }) =>
+                MuxedAccount::KeyTypeMuxedEd25519(MuxedAccountMed25519 { id: *id, ed25519: *ed25519 }).to_encoding(),
+            ScAddress::ScAddressTypeContract(contract_id) => encode_stellar_key(contract_id, CONTRACT_VERSION_BYTE),
+            ScAddress::ScAddressTypeClaimableBalance(ClaimableBalanceId::ClaimableBalanceIdTypeV0(hash)) => {
+                let mut raw_bytes = [0u8; 33];
+                raw_bytes[1..].copy_from_slice(hash);
+                encode_stellar_key(&raw_bytes, CLAIMABLE_BALANCE_VERSION_BYTE)
+            },
+            ScAddress::ScAddressTypeLiquidityPool(pool_id) => encode_stellar_key(pool_id, LIQUIDITY_POOL_VERSION_BYTE),
+        }
+    }
+}
+
+pub trait IntoScAddress {
+    fn into_sc_address(self) -> Result<ScAddress, StellarSdkError>;
+}
+
+impl IntoScAddress for ScAddress {
+    fn into_sc_address(self) -> Result<ScAddress, StellarSdkError> {
+        Ok(self)
+    }
+}
+
+impl IntoScAddress for AccountId {
+    fn into_sc_address(self) -> Result<ScAddress, StellarSdkError> {
+        Ok(ScAddress::ScAddressTypeAccount(self))
+    }
+}
+
+impl IntoScAddress for MuxedAccount {
+    fn into_sc_address(self) -> Result<ScAddress, StellarSdkError> {
+        ScAddress::from_muxed_account(self)
+    }
+}
+
+impl<T: AsRef<[u8]>> IntoScAddress for T {
+    fn into_sc_address(self) -> Result<ScAddress, StellarSdkError> {
+        ScAddress::from_encoding(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryInto;
+
+    use crate::{types::ScAddress, PublicKey};
+
+    #[test]
+    fn sc_address_encoding() {
+        let contract = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+        let address = ScAddress::from_encoding(contract).unwrap();
+        assert_eq!(
+            address,
+            ScAddress::ScAddressTypeContract(
+                hex::decode("363eaa3867841fbad0f4ed88c779e4fe66e56a2470dc98c0ec9c073d05c7b103")
+                    .unwrap()
+                    .try_into()
+                    .unwrap()
+            )
+        );
+        assert_eq!(address.to_encoding(), contract.as_bytes());
+
+        let account = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+        let address = ScAddress::from_encoding(account).unwrap();
+        assert_eq!(address, ScAddress::ScAddressTypeAccount(PublicKey::from_encoding(account).unwrap()));
+        assert_eq!(address.to_encoding(), account.as_bytes());
+
+        for encoding in [
+            "MA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAAAAAAE2JUG6",
+            "BAADMPVKHBTYIH522D2O3CGHPHSP4ZXFNISHBXEYYDWJYBZ5AXD3CA3GDE",
+            "LA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGZ5J",
+        ] {
+            assert_eq!(ScAddress::from_encoding(encoding).unwrap().to_encoding(), encoding.as_bytes());
+        }
+        assert!(ScAddress::from_encoding("SA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ").is_err());
+    }
+}
@@ -0,0 +1,575 @@
+//! Conversions between Rust values and `ScVal`, the values of Soroban contracts
+//!
+//! Maps are sorted by their keys in the order of the Soroban host, otherwise the host rejects
+//! them. Symbols and byte strings need the wrappers `AsScSymbol` and `AsScBytes` because
+//! Rust strings are converted to `ScvString` and vectors to `ScvVec`.
+
+use core::{
+    cmp::Ordering,
+    convert::{AsRef, TryInto},
+};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+use crate::{
+    compound_types::{LimitedString, LimitedVarArray, LimitedVarOpaque},
+    lib::String,
+    types::{
+        Int128Parts, Int256Parts, ScAddress, ScMapEntry, ScSymbol, ScVal, ScValType, UInt128Parts, UInt256Parts,
+        SCSYMBOL_LIMIT,
+    },
+    AccountId, MuxedAccount, StellarSdkError, XdrCodec,
+};
+
+pub trait IntoScVal {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError>;
+}
+
+pub trait TryFromScVal: Sized {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError>;
+}
+
+/// A symbol, i.e., a string of at most `SCSYMBOL_LIMIT` characters `a-zA-Z0-9_`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AsScSymbol<T>(pub T);
+
+/// A byte string that is converted to `ScvBytes`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AsScBytes<T>(pub T);
+
+impl ScVal {
+    pub fn get_type(&self) -> ScValType {
+        match self {
+            ScVal::ScvBool(_) => ScValType::ScvBool,
+            ScVal::ScvVoid => ScValType::ScvVoid,
+            ScVal::ScvError(_) => ScValType::ScvError,
+            ScVal::ScvU32(_) => ScValType::ScvU32,
+            ScVal::ScvI32(_) => ScValType::ScvI32,
+            ScVal::ScvU64(_) => ScValType::ScvU64,
+            ScVal::ScvI64(_) => ScValType::ScvI64,
+            ScVal::ScvTimepoint(_) => ScValType::ScvTimepoint,
+            ScVal::ScvDuration(_) => ScValType::ScvDuration,
+            ScVal::ScvU128(_) => ScValType::ScvU128,
+            ScVal::ScvI128(_) => ScValType::ScvI128,
+            ScVal::ScvU256(_) => ScValType::ScvU256,
+            ScVal::ScvI256(_) => ScValType::ScvI256,
+            ScVal::ScvBytes(_) => ScValType::ScvBytes,
+            ScVal::ScvString(_) => ScValType::ScvString,
+            ScVal::ScvSymbol(_) => ScValType::ScvSymbol,
+            ScVal::ScvVec(_) => ScValType::ScvVec,
+            ScVal::ScvMap(_) => ScValType::ScvMap,
+            ScVal::ScvAddress(_) => ScValType::ScvAddress,
+            ScVal::ScvContractInstance(_) => ScValType::ScvContractInstance,
+            ScVal::ScvLedgerKeyContractInstance => ScValType::ScvLedgerKeyContractInstance,
+            ScVal::ScvLedgerKeyNonce(_) => ScValType::ScvLedgerKeyNonce,
+        }
+    }
+
+    /// Create a map from `entries`, sorted by their keys as required by the Soroban host
+    ///
+    /// Returns an error if two keys are equal.
+    pub fn new_map(mut entries: Vec<ScMapEntry>) -> Result<ScVal, StellarSdkError> {
+        entries.sort_by(|entry_a, entry_b| compare_sc_vals(&entry_a.key, &entry_b.key));
+        if entries.windows(2).any(|pair| pair[0].key == pair[1].key) {
+            return Err(StellarSdkError::InvalidScVal)
+        }
+        Ok(ScVal::ScvMap(Some(LimitedVarArray::new(entries)?)))
+    }
+}
+
+fn compare_slices<T>(slice_a: &[T], slice_b: &[T], compare: impl Fn(&T, &T) -> Ordering) -> Ordering {
+    for (element_a, element_b) in slice_a.iter().zip(slice_b.iter()) {
+        match compare(element_a, element_b) {
+            Ordering::Equal => continue,
+            ordering => return ordering,
+        }
+    }
+    slice_a.len().cmp(&slice_b.len())
+}
+
+/// Compare two values in the order used by the Soroban host
+///
+/// Values of different types are ordered by their type. Values of the remaining types without
+/// a natural order (addresses, errors, contract instances, nonce keys) are compared by their
+/// XDR encoding.
+pub(crate) fn compare_sc_vals(value_a: &ScVal, value_b: &ScVal) -> Ordering {
+    match (value_a, value_b) {
+        (ScVal::ScvBool(a), ScVal::ScvBool(b)) => a.cmp(b),
+        (ScVal::ScvU32(a), ScVal::ScvU32(b)) => a.cmp(b),
+        (ScVal::ScvI32(a), ScVal::ScvI32(b)) => a.cmp(b),
+        (ScVal::ScvU64(a), ScVal::ScvU64(b)) |
+        (ScVal::ScvTimepoint(a), ScVal::ScvTimepoint(b)) |
+        (ScVal::ScvDuration(a), ScVal::ScvDuration(b)) => a.cmp(b),
+        (ScVal::ScvI64(a), ScVal::ScvI64(b)) => a.cmp(b),
+        (ScVal::ScvU128(a), ScVal::ScvU128(b)) => (a.hi, a.lo).cmp(&(b.hi, b.lo)),
+        (ScVal::ScvI128(a), ScVal::ScvI128(b)) => (a.hi, a.lo).cmp(&(b.hi, b.lo)),
+        (ScVal::ScvU256(a), ScVal::ScvU256(b)) =>
+            (a.hi_hi, a.hi_lo, a.lo_hi, a.lo_lo).cmp(&(b.hi_hi, b.hi_lo, b.lo_hi, b.lo_lo)),
+        (ScVal::ScvI256(a), ScVal::ScvI256(b)) =>
+            (a.hi_hi, a.hi_lo, a.lo_hi, a.lo_lo).cmp(&(b.hi_hi, b.hi_lo, b.lo_hi, b.lo_lo)),
+        (ScVal::ScvBytes(a), ScVal::ScvBytes(b)) => a.get_vec().cmp(b.get_vec()),
+        (ScVal::ScvString(a), ScVal::ScvString(b)) => a.get_vec().cmp(b.get_vec()),
+        (ScVal::ScvSymbol(a), ScVal::ScvSymbol(b)) => a.get_vec().cmp(b.get_vec()),
+        (ScVal::ScvVec(a), ScVal::ScvVec(b)) => match (a, b) {
+            (Some(a), Some(b)) => compare_slices(a.get_vec(), b.get_vec(), compare_sc_vals),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        },
+        (ScVal::ScvMap(a), ScVal::ScvMap(b)) => match (a, b) {
+            (Some(a), Some(b)) => compare_slices(a.get_vec(), b.get_vec(), |entry_a, entry_b| {
+                compare_sc_vals(&entry_a.key, &entry_b.key).then_with(|| compare_sc_vals(&entry_a.val, &entry_b.val))
+            }),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        },
+        (value_a, value_b) => match (value_a.get_type() as i32).cmp(&(value_b.get_type() as i32)) {
+            Ordering::Equal => value_a.to_xdr().cmp(&value_b.to_xdr()),
+            ordering => ordering,
+        },
+    }
+}
+
+fn unexpected_type(expected: ScValType, value: &ScVal) -> StellarSdkError {
+    StellarSdkError::UnexpectedScValType { expected, found: value.get_type() }
+}
+
+impl IntoScVal for ScVal {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        Ok(self)
+    }
+}
+
+impl TryFromScVal for ScVal {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        Ok(value)
+    }
+}
+
+macro_rules! sc_val_primitive {
+    ($rust_type:ty, $variant:ident) => {
+        impl IntoScVal for $rust_type {
+            fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+                Ok(ScVal::$variant(self))
+            }
+        }
+
+        impl TryFromScVal for $rust_type {
+            fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+                match value {
+                    ScVal::$variant(value) => Ok(value),
+                    value => Err(unexpected_type(ScValType::$variant, &value)),
+                }
+            }
+        }
+    };
+}
+
+sc_val_primitive!(bool, ScvBool);
+sc_val_primitive!(u32, ScvU32);
+sc_val_primitive!(i32, ScvI32);
+sc_val_primitive!(u64, ScvU64);
+sc_val_primitive!(i64, ScvI64);
+sc_val_primitive!(UInt128Parts, ScvU128);
+sc_val_primitive!(Int128Parts, ScvI128);
+sc_val_primitive!(UInt256Parts, ScvU256);
+sc_val_primitive!(Int256Parts, ScvI256);
+sc_val_primitive!(ScAddress, ScvAddress);
+
+impl IntoScVal for u128 {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        Ok(ScVal::ScvU128(UInt128Parts { hi: (self >> 64) as u64, lo: self as u64 }))
+    }
+}
+
+impl TryFromScVal for u128 {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        UInt128Parts::try_from_sc_val(value).map(|parts| (parts.hi as u128) << 64 | parts.lo as u128)
+    }
+}
+
+impl IntoScVal for i128 {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        Ok(ScVal::ScvI128(Int128Parts { hi: (self >> 64) as i64, lo: self as u64 }))
+    }
+}
+
+impl TryFromScVal for i128 {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        Int128Parts::try_from_sc_val(value).map(|parts| (parts.hi as i128) << 64 | parts.lo as i128)
+    }
+}
+
+fn split_be_bytes(bytes: [u8; 32]) -> [u64; 4] {
+    let mut parts = [0u64; 4];
+    for (part, chunk) in parts.iter_mut().zip(bytes.chunks_exact(8)) {
+        *part = u64::from_be_bytes(chunk.try_into().unwrap());
+    }
+    parts
+}
+
+fn join_be_bytes(parts: [u64; 4]) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    for (chunk, part) in bytes.chunks_exact_mut(8).zip(parts.iter()) {
+        chunk.copy_from_slice(&part.to_be_bytes());
+    }
+    bytes
+}
+
+impl UInt256Parts {
+    /// Create the 256-bit unsigned integer from its big endian representation
+    pub fn from_be_bytes(bytes: [u8; 32]) -> Self {
+        let [hi_hi, hi_lo, lo_hi, lo_lo] = split_be_bytes(bytes);
+        UInt256Parts { hi_hi, hi_lo, lo_hi, lo_lo }
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        join_be_bytes([self.hi_hi, self.hi_lo, self.lo_hi, self.lo_lo])
+    }
+}
+
+impl Int256Parts {
+    /// Create the 256-bit signed integer from its big endian two's complement representation
+    pub fn from_be_bytes(bytes: [u8; 32]) -> Self {
+        let [hi_hi, hi_lo, lo_hi, lo_lo] = split_be_bytes(bytes);
+        Int256Parts { hi_hi: hi_hi as i64, hi_lo, lo_hi, lo_lo }
+    }
+
+    pub fn to_be_bytes(&self) -> [u8; 32] {
+        join_be_bytes([self.hi_hi as u64, self.hi_lo, self.lo_hi, self.lo_lo])
+    }
+}
+
+impl IntoScVal for () {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        Ok(ScVal::ScvVoid)
+    }
+}
+
+impl TryFromScVal for () {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        match value {
+            ScVal::ScvVoid => Ok(()),
+            value => Err(unexpected_type(ScValType::ScvVoid, &value)),
+        }
+    }
+}
+
+impl IntoScVal for &str {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        Ok(ScVal::ScvString(LimitedString::new(self.as_bytes().to_vec())?))
+    }
+}
+
+impl IntoScVal for String {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        Ok(ScVal::ScvString(LimitedString::new(self.into_bytes())?))
+    }
+}
+
+impl TryFromScVal for String {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        match value {
+            ScVal::ScvString(string) => String::from_utf8(string.into_vec()).map_err(|_| StellarSdkError::InvalidScVal),
+            value => Err(unexpected_type(ScValType::ScvString, &value)),
+        }
+    }
+}
+
+impl ScVal {
+    /// Create a symbol and check that it consists of at most `SCSYMBOL_LIMIT` characters
+    /// `a-zA-Z0-9_`
+    pub fn new_symbol<T: AsRef<[u8]>>(symbol: T) -> Result<ScVal, StellarSdkError> {
+        let symbol = symbol.as_ref();
+        if symbol.len() > SCSYMBOL_LIMIT as usize ||
+            !symbol
+                .iter()
+                .all(|character| character.is_ascii_alphanumeric() || *character == b'_')
+        {
+            return Err(StellarSdkError::InvalidScSymbol)
+        }
+        Ok(ScVal::ScvSymbol(ScSymbol::new(symbol.to_vec())?))
+    }
+}
+
+impl<T: AsRef<[u8]>> IntoScVal for AsScSymbol<T> {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        ScVal::new_symbol(self.0)
+    }
+}
+
+impl TryFromScVal for AsScSymbol<Vec<u8>> {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        match value {
+            ScVal::ScvSymbol(symbol) => Ok(AsScSymbol(symbol.into_vec())),
+            value => Err(unexpected_type(ScValType::ScvSymbol, &value)),
+        }
+    }
+}
+
+impl<T: AsRef<[u8]>> IntoScVal for AsScBytes<T> {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        Ok(ScVal::ScvBytes(LimitedVarOpaque::new(self.0.as_ref().to_vec())?))
+    }
+}
+
+impl TryFromScVal for AsScBytes<Vec<u8>> {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        match value {
+            ScVal::ScvBytes(bytes) => Ok(AsScBytes(bytes.into_vec())),
+            value => Err(unexpected_type(ScValType::ScvBytes, &value)),
+        }
+    }
+}
+
+impl<const N: usize> IntoScVal for [u8; N] {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        AsScBytes(self).into_sc_val()
+    }
+}
+
+impl<const N: usize> TryFromScVal for [u8; N] {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        AsScBytes::<Vec<u8>>::try_from_sc_val(value)?
+            .0
+            .try_into()
+            .map_err(|_| StellarSdkError::InvalidScVal)
+    }
+}
+
+impl<T: IntoScVal> IntoScVal for Option<T> {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        match self {
+            Some(value) => value.into_sc_val(),
+            None => Ok(ScVal::ScvVoid),
+        }
+    }
+}
+
+impl<T: TryFromScVal> TryFromScVal for Option<T> {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        match value {
+            ScVal::ScvVoid => Ok(None),
+            value => T::try_from_sc_val(value).map(Some),
+        }
+    }
+}
+
+fn into_sc_vec(values: Vec<ScVal>) -> Result<ScVal, StellarSdkError> {
+    Ok(ScVal::ScvVec(Some(LimitedVarArray::new(values)?)))
+}
+
+fn try_from_sc_vec(value: ScVal) -> Result<Vec<ScVal>, StellarSdkError> {
+    match value {
+        ScVal::ScvVec(Some(values)) => Ok(values.into_vec()),
+        value => Err(unexpected_type(ScValType::ScvVec, &value)),
+    }
+}
+
+impl<T: IntoScVal> IntoScVal for Vec<T> {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        into_sc_vec(self.into_iter().map(IntoScVal::into_sc_val).collect::<Result<_, _>>()?)
+    }
+}
+
+impl<T: TryFromScVal> TryFromScVal for Vec<T> {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        try_from_sc_vec(value)?.into_iter().map(T::try_from_sc_val).collect()
+    }
+}
+
+impl<K: IntoScVal, V: IntoScVal> IntoScVal for BTreeMap<K, V> {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        let entries = self
+            .into_iter()
+            .map(|(key, val)| Ok(ScMapEntry { key: key.into_sc_val()?, val: val.into_sc_val()? }))
+            .collect::<Result<_, StellarSdkError>>()?;
+        ScVal::new_map(entries)
+    }
+}
+
+impl<K: TryFromScVal + Ord, V: TryFromScVal> TryFromScVal for BTreeMap<K, V> {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        match value {
+            ScVal::ScvMap(Some(entries)) => entries
+                .into_vec()
+                .into_iter()
+                .map(|entry| Ok((K::try_from_sc_val(entry.key)?, V::try_from_sc_val(entry.val)?)))
+                .collect(),
+            value => Err(unexpected_type(ScValType::ScvMap, &value)),
+        }
+    }
+}
+
+macro_rules! sc_val_tuple {
+    ($length:literal, $($name:ident),+) => {
+        impl<$($name: IntoScVal),+> IntoScVal for ($($name,)+) {
+            #[allow(non_snake_case)]
+            fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+                let ($($name,)+) = self;
+                into_sc_vec(sp_std::vec![$($name.into_sc_val()?),+])
+            }
+        }
+
+        impl<$($name: TryFromScVal),+> TryFromScVal for ($($name,)+) {
+            fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+                let values = try_from_sc_vec(value)?;
+                if values.len() != $length {
+                    return Err(StellarSdkError::InvalidScVal)
+                }
+                let mut values = values.into_iter();
+                Ok(($($name::try_from_sc_val(values.next().unwrap())?,)+))
+            }
+        }
+    };
+}
+
+sc_val_tuple!(1, A);
+sc_val_tuple!(2, A, B);
+sc_val_tuple!(3, A, B, C);
+sc_val_tuple!(4, A, B, C, D);
+sc_val_tuple!(5, A, B, C, D, E);
+sc_val_tuple!(6, A, B, C, D, E, F);
+
+impl IntoScVal for AccountId {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        Ok(ScVal::ScvAddress(ScAddress::ScAddressTypeAccount(self)))
+    }
+}
+
+impl TryFromScVal for AccountId {
+    fn try_from_sc_val(value: ScVal) -> Result<Self, StellarSdkError> {
+        match ScAddress::try_from_sc_val(value)? {
+            ScAddress::ScAddressTypeAccount(account_id) => Ok(account_id),
+            _ => Err(StellarSdkError::InvalidScVal),
+        }
+    }
+}
+
+impl IntoScVal for MuxedAccount {
+    fn into_sc_val(self) -> Result<ScVal, StellarSdkError> {
+        Ok(ScVal::ScvAddress(ScAddress::from_muxed_account(self)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sp_std::collections::btree_map::BTreeMap;
+
+    use super::{AsScBytes, AsScSymbol, IntoScVal, TryFromScVal};
+    use crate::{
+        lib::String,
+        types::{Int256Parts, ScAddress, ScMapEntry, ScVal, ScValType, UInt256Parts},
+        AccountId, MuxedAccount, PublicKey, StellarSdkError, XdrCodec,
+    };
+
+    const ACCOUNT_ID: &str = "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ";
+
+    fn round_trip<T: IntoScVal + TryFromScVal + Clone + PartialEq + core::fmt::Debug>(value: T) -> ScVal {
+        let sc_val = value.clone().into_sc_val().unwrap();
+        let decoded = ScVal::from_xdr(sc_val.to_xdr()).unwrap();
+        assert_eq!(T::try_from_sc_val(decoded).unwrap(), value);
+        sc_val
+    }
+
+    #[test]
+    fn primitives() {
+        round_trip(true);
+        round_trip(7u32);
+        round_trip(-7i32);
+        round_trip(u64::MAX);
+        round_trip(i64::MIN);
+        round_trip(());
+        round_trip(Some(3u32));
+        round_trip(Option::<u32>::None);
+        round_trip(String::from("Hello World!"));
+        round_trip(AsScSymbol(b"transfer".to_vec()));
+        round_trip(AsScBytes(vec![1, 2, 3]));
+        round_trip([7u8; 32]);
+        round_trip((1u32, String::from("a"), (true,)));
+
+        assert_eq!(
+            round_trip(u128::MAX - 1),
+            ScVal::ScvU128(crate::types::UInt128Parts { hi: u64::MAX, lo: u64::MAX - 1 })
+        );
+        assert_eq!(round_trip(-2i128), ScVal::ScvI128(crate::types::Int128Parts { hi: -1, lo: u64::MAX - 1 }));
+        assert_eq!(round_trip(i128::MIN), ScVal::ScvI128(crate::types::Int128Parts { hi: i64::MIN, lo: 0 }));
+
+        let mut bytes = [0xffu8; 32];
+        bytes[31] = 0xfe;
+        assert_eq!(
+            Int256Parts::from_be_bytes(bytes),
+            Int256Parts { hi_hi: -1, hi_lo: u64::MAX, lo_hi: u64::MAX, lo_lo: u64::MAX - 1 }
+        );
+        assert_eq!(Int256Parts::from_be_bytes(bytes).to_be_bytes(), bytes);
+        round_trip(UInt256Parts::from_be_bytes(bytes));
+
+        assert_eq!(
+            u32::try_from_sc_val(ScVal::ScvU64(1)),
+            Err(StellarSdkError::UnexpectedScValType { expected: ScValType::ScvU32, found: ScValType::ScvU64 })
+        );
+        assert_eq!(<(u32, u32)>::try_from_sc_val((1u32,).into_sc_val().unwrap()), Err(StellarSdkError::InvalidScVal));
+        assert_eq!(<[u8; 4]>::try_from_sc_val([1u8; 3].into_sc_val().unwrap()), Err(StellarSdkError::InvalidScVal));
+    }
+
+    #[test]
+    fn symbols() {
+        assert!(AsScSymbol("valid_Symbol_1").into_sc_val().is_ok());
+        assert_eq!(AsScSymbol("in valid").into_sc_val(), Err(StellarSdkError::InvalidScSymbol));
+        assert_eq!(AsScSymbol("a".repeat(33)).into_sc_val(), Err(StellarSdkError::InvalidScSymbol));
+    }
+
+    #[test]
+    fn maps_are_sorted() {
+        let mut map = BTreeMap::new();
+        map.insert(-1i64, String::from("minus one"));
+        map.insert(i64::MIN, String::from("min"));
+        map.insert(2, String::from("two"));
+        round_trip(map);
+
+        let entry = |key: ScVal| ScMapEntry { key, val: ScVal::ScvVoid };
+        let map = ScVal::new_map(vec![
+            entry(ScVal::ScvU32(10)),
+            entry(AsScBytes(vec![1, 2]).into_sc_val().unwrap()),
+            entry(AsScBytes(vec![3]).into_sc_val().unwrap()),
+            entry(ScVal::ScvBool(true)),
+            entry(ScVal::ScvU32(9)),
+        ])
+        .unwrap();
+        let keys: Vec<ScVal> = match map {
+            ScVal::ScvMap(Some(entries)) => entries.into_vec().into_iter().map(|entry| entry.key).collect(),
+            _ => panic!("Not a map"),
+        };
+        assert_eq!(
+            keys,
+            vec![
+                ScVal::ScvBool(true),
+                ScVal::ScvU32(9),
+                ScVal::ScvU32(10),
+                AsScBytes(vec![1, 2]).into_sc_val().unwrap(),
+                AsScBytes(vec![3]).into_sc_val().unwrap(),
+            ]
+        );
+
+        assert_eq!(
+            ScVal::new_map(vec![entry(ScVal::ScvU32(1)), entry(ScVal::ScvU32(1))]),
+            Err(StellarSdkError::InvalidScVal)
+        );
+    }
+
+    #[test]
+    fn addresses() {
+        let account_id = PublicKey::from_encoding(ACCOUNT_ID).unwrap();
+        round_trip(account_id.clone());
+        assert_eq!(
+            MuxedAccount::from_account_id(ACCOUNT_ID).unwrap().into_sc_val().unwrap(),
+            ScVal::ScvAddress(ScAddress::ScAddressTypeAccount(account_id.clone()))
+        );
+        assert!(matches!(
+            MuxedAccount::from_muxed_account_id(ACCOUNT_ID, 3)
+                .unwrap()
+                .into_sc_val()
+                .unwrap(),
+            ScVal::ScvAddress(ScAddress::ScAddressTypeMuxedAccount(_))
+        ));
+        let contract = ScAddress::from_contract_id([1; 32]);
+        round_trip(contract.clone());
+        assert_eq!(AccountId::try_from_sc_val(contract.into_sc_val().unwrap()), Err(StellarSdkError::InvalidScVal));
+    }
+}
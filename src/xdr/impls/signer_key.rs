@@ -1,4 +1,28 @@
-use crate::{IntoHash, IntoPublicKey, SignerKey, StellarSdkError};
+use core::convert::{AsRef, TryInto};
+use sp_std::vec::Vec;
+
+use crate::{
+    network::Network,
+    types::{DecoratedSignature, SignerKeyEd25519SignedPayload},
+    utils::{
+        key_encoding::{
+            decode_stellar_key, decode_variable_length_stellar_key, encode_stellar_key,
+            encode_variable_length_stellar_key, ED25519_PUBLIC_KEY_VERSION_BYTE, PRE_AUTH_TX_VERSION_BYTE,
+            SHA256_HASH_VERSION_BYTE, SIGNED_PAYLOAD_VERSION_BYTE,
+        },
+        sha256::{sha256, BinarySha256Hash},
+    },
+    xdr::compound_types::LimitedVarOpaque,
+    IntoHash, IntoPublicKey, PublicKey, SignerKey, StellarSdkError, Transaction,
+};
+
+const MAX_SIGNED_PAYLOAD_LENGTH: usize = 64;
+
+impl From<PublicKey> for SignerKey {
+    fn from(public_key: PublicKey) -> Self {
+        Self::SignerKeyTypeEd25519(public_key.into_binary())
+    }
+}
 
 impl SignerKey {
     pub fn from_ed25519_public_key<T: IntoPublicKey>(public_key: T) -> Result<Self, StellarSdkError> {
@@ -13,4 +37,155 @@ impl SignerKey {
     pub fn from_hash_x<T: IntoHash>(hash: T) -> Result<Self, StellarSdkError> {
         Ok(Self::SignerKeyTypeHashX(hash.into_hash()?))
     }
+
+    /// Create the pre-authorized transaction signer for `transaction` on the `network`
+    pub fn from_transaction(transaction: &Transaction, network: &Network) -> Self {
+        Self::SignerKeyTypePreAuthTx(transaction.get_hash(network))
+    }
+
+    /// Create the hash-x signer whose hash is the sha256 hash of `preimage`
+    pub fn from_hash_x_preimage<T: AsRef<[u8]>>(preimage: T) -> Self {
+        Self::SignerKeyTypeHashX(sha256(preimage))
+    }
+
+    /// Generate a random 32 byte preimage and the corresponding hash-x signer
+    ///
+    /// This uses the randomness of the offchain worker and can only be called in an offchain
+    /// context. The preimage needs to be kept secret until the hash-x signer should sign.
+    #[cfg(feature = "offchain")]
+    pub fn generate_hash_x() -> ([u8; 32], Self) {
+        let preimage = sp_io::offchain::random_seed();
+        (preimage, Self::from_hash_x_preimage(preimage))
+    }
+
+    /// Decode a signer key from its strkey encoding
+    ///
+    /// The type of the signer key is determined by the first character: `G` (ed25519 public
+    /// key), `T` (pre-authorized transaction), `X` (sha256 hash) or `P` (signed payload).
+    pub fn from_encoding<T: AsRef<[u8]>>(encoded_key: T) -> Result<Self, StellarSdkError> {
+        let encoded_key = encoded_key.as_ref();
+        match encoded_key.first() {
+            Some(b'G') =>
+                Ok(Self::SignerKeyTypeEd25519(decode_stellar_key(encoded_key, ED25519_PUBLIC_KEY_VERSION_BYTE)?)),
+            Some(b'T') => Ok(Self::SignerKeyTypePreAuthTx(decode_stellar_key(encoded_key, PRE_AUTH_TX_VERSION_BYTE)?)),
+            Some(b'X') => Ok(Self::SignerKeyTypeHashX(decode_stellar_key(encoded_key, SHA256_HASH_VERSION_BYTE)?)),
+            Some(b'P') => {
+                let raw_bytes = decode_variable_length_stellar_key(
+                    encoded_key,
+                    SIGNED_PAYLOAD_VERSION_BYTE,
+                    32 + 4 + 4..=32 + 4 + MAX_SIGNED_PAYLOAD_LENGTH,
+                )?;
+
+                let payload_length = u32::from_be_bytes(raw_bytes[32..36].try_into().unwrap()) as usize;
+                let padded_length = (payload_length + 3) & !3;
+                if payload_length == 0 ||
+                    raw_bytes.len() != 36 + padded_length ||
+                    raw_bytes[36 + payload_length..].iter().any(|byte| *byte != 0)
+                {
+                    return Err(StellarSdkError::InvalidStellarKeyEncodingLength)
+                }
+
+                Ok(Self::SignerKeyTypeEd25519SignedPayload(SignerKeyEd25519SignedPayload {
+                    ed25519: raw_bytes[..32].try_into().unwrap(),
+                    payload: LimitedVarOpaque::new(raw_bytes[36..36 + payload_length].to_vec())?,
+                }))
+            },
+            found_version => Err(StellarSdkError::InvalidStellarKeyEncodingVersion {
+                expected_version: 'G',
+                found_version: found_version.copied().unwrap_or(b' ') as char,
+            }),
+        }
+    }
+
+    /// Return the strkey encoding of the signer key as an ASCII string (given as `Vec<u8>`)
+    pub fn to_encoding(&self) -> Vec<u8> {
+        match self {
+            Self::SignerKeyTypeEd25519(key) => encode_stellar_key(key, ED25519_PUBLIC_KEY_VERSION_BYTE),
+            Self::SignerKeyTypePreAuthTx(hash) => encode_stellar_key(hash, PRE_AUTH_TX_VERSION_BYTE),
+            Self::SignerKeyTypeHashX(hash) => encode_stellar_key(hash, SHA256_HASH_VERSION_BYTE),
+            Self::SignerKeyTypeEd25519SignedPayload(signed_payload) => {
+                let payload = signed_payload.payload.get_vec();
+                let mut raw_bytes = Vec::with_capacity(36 + payload.len() + 3);
+                raw_bytes.extend_from_slice(&signed_payload.ed25519);
+                raw_bytes.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+                raw_bytes.extend_from_slice(payload);
+                raw_bytes.resize((raw_bytes.len() + 3) & !3, 0);
+                encode_variable_length_stellar_key(&raw_bytes, SIGNED_PAYLOAD_VERSION_BYTE)
+            },
+        }
+    }
+
+    /// Check whether `signature` is a valid signature of this signer for the transaction with
+    /// the hash `transaction_hash`
+    ///
+    /// The hint of the signature needs to match. Hash-x signatures are the preimage of the hash
+    /// and signed payload signatures are ed25519 signatures of the payload. Pre-authorized
+    /// transactions are never signed by a `DecoratedSignature`.
+    pub fn is_signed_by(&self, transaction_hash: &BinarySha256Hash, signature: &DecoratedSignature) -> bool {
+        if signature.hint != self.get_signature_hint() {
+            return false
+        }
+
+        let signature = signature.signature.get_vec();
+        match self {
+            Self::SignerKeyTypeEd25519(key) => match signature[..].try_into() {
+                Ok(signature) => PublicKey::from_binary(*key).verify_signature(transaction_hash, &signature),
+                Err(_) => false,
+            },
+            Self::SignerKeyTypeHashX(hash) => sha256(signature) == *hash,
+            Self::SignerKeyTypeEd25519SignedPayload(signed_payload) => match signature[..].try_into() {
+                Ok(signature) => PublicKey::from_binary(signed_payload.ed25519)
+                    .verify_signature(signed_payload.payload.get_vec(), &signature),
+                Err(_) => false,
+            },
+            Self::SignerKeyTypePreAuthTx(_) => false,
+        }
+    }
+
+    /// Return the hint of a `DecoratedSignature` created for this signer key
+    ///
+    /// This is the last 4 bytes of the key or hash. For signed payloads it is the last 4 bytes
+    /// of the ed25519 key XOR the last 4 bytes of the payload (padded with zeros if the payload
+    /// is shorter).
+    pub fn get_signature_hint(&self) -> [u8; 4] {
+        match self {
+            Self::SignerKeyTypeEd25519(key) | Self::SignerKeyTypePreAuthTx(key) | Self::SignerKeyTypeHashX(key) =>
+                key[28..].try_into().unwrap(),
+            Self::SignerKeyTypeEd25519SignedPayload(signed_payload) => {
+                let payload = signed_payload.payload.get_vec();
+                let mut payload_hint = [0u8; 4];
+                match payload.len() {
+                    length if length >= 4 => payload_hint.copy_from_slice(&payload[length - 4..]),
+                    length => payload_hint[..length].copy_from_slice(payload),
+                }
+
+                let mut hint: [u8; 4] = signed_payload.ed25519[28..].try_into().unwrap();
+                hint.iter_mut()
+                    .zip(payload_hint.iter())
+                    .for_each(|(hint, payload)| *hint ^= payload);
+                hint
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::SignerKey;
+
+    #[test]
+    fn signer_key_encoding() {
+        for encoded_key in [
+            "GA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJVSGZ",
+            "TBU2RRGLXH3E5CQHTD3ODLDF2BWDCYUSSBLLZ5GNW7JXHDIYKXZWHXL7",
+            "XBU2RRGLXH3E5CQHTD3ODLDF2BWDCYUSSBLLZ5GNW7JXHDIYKXZWGTOG",
+            "PA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAQACAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUPB6IBZGM",
+            "PA7QYNF7SOWQ3GLR2BGMZEHXAVIRZA4KVWLTJJFC7MGXUA74P7UJUAAAAAOQCAQDAQCQMBYIBEFAWDANBYHRAEISCMKBKFQXDAMRUGY4DUAAAAFGBU",
+        ] {
+            let signer_key = SignerKey::from_encoding(encoded_key).unwrap();
+            assert_eq!(signer_key.to_encoding(), encoded_key.as_bytes());
+        }
+
+        assert!(SignerKey::from_encoding("SBU2RRGLXH3E5CQHTD3ODLDF2BWDCYUSSBLLZ5GNW7JXHDIYKXZWHOKR").is_err());
+    }
 }
@@ -0,0 +1,280 @@
+//! Authorization entries of Soroban contract invocations
+//!
+//! An address authorizes a tree of contract invocations by signing the hash of a
+//! `HashIdPreimage::EnvelopeTypeSorobanAuthorization`. The signature of an account is a vector of
+//! maps `{ public_key: Bytes, signature: Bytes }`, sorted by the public keys, so that accounts
+//! with multiple signers can be authorized.
+
+use core::convert::{AsRef, TryInto};
+use sp_std::{vec, vec::Vec};
+
+use crate::{
+    compound_types::{LimitedString, LimitedVarArray},
+    network::Network,
+    types::{
+        EnvelopeType, InvokeContractArgs, ScMapEntry, ScVal, SorobanAddressCredentials, SorobanAuthorizationEntry,
+        SorobanAuthorizedFunction, SorobanAuthorizedInvocation, SorobanCredentials,
+    },
+    utils::sha256::{sha256, BinarySha256Hash},
+    xdr::streams::WriteStream,
+    AsScBytes, AsScSymbol, IntoScAddress, IntoScVal, IntoSecretKey, PublicKey, StellarSdkError, XdrCodec,
+};
+
+const PUBLIC_KEY_FIELD: &str = "public_key";
+const SIGNATURE_FIELD: &str = "signature";
+
+fn signature_payload(
+    credentials: &SorobanAddressCredentials,
+    root_invocation: &SorobanAuthorizedInvocation,
+    network: &Network,
+) -> BinarySha256Hash {
+    let mut write_stream = WriteStream::new();
+    EnvelopeType::EnvelopeTypeSorobanAuthorization.to_xdr_buffered(&mut write_stream);
+    network.get_id().to_xdr_buffered(&mut write_stream);
+    credentials.nonce.to_xdr_buffered(&mut write_stream);
+    credentials.signature_expiration_ledger.to_xdr_buffered(&mut write_stream);
+    root_invocation.to_xdr_buffered(&mut write_stream);
+    sha256(write_stream.get_result())
+}
+
+fn account_signatures(credentials: &SorobanAddressCredentials) -> Result<Vec<(PublicKey, [u8; 64])>, StellarSdkError> {
+    let signatures = match &credentials.signature {
+        ScVal::ScvVoid => return Ok(Vec::new()),
+        ScVal::ScvVec(Some(signatures)) => signatures.get_vec(),
+        _ => return Err(StellarSdkError::InvalidSorobanAuthorizationSignature),
+    };
+
+    signatures
+        .iter()
+        .map(|signature| {
+            let entries = match signature {
+                ScVal::ScvMap(Some(entries)) if entries.len() == 2 => entries.get_vec(),
+                _ => return Err(StellarSdkError::InvalidSorobanAuthorizationSignature),
+            };
+            let get_field = |index: usize, name: &str| match (&entries[index].key, &entries[index].val) {
+                (ScVal::ScvSymbol(key), ScVal::ScvBytes(value)) if key.get_vec() == name.as_bytes() =>
+                    Ok(value.get_vec()),
+                _ => Err(StellarSdkError::InvalidSorobanAuthorizationSignature),
+            };
+
+            let public_key = get_field(0, PUBLIC_KEY_FIELD)?[..]
+                .try_into()
+                .map_err(|_| StellarSdkError::InvalidSorobanAuthorizationSignature)?;
+            let signature = get_field(1, SIGNATURE_FIELD)?[..]
+                .try_into()
+                .map_err(|_| StellarSdkError::InvalidSorobanAuthorizationSignature)?;
+            Ok((PublicKey::from_binary(public_key), signature))
+        })
+        .collect()
+}
+
+impl SorobanAuthorizedInvocation {
+    pub fn new(
+        function: SorobanAuthorizedFunction,
+        sub_invocations: Vec<SorobanAuthorizedInvocation>,
+    ) -> Result<Self, StellarSdkError> {
+        Ok(SorobanAuthorizedInvocation { function, sub_invocations: LimitedVarArray::new(sub_invocations)? })
+    }
+
+    /// Authorize the call of the function `function_name` of the contract `contract_address`
+    /// with `args` and the invocations `sub_invocations` that this call makes
+    pub fn new_contract_fn<T: IntoScAddress, S: AsRef<[u8]>>(
+        contract_address: T,
+        function_name: S,
+        args: Vec<ScVal>,
+        sub_invocations: Vec<SorobanAuthorizedInvocation>,
+    ) -> Result<Self, StellarSdkError> {
+        Self::new(
+            SorobanAuthorizedFunction::SorobanAuthorizedFunctionTypeContractFn(InvokeContractArgs {
+                contract_address: contract_address.into_sc_address()?,
+                function_name: LimitedString::new(function_name.as_ref().to_vec())?,
+                args: LimitedVarArray::new(args)?,
+            }),
+            sub_invocations,
+        )
+    }
+}
+
+impl SorobanAuthorizationEntry {
+    /// Create an entry that is authorized by the source account of the transaction or operation
+    ///
+    /// Such an entry does not need a signature.
+    pub fn new_source_account_credentials(root_invocation: SorobanAuthorizedInvocation) -> Self {
+        SorobanAuthorizationEntry { credentials: SorobanCredentials::SorobanCredentialsSourceAccount, root_invocation }
+    }
+
+    /// Create an unsigned entry that is authorized by `address`
+    ///
+    /// The `nonce` must not have been used by `address` before and the signature expires after
+    /// the ledger `signature_expiration_ledger`.
+    pub fn new_address_credentials<T: IntoScAddress>(
+        address: T,
+        nonce: i64,
+        signature_expiration_ledger: u32,
+        root_invocation: SorobanAuthorizedInvocation,
+    ) -> Result<Self, StellarSdkError> {
+        Ok(SorobanAuthorizationEntry {
+            credentials: SorobanCredentials::SorobanCredentialsAddress(SorobanAddressCredentials {
+                address: address.into_sc_address()?,
+                nonce,
+                signature_expiration_ledger,
+                signature: ScVal::ScvVoid,
+            }),
+            root_invocation,
+        })
+    }
+
+    fn get_address_credentials(&self) -> Result<&SorobanAddressCredentials, StellarSdkError> {
+        match &self.credentials {
+            SorobanCredentials::SorobanCredentialsAddress(credentials) => Ok(credentials),
+            SorobanCredentials::SorobanCredentialsSourceAccount => Err(StellarSdkError::NoAddressCredentials),
+        }
+    }
+
+    /// Return the hash that the signers of the address sign for the `network`
+    ///
+    /// This is the hash of the XDR encoding of the
+    /// `HashIdPreimage::EnvelopeTypeSorobanAuthorization` of this entry.
+    pub fn get_signature_payload(&self, network: &Network) -> Result<BinarySha256Hash, StellarSdkError> {
+        Ok(signature_payload(self.get_address_credentials()?, &self.root_invocation, network))
+    }
+
+    /// Return the public keys and signatures of the account signature of this entry
+    ///
+    /// Unsigned entries have no signatures. Returns an error if the signature is not an account
+    /// signature.
+    pub fn get_account_signatures(&self) -> Result<Vec<(PublicKey, [u8; 64])>, StellarSdkError> {
+        account_signatures(self.get_address_credentials()?)
+    }
+
+    /// Sign the entry with `secret_key` for the `network`
+    ///
+    /// The signature is added to the signatures that the entry already has, so that entries of
+    /// accounts with several signers can be signed one after another. Signatures are kept
+    /// sorted by public key as required by the Soroban host; a signature of the same key is
+    /// replaced.
+    pub fn sign<T: IntoSecretKey>(&mut self, secret_key: T, network: &Network) -> Result<(), StellarSdkError> {
+        let credentials = match &mut self.credentials {
+            SorobanCredentials::SorobanCredentialsAddress(credentials) => credentials,
+            SorobanCredentials::SorobanCredentialsSourceAccount => return Err(StellarSdkError::NoAddressCredentials),
+        };
+        let secret_key = secret_key.into_secret_key()?;
+        let payload = signature_payload(credentials, &self.root_invocation, network);
+
+        let mut signatures = account_signatures(credentials)?;
+        signatures.retain(|(public_key, _)| public_key != secret_key.get_public());
+        signatures.push((secret_key.get_public().clone(), secret_key.create_signature(payload)));
+        signatures
+            .sort_by(|(public_key_a, _), (public_key_b, _)| public_key_a.as_binary().cmp(public_key_b.as_binary()));
+
+        let signatures = signatures
+            .into_iter()
+            .map(|(public_key, signature)| {
+                Ok(ScVal::ScvMap(Some(LimitedVarArray::new(vec![
+                    ScMapEntry {
+                        key: AsScSymbol(PUBLIC_KEY_FIELD).into_sc_val()?,
+                        val: AsScBytes(public_key.into_binary()).into_sc_val()?,
+                    },
+                    ScMapEntry {
+                        key: AsScSymbol(SIGNATURE_FIELD).into_sc_val()?,
+                        val: AsScBytes(signature).into_sc_val()?,
+                    },
+                ])?)))
+            })
+            .collect::<Result<Vec<_>, StellarSdkError>>()?;
+
+        credentials.signature = ScVal::ScvVec(Some(LimitedVarArray::new(signatures)?));
+        Ok(())
+    }
+
+    /// Verify the account signature of this entry for the `network` and return its signers
+    ///
+    /// Returns an error if the entry is not signed, a signature is invalid or the signatures are
+    /// not sorted by public key. Whether the signers have enough weight for the address and
+    /// whether the signature expiration ledger has passed is not checked.
+    pub fn verify_signatures(&self, network: &Network) -> Result<Vec<PublicKey>, StellarSdkError> {
+        let payload = self.get_signature_payload(network)?;
+        let signatures = self.get_account_signatures()?;
+
+        let is_sorted = signatures.windows(2).all(|pair| pair[0].0.as_binary() < pair[1].0.as_binary());
+        if signatures.is_empty() ||
+            !is_sorted ||
+            !signatures
+                .iter()
+                .all(|(public_key, signature)| public_key.verify_signature(payload, signature))
+        {
+            return Err(StellarSdkError::InvalidSorobanAuthorizationSignature)
+        }
+
+        Ok(signatures.into_iter().map(|(public_key, _)| public_key).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        network::TEST_NETWORK,
+        types::{ScVal, SorobanAuthorizationEntry, SorobanAuthorizedInvocation},
+        IntoSecretKey, StellarSdkError, XdrCodec,
+    };
+
+    const CONTRACT: &str = "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE";
+    const SECRET_KEY_1: &str = "SCVKZEONBSU3XD6OTHXGAP6BTEWHOU4RPZQZJJ5AVAGPXUZ5A4D7MU6S";
+    const SECRET_KEY_2: &str = "SDOKV37I4TI655LMEMDQFOWESJ3LK6DDFKIVTYKN4YYTSAYFIBPP7MYI";
+
+    fn entry() -> SorobanAuthorizationEntry {
+        let secret_key = SECRET_KEY_1.into_secret_key().unwrap();
+        let invocation =
+            SorobanAuthorizedInvocation::new_contract_fn(CONTRACT, "transfer", vec![ScVal::ScvU32(1)], vec![]).unwrap();
+        SorobanAuthorizationEntry::new_address_credentials(secret_key.get_public().clone(), 42, 1000, invocation)
+            .unwrap()
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let mut entry = entry();
+        assert_eq!(entry.verify_signatures(&TEST_NETWORK), Err(StellarSdkError::InvalidSorobanAuthorizationSignature));
+
+        entry.sign(SECRET_KEY_2, &TEST_NETWORK).unwrap();
+        entry.sign(SECRET_KEY_1, &TEST_NETWORK).unwrap();
+        entry.sign(SECRET_KEY_2, &TEST_NETWORK).unwrap();
+
+        let mut expected_signers = vec![
+            SECRET_KEY_1.into_secret_key().unwrap().get_public().clone(),
+            SECRET_KEY_2.into_secret_key().unwrap().get_public().clone(),
+        ];
+        expected_signers.sort_by(|a, b| a.as_binary().cmp(b.as_binary()));
+        let entry = SorobanAuthorizationEntry::from_xdr(entry.to_xdr()).unwrap();
+        assert_eq!(entry.verify_signatures(&TEST_NETWORK), Ok(expected_signers));
+
+        let mut tampered = entry.clone();
+        tampered.root_invocation.sub_invocations =
+            crate::compound_types::LimitedVarArray::new(vec![entry.root_invocation.clone()]).unwrap();
+        assert_eq!(
+            tampered.verify_signatures(&TEST_NETWORK),
+            Err(StellarSdkError::InvalidSorobanAuthorizationSignature)
+        );
+
+        let mut source_account = SorobanAuthorizationEntry::new_source_account_credentials(entry.root_invocation);
+        assert_eq!(source_account.verify_signatures(&TEST_NETWORK), Err(StellarSdkError::NoAddressCredentials));
+        assert_eq!(source_account.sign(SECRET_KEY_1, &TEST_NETWORK), Err(StellarSdkError::NoAddressCredentials));
+    }
+
+    #[cfg(feature = "all-types")]
+    #[test]
+    fn signature_payload() {
+        use crate::{
+            types::{HashIdPreimage, HashIdPreimageSorobanAuthorization},
+            utils::sha256::sha256,
+        };
+
+        let entry = entry();
+        let preimage = HashIdPreimage::EnvelopeTypeSorobanAuthorization(HashIdPreimageSorobanAuthorization {
+            network_id: *TEST_NETWORK.get_id(),
+            nonce: 42,
+            signature_expiration_ledger: 1000,
+            invocation: entry.root_invocation.clone(),
+        });
+        assert_eq!(entry.get_signature_payload(&TEST_NETWORK).unwrap(), sha256(preimage.to_xdr()));
+    }
+}
@@ -1,12 +1,15 @@
 //! Transaction envelopes and signatures
 
 use crate::{
+    network::Network,
     types::{
         FeeBumpTransactionEnvelope, FeeBumpTransactionExt, FeeBumpTransactionInnerTx, Memo, MuxedAccount, Operation,
-        Preconditions, TimeBounds, Transaction, TransactionEnvelope, TransactionExt, TransactionV0, TransactionV0Ext,
+        Preconditions, SorobanTransactionData, TimeBounds, Transaction, TransactionEnvelope, TransactionExt,
+        TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction, TransactionV0, TransactionV0Ext,
         TransactionV1Envelope,
     },
-    xdr::compound_types::LimitedVarArray,
+    utils::sha256::{sha256, BinarySha256Hash},
+    xdr::{compound_types::LimitedVarArray, xdr_codec::XdrCodec},
     FeeBumpTransaction, IntoAmount, IntoMuxedAccountId, StellarSdkError, BASE_FEE_STROOPS,
 };
 
@@ -37,7 +40,36 @@ impl Transaction {
         self.operations.push(operation)
     }
 
+    /// Attach the footprint and the resources of a Soroban transaction
+    ///
+    /// The fee of the transaction must include the `resource_fee` of `soroban_data`.
+    pub fn set_soroban_data(&mut self, soroban_data: SorobanTransactionData) {
+        self.ext = TransactionExt::V1(soroban_data);
+    }
+
+    /// Return the footprint and the resources of a Soroban transaction
+    pub fn get_soroban_data(&self) -> Option<&SorobanTransactionData> {
+        match &self.ext {
+            TransactionExt::V1(soroban_data) => Some(soroban_data),
+            _ => None,
+        }
+    }
+
+    /// Return the hash of the transaction for the `network`
+    ///
+    /// This is the hash that is signed by the signers of the transaction and the hash of a
+    /// pre-authorized transaction signer (see `SignerKey::from_transaction`).
+    pub fn get_hash(&self, network: &Network) -> BinarySha256Hash {
+        let signature_payload = TransactionSignaturePayload {
+            network_id: *network.get_id(),
+            tagged_transaction: TransactionSignaturePayloadTaggedTransaction::EnvelopeTypeTx(self.clone()),
+        };
+
+        sha256(signature_payload.to_xdr())
+    }
+
     // careful: this operation also multiplies the fees with the number of operations
+    // use `TransactionEnvelope::from` to keep the fee unchanged
     pub fn into_transaction_envelope(mut self) -> TransactionEnvelope {
         self.fee = self.fee.checked_mul(self.operations.len() as u32).unwrap_or(self.fee);
 
@@ -48,6 +80,15 @@ impl Transaction {
     }
 }
 
+impl From<Transaction> for TransactionEnvelope {
+    fn from(transaction: Transaction) -> Self {
+        TransactionEnvelope::EnvelopeTypeTx(TransactionV1Envelope {
+            tx: transaction,
+            signatures: LimitedVarArray::new_empty(),
+        })
+    }
+}
+
 impl From<TransactionV0> for Transaction {
     fn from(transaction: TransactionV0) -> Self {
         let time_bounds = transaction.time_bounds.unwrap_or(TimeBounds::from_time_points((), ()));
@@ -0,0 +1,445 @@
+//! A builder for checked transactions
+
+use core::convert::TryFrom;
+use sp_std::{vec, vec::Vec};
+
+use crate::{
+    types::{LedgerBounds, Preconditions, PreconditionsV2, SignerKey, SorobanTransactionData, TransactionExt},
+    xdr::compound_types::LimitedVarArray,
+    IntoMuxedAccountId, IntoTimePoint, Memo, MuxedAccount, Operation, StellarSdkError, TimeBounds, Transaction,
+    BASE_FEE_STROOPS,
+};
+
+const MAX_EXTRA_SIGNERS: i32 = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fee {
+    PerOperation(u32),
+    Total(u32),
+}
+
+/// A builder for transactions
+///
+/// In contrast to `Transaction::new` the builder checks the transaction when calling `build`:
+/// it requires time bounds (use `set_infinite_timeout` to explicitly build a transaction
+/// without an upper time bound), at least one operation and a fee of at least
+/// `BASE_FEE_STROOPS` per operation. The fee of the resulting transaction is the total fee.
+/// ```
+/// use substrate_stellar_sdk::{
+///     Asset, Operation, SecondEpochTime, TransactionBuilder, TransactionEnvelope,
+/// };
+///
+/// let transaction = TransactionBuilder::new("GDGRDTRINPF66FNC47H22NY6BNWMCD5Q4XZTVA2KG7PFZ64WHRIU62TQ", 1980190376853505)
+///     .unwrap()
+///     .set_base_fee(200)
+///     .set_timeout(SecondEpochTime(1626258131), 300)
+///     .add_operation(
+///         Operation::new_payment("GBNKQVTFRP25TIQRODMU5GJGSXDKHCEUDN7LNMOS5PNM427LMR77NV4M", Asset::native(), "10")
+///             .unwrap(),
+///     )
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(transaction.fee, 200);
+/// let envelope = TransactionEnvelope::from(transaction);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransactionBuilder {
+    source_account: MuxedAccount,
+    sequence_number: i64,
+    fee: Fee,
+    time_bounds: Option<TimeBounds>,
+    ledger_bounds: Option<LedgerBounds>,
+    min_sequence_number: Option<i64>,
+    min_sequence_age: u64,
+    min_sequence_ledger_gap: u32,
+    extra_signers: Vec<SignerKey>,
+    memo: Memo,
+    operations: Vec<Operation>,
+    soroban_data: Option<SorobanTransactionData>,
+}
+
+impl TransactionBuilder {
+    /// Start building a transaction
+    ///
+    /// The `sequence_number` must be 1 + the current sequence number of the source account.
+    pub fn new<T: IntoMuxedAccountId>(source_account: T, sequence_number: i64) -> Result<Self, StellarSdkError> {
+        Ok(Self {
+            source_account: source_account.into_muxed_account_id()?,
+            sequence_number,
+            fee: Fee::PerOperation(BASE_FEE_STROOPS),
+            time_bounds: None,
+            ledger_bounds: None,
+            min_sequence_number: None,
+            min_sequence_age: 0,
+            min_sequence_ledger_gap: 0,
+            extra_signers: vec![],
+            memo: Memo::MemoNone,
+            operations: vec![],
+            soroban_data: None,
+        })
+    }
+
+    /// Set the fee per operation in stroops (default: `BASE_FEE_STROOPS`)
+    pub fn set_base_fee(mut self, fee_per_operation: u32) -> Self {
+        self.fee = Fee::PerOperation(fee_per_operation);
+        self
+    }
+
+    /// Set the total fee of the transaction in stroops
+    pub fn set_total_fee(mut self, total_fee: u32) -> Self {
+        self.fee = Fee::Total(total_fee);
+        self
+    }
+
+    /// Set the time bounds of the transaction
+    pub fn set_time_bounds(mut self, time_bounds: TimeBounds) -> Self {
+        self.time_bounds = Some(time_bounds);
+        self
+    }
+
+    /// Let the transaction expire `timeout_seconds` after `now`
+    ///
+    /// This only changes the upper time bound, a lower time bound set before is kept.
+    pub fn set_timeout<T: IntoTimePoint>(mut self, now: T, timeout_seconds: u64) -> Self {
+        let min_time = self.time_bounds.as_ref().map(|time_bounds| time_bounds.min_time).unwrap_or(0);
+        let max_time = now.into_time_point().saturating_add(timeout_seconds);
+        self.time_bounds = Some(TimeBounds { min_time, max_time });
+        self
+    }
+
+    /// Build a transaction that does not expire
+    pub fn set_infinite_timeout(mut self) -> Self {
+        let min_time = self.time_bounds.as_ref().map(|time_bounds| time_bounds.min_time).unwrap_or(0);
+        self.time_bounds = Some(TimeBounds { min_time, max_time: 0 });
+        self
+    }
+
+    /// Only allow the transaction in ledgers `min_ledger` to `max_ledger` (exclusive)
+    ///
+    /// A `max_ledger` of 0 means that there is no upper bound.
+    pub fn set_ledger_bounds(mut self, min_ledger: u32, max_ledger: u32) -> Self {
+        self.ledger_bounds = Some(LedgerBounds { min_ledger, max_ledger });
+        self
+    }
+
+    /// Only allow the transaction if the sequence number of the source account is at least
+    /// `min_sequence_number`
+    pub fn set_min_sequence_number(mut self, min_sequence_number: i64) -> Self {
+        self.min_sequence_number = Some(min_sequence_number);
+        self
+    }
+
+    /// Only allow the transaction if the sequence number of the source account is at least
+    /// `min_sequence_age` seconds old
+    pub fn set_min_sequence_age(mut self, min_sequence_age: u64) -> Self {
+        self.min_sequence_age = min_sequence_age;
+        self
+    }
+
+    /// Only allow the transaction if the sequence number of the source account has been
+    /// changed at least `min_sequence_ledger_gap` ledgers ago
+    pub fn set_min_sequence_ledger_gap(mut self, min_sequence_ledger_gap: u32) -> Self {
+        self.min_sequence_ledger_gap = min_sequence_ledger_gap;
+        self
+    }
+
+    /// Require an additional signature of `signer_key` (at most 2 distinct extra signers are
+    /// allowed)
+    pub fn add_extra_signer(mut self, signer_key: SignerKey) -> Self {
+        self.extra_signers.push(signer_key);
+        self
+    }
+
+    pub fn set_memo(mut self, memo: Memo) -> Self {
+        self.memo = memo;
+        self
+    }
+
+    pub fn add_operation(mut self, operation: Operation) -> Self {
+        self.operations.push(operation);
+        self
+    }
+
+    /// Attach the footprint and the resources of a Soroban transaction
+    ///
+    /// The `resource_fee` of `soroban_data` is added to the fee of the transaction.
+    pub fn set_soroban_data(mut self, soroban_data: SorobanTransactionData) -> Self {
+        self.soroban_data = Some(soroban_data);
+        self
+    }
+
+    fn has_v2_preconditions(&self) -> bool {
+        self.ledger_bounds.is_some() ||
+            self.min_sequence_number.is_some() ||
+            self.min_sequence_age != 0 ||
+            self.min_sequence_ledger_gap != 0 ||
+            !self.extra_signers.is_empty()
+    }
+
+    /// Check the parameters and build the transaction
+    ///
+    /// The `fee` of the returned transaction is the total fee. Use `TransactionEnvelope::from`
+    /// to wrap it into an envelope (`Transaction::into_transaction_envelope` would multiply
+    /// the fee by the number of operations again).
+    pub fn build(self) -> Result<Transaction, StellarSdkError> {
+        let no_of_operations = self.operations.len();
+        if no_of_operations == 0 {
+            return Err(StellarSdkError::NoOperations)
+        }
+
+        let minimum_fee = BASE_FEE_STROOPS
+            .checked_mul(no_of_operations as u32)
+            .ok_or(StellarSdkError::FeeOverflow)?;
+        let fee = match self.fee {
+            Fee::PerOperation(fee_per_operation) => fee_per_operation
+                .checked_mul(no_of_operations as u32)
+                .ok_or(StellarSdkError::FeeOverflow)?,
+            Fee::Total(total_fee) => total_fee,
+        };
+        if fee < minimum_fee {
+            return Err(StellarSdkError::InsufficientFee { found: fee, minimum: minimum_fee })
+        }
+        let fee = match &self.soroban_data {
+            Some(soroban_data) => u32::try_from(soroban_data.resource_fee)
+                .ok()
+                .and_then(|resource_fee| fee.checked_add(resource_fee))
+                .ok_or(StellarSdkError::FeeOverflow)?,
+            None => fee,
+        };
+
+        let time_bounds = self.time_bounds.clone().ok_or(StellarSdkError::MissingTimeBounds)?;
+        if time_bounds.max_time != 0 && time_bounds.min_time > time_bounds.max_time {
+            return Err(StellarSdkError::InvalidTimeBounds)
+        }
+
+        if let Some(ledger_bounds) = &self.ledger_bounds {
+            if ledger_bounds.max_ledger != 0 && ledger_bounds.min_ledger >= ledger_bounds.max_ledger {
+                return Err(StellarSdkError::InvalidLedgerBounds)
+            }
+        }
+
+        if let Some(min_sequence_number) = self.min_sequence_number {
+            if min_sequence_number < 0 || min_sequence_number >= self.sequence_number {
+                return Err(StellarSdkError::InvalidMinSequenceNumber)
+            }
+        }
+
+        let has_v2_preconditions = self.has_v2_preconditions();
+        let extra_signers = LimitedVarArray::<SignerKey, MAX_EXTRA_SIGNERS>::new(self.extra_signers)?;
+        let signers = extra_signers.get_vec();
+        if signers
+            .iter()
+            .enumerate()
+            .any(|(index, signer)| signers[..index].contains(signer))
+        {
+            return Err(StellarSdkError::DuplicateExtraSigners)
+        }
+
+        let cond = match has_v2_preconditions {
+            true => Preconditions::PrecondV2(PreconditionsV2 {
+                time_bounds: Some(time_bounds),
+                ledger_bounds: self.ledger_bounds,
+                min_seq_num: self.min_sequence_number,
+                min_seq_age: self.min_sequence_age,
+                min_seq_ledger_gap: self.min_sequence_ledger_gap,
+                extra_signers,
+            }),
+            false => Preconditions::PrecondTime(time_bounds),
+        };
+
+        Ok(Transaction {
+            source_account: self.source_account,
+            fee,
+            seq_num: self.sequence_number,
+            cond,
+            memo: self.memo,
+            operations: LimitedVarArray::new(self.operations)?,
+            ext: match self.soroban_data {
+                Some(soroban_data) => TransactionExt::V1(soroban_data),
+                None => TransactionExt::V0,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        compound_types::LimitedVarArray,
+        types::{
+            HostFunction, LedgerFootprint, OperationBody, Preconditions, ScVal, SorobanResources,
+            SorobanTransactionData, SorobanTransactionDataExt, TransactionExt,
+        },
+        Asset, Memo, MilliSecondEpochTime, Operation, Price, SecondEpochTime, SignerKey, StellarSdkError, StroopAmount,
+        TimeBounds, TransactionBuilder, TransactionEnvelope, XdrCodec,
+    };
+
+    const ACCOUNT_ID1: &str = "GDGRDTRINPF66FNC47H22NY6BNWMCD5Q4XZTVA2KG7PFZ64WHRIU62TQ";
+    const ACCOUNT_ID2: &str = "GBNKQVTFRP25TIQRODMU5GJGSXDKHCEUDN7LNMOS5PNM427LMR77NV4M";
+    const ACCOUNT_ID3: &str = "GCACWDM2VEYTXGUI3CUYLBJ453IBEPQ3XEJKA772ARAP5XDQ4NMGFZGJ";
+
+    fn payment() -> Operation {
+        Operation::new_payment(ACCOUNT_ID2, Asset::native(), "123.456").unwrap()
+    }
+
+    #[test]
+    fn build_transaction() {
+        let transaction = TransactionBuilder::new(ACCOUNT_ID1, 1980190376853505)
+            .unwrap()
+            .set_base_fee(321)
+            .set_time_bounds(TimeBounds::from_time_points(
+                SecondEpochTime(162620000),
+                MilliSecondEpochTime(1_626_263_454_000),
+            ))
+            .set_memo(Memo::from_text_memo("Hello World!").unwrap())
+            .add_operation(
+                Operation::new_payment(
+                    ACCOUNT_ID2,
+                    Asset::from_asset_code("USD", ACCOUNT_ID3).unwrap(),
+                    StroopAmount(1234560000),
+                )
+                .unwrap()
+                .set_source_account(ACCOUNT_ID3)
+                .unwrap(),
+            )
+            .add_operation(
+                Operation::new_manage_sell_offer(
+                    Asset::from_asset_code("DOMINATION", ACCOUNT_ID2).unwrap(),
+                    Asset::native(),
+                    "152.103",
+                    Price::from_float(4.58).unwrap(),
+                    Some(123456789),
+                )
+                .unwrap(),
+            )
+            .build()
+            .unwrap();
+
+        let expexted_base64 = b"AAAAAgAAAADNEc4oa8vvFaLnz603HgtswQ+w5fM6g0o33lz7ljxRTwAAAoIABwj5AAAAAQAAAAEAAAAACbFiYAAAAABg7s+eAAAAAQAAAAxIZWxsbyBXb3JsZCEAAAACAAAAAQAAAACAKw2aqTE7mojYqYWFPO7QEj4buRKgf/oEQP7ccONYYgAAAAEAAAAAWqhWZYv12aIRcNlOmSaVxqOIlBt+trHS69rOa+tkf/YAAAABVVNEAAAAAACAKw2aqTE7mojYqYWFPO7QEj4buRKgf/oEQP7ccONYYgAAAABJleQAAAAAAAAAAAMAAAACRE9NSU5BVElPTgAAAAAAAFqoVmWL9dmiEXDZTpkmlcajiJQbfrax0uvazmvrZH/2AAAAAAAAAABaqRNwAAAA5QAAADIAAAAAB1vNFQAAAAAAAAAA";
+        assert_eq!(TransactionEnvelope::from(transaction).to_base64_xdr(), expexted_base64);
+    }
+
+    #[test]
+    fn preconditions_v2() {
+        let transaction = TransactionBuilder::new(ACCOUNT_ID1, 100)
+            .unwrap()
+            .set_timeout(SecondEpochTime(1_000), 60)
+            .set_ledger_bounds(10, 20)
+            .set_min_sequence_number(90)
+            .set_min_sequence_ledger_gap(2)
+            .add_extra_signer(SignerKey::from_ed25519_public_key(ACCOUNT_ID2).unwrap())
+            .add_extra_signer(SignerKey::from_hash_x([1; 32]).unwrap())
+            .set_total_fee(1_000)
+            .add_operation(payment())
+            .build()
+            .unwrap();
+
+        assert_eq!(transaction.fee, 1_000);
+        match transaction.cond {
+            Preconditions::PrecondV2(preconditions) => {
+                assert_eq!(preconditions.time_bounds, Some(TimeBounds { min_time: 0, max_time: 1_060 }));
+                assert_eq!(preconditions.min_seq_num, Some(90));
+                assert_eq!(preconditions.min_seq_ledger_gap, 2);
+                assert_eq!(preconditions.extra_signers.len(), 2);
+            },
+            _ => panic!("Expected preconditions V2"),
+        }
+    }
+
+    #[test]
+    fn build_errors() {
+        let builder = TransactionBuilder::new(ACCOUNT_ID1, 100).unwrap().set_infinite_timeout();
+
+        assert_eq!(builder.clone().build(), Err(StellarSdkError::NoOperations));
+        assert_eq!(
+            TransactionBuilder::new(ACCOUNT_ID1, 100)
+                .unwrap()
+                .add_operation(payment())
+                .build(),
+            Err(StellarSdkError::MissingTimeBounds)
+        );
+
+        let builder = builder.add_operation(payment()).add_operation(payment());
+        assert_eq!(
+            builder.clone().set_total_fee(150).build(),
+            Err(StellarSdkError::InsufficientFee { found: 150, minimum: 200 })
+        );
+        assert_eq!(builder.clone().set_base_fee(u32::MAX).build(), Err(StellarSdkError::FeeOverflow));
+        assert_eq!(builder.clone().set_ledger_bounds(20, 10).build(), Err(StellarSdkError::InvalidLedgerBounds));
+        assert_eq!(
+            builder.clone().set_min_sequence_number(100).build(),
+            Err(StellarSdkError::InvalidMinSequenceNumber)
+        );
+        assert_eq!(
+            builder
+                .clone()
+                .set_time_bounds(TimeBounds::from_time_points(SecondEpochTime(20), SecondEpochTime(10)))
+                .build(),
+            Err(StellarSdkError::InvalidTimeBounds)
+        );
+
+        let signer = SignerKey::from_ed25519_public_key(ACCOUNT_ID2).unwrap();
+        assert_eq!(
+            builder
+                .clone()
+                .add_extra_signer(signer.clone())
+                .add_extra_signer(signer.clone())
+                .build(),
+            Err(StellarSdkError::DuplicateExtraSigners)
+        );
+        assert_eq!(
+            builder
+                .add_extra_signer(signer.clone())
+                .add_extra_signer(signer.clone())
+                .add_extra_signer(signer)
+                .build(),
+            Err(StellarSdkError::ExceedsMaximumLength { requested_length: 3, allowed_length: 2 })
+        );
+    }
+
+    #[test]
+    fn soroban_transaction() {
+        let operation = Operation::new_invoke_contract(
+            "CA3D5KRYM6CB7OWQ6TWYRR3Z4T7GNZLKERYNZGGA5SOAOPIFY6YQGAXE",
+            "transfer",
+            vec![ScVal::ScvU32(7)],
+        )
+        .unwrap()
+        .set_authorization(vec![])
+        .unwrap();
+        assert!(matches!(
+            &operation.body,
+            OperationBody::InvokeHostFunction(operation)
+                if matches!(operation.host_function, HostFunction::HostFunctionTypeInvokeContract(_))
+        ));
+        assert_eq!(payment().set_authorization(vec![]), Err(StellarSdkError::NoInvokeHostFunctionOperation));
+
+        let soroban_data = SorobanTransactionData {
+            ext: SorobanTransactionDataExt::V0,
+            resources: SorobanResources {
+                footprint: LedgerFootprint {
+                    read_only: LimitedVarArray::new_empty(),
+                    read_write: LimitedVarArray::new_empty(),
+                },
+                instructions: 1_000_000,
+                disk_read_bytes: 1000,
+                write_bytes: 1000,
+            },
+            resource_fee: 50_000,
+        };
+        let transaction = TransactionBuilder::new(ACCOUNT_ID1, 1980190376853505)
+            .unwrap()
+            .set_infinite_timeout()
+            .add_operation(operation)
+            .set_soroban_data(soroban_data.clone())
+            .build()
+            .unwrap();
+
+        assert_eq!(transaction.fee, 50_100);
+        assert_eq!(transaction.ext, TransactionExt::V1(soroban_data.clone()));
+        assert_eq!(transaction.get_soroban_data(), Some(&soroban_data));
+        let envelope = TransactionEnvelope::from(transaction);
+        assert_eq!(TransactionEnvelope::from_base64_xdr(envelope.to_base64_xdr()).unwrap(), envelope);
+    }
+}
@@ -8,8 +8,8 @@ use crate::{
     network::Network,
     secret_key::SecretKey,
     types::{
-        DecoratedSignature, PublicKey, TransactionEnvelope, TransactionSignaturePayload,
-        TransactionSignaturePayloadTaggedTransaction,
+        DecoratedSignature, FeeBumpTransactionInnerTx, PublicKey, SignerKey, TransactionEnvelope,
+        TransactionSignaturePayload, TransactionSignaturePayloadTaggedTransaction,
     },
     utils::{
         base64,
@@ -22,6 +22,93 @@ use crate::{
     StellarSdkError,
 };
 
+/// The result of the verification of a `DecoratedSignature`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    /// The signature is a valid signature of `signer`
+    Valid { signer: SignerKey },
+
+    /// The hint matches at least one candidate signer but the signature is not valid for any of them
+    Invalid,
+
+    /// No candidate signer has the hint of this signature
+    UnknownHint,
+
+    /// The signature is valid but the same signer already signed with the signature at index `of_index`
+    Duplicate { signer: SignerKey, of_index: usize },
+}
+
+/// The verification results for the signatures of a transaction envelope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureVerificationReport {
+    /// The status of each signature of the envelope, in the same order as the signatures
+    pub signatures: Vec<SignatureStatus>,
+    /// The status of each signature of the inner transaction of a fee bump transaction
+    pub inner_signatures: Option<Vec<SignatureStatus>>,
+}
+
+impl SignatureVerificationReport {
+    /// Return whether all signatures (including the ones of the inner transaction) are valid
+    /// and not duplicated
+    pub fn all_valid(&self) -> bool {
+        let is_valid = |status: &SignatureStatus| matches!(status, SignatureStatus::Valid { .. });
+        self.signatures.iter().all(is_valid) &&
+            self.inner_signatures
+                .as_ref()
+                .map(|signatures| signatures.iter().all(is_valid))
+                .unwrap_or(true)
+    }
+}
+
+/// The result of merging the signatures of several copies of a transaction envelope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergedEnvelope {
+    pub envelope: TransactionEnvelope,
+    /// The indices of the merged envelopes that did not contribute any new valid signature
+    pub redundant_inputs: Vec<usize>,
+}
+
+fn verify_decorated_signatures(
+    transaction_hash: &BinarySha256Hash,
+    signatures: &[DecoratedSignature],
+    candidate_signers: &[SignerKey],
+) -> Vec<SignatureStatus> {
+    let mut result: Vec<SignatureStatus> = Vec::with_capacity(signatures.len());
+
+    for signature in signatures.iter() {
+        let mut hint_matches = false;
+        let mut valid_signer = None;
+        for signer in candidate_signers.iter() {
+            if signer.get_signature_hint() != signature.hint {
+                continue
+            }
+            hint_matches = true;
+            if signer.is_signed_by(transaction_hash, signature) {
+                valid_signer = Some(signer.clone());
+                break
+            }
+        }
+
+        let status = match valid_signer {
+            Some(signer) => {
+                let earlier_index = result.iter().position(|status| match status {
+                    SignatureStatus::Valid { signer: earlier_signer } => *earlier_signer == signer,
+                    _ => false,
+                });
+                match earlier_index {
+                    Some(of_index) => SignatureStatus::Duplicate { signer, of_index },
+                    None => SignatureStatus::Valid { signer },
+                }
+            },
+            None if hint_matches => SignatureStatus::Invalid,
+            None => SignatureStatus::UnknownHint,
+        };
+        result.push(status);
+    }
+
+    result
+}
+
 impl TransactionEnvelope {
     fn get_signatures(&mut self) -> &mut LimitedVarArray<DecoratedSignature, 20> {
         match self {
@@ -32,6 +119,15 @@ impl TransactionEnvelope {
         }
     }
 
+    fn signatures(&self) -> &[DecoratedSignature] {
+        match self {
+            TransactionEnvelope::EnvelopeTypeTxV0(envelope) => envelope.signatures.get_vec(),
+            TransactionEnvelope::EnvelopeTypeTx(envelope) => envelope.signatures.get_vec(),
+            TransactionEnvelope::EnvelopeTypeTxFeeBump(envelope) => envelope.signatures.get_vec(),
+            _ => unreachable!("Invalid transaction envelope type"),
+        }
+    }
+
     /// Generate a base64 encoded signature
     ///
     /// Generate a signature for the `transaction_envelope`. Generate the signature
@@ -107,6 +203,101 @@ impl TransactionEnvelope {
         Ok(())
     }
 
+    /// Add the `preimage` of a hash-x signer as a signature
+    ///
+    /// The hint of the signature is the last 4 bytes of the hash of the preimage. The preimage
+    /// must not be longer than 64 bytes.
+    pub fn add_hash_x_signature<T: AsRef<[u8]>>(&mut self, preimage: T) -> Result<(), StellarSdkError> {
+        let preimage = preimage.as_ref();
+        let hint = SignerKey::from_hash_x_preimage(preimage).get_signature_hint();
+        let signature = DecoratedSignature { hint, signature: LimitedVarOpaque::new(preimage.to_vec())? };
+
+        self.get_signatures()
+            .push(signature)
+            .map_err(|_| StellarSdkError::TooManySignatures)
+    }
+
+    /// Verify the signatures of the envelope
+    ///
+    /// Each signature is matched by its hint against the `candidate_signers` (ed25519 keys,
+    /// hash-x and signed payload signers) and then verified for the transaction hash of the
+    /// envelope for the `network`. For fee bump transactions the signatures of the inner
+    /// transaction are verified for the hash of the inner transaction.
+    pub fn verify_signatures(&self, network: &Network, candidate_signers: &[SignerKey]) -> SignatureVerificationReport {
+        let transaction_hash = self.get_hash(network);
+
+        let inner_signatures = match self {
+            TransactionEnvelope::EnvelopeTypeTxFeeBump(envelope) => {
+                let inner_envelope = match &envelope.tx.inner_tx {
+                    FeeBumpTransactionInnerTx::EnvelopeTypeTx(inner) =>
+                        TransactionEnvelope::EnvelopeTypeTx(inner.clone()),
+                    FeeBumpTransactionInnerTx::Default(_) => unreachable!("Invalid inner transaction type"),
+                };
+                Some(inner_envelope.verify_signatures(network, candidate_signers).signatures)
+            },
+            _ => None,
+        };
+
+        SignatureVerificationReport {
+            signatures: verify_decorated_signatures(&transaction_hash, self.signatures(), candidate_signers),
+            inner_signatures,
+        }
+    }
+
+    /// Merge the signatures of several copies of this envelope
+    ///
+    /// All `envelopes` must have the same transaction hash as this envelope for the `network`.
+    /// The result contains the valid signatures of this envelope followed by the new valid
+    /// signatures of `envelopes`. Signatures that are invalid or don't belong to one of the
+    /// `candidate_signers` and signatures of signers that already signed are dropped. For fee
+    /// bump transactions only the signatures of the outer envelope are merged, the inner
+    /// transaction is identical in all envelopes.
+    pub fn merge_signatures(
+        &self,
+        network: &Network,
+        envelopes: &[TransactionEnvelope],
+        candidate_signers: &[SignerKey],
+    ) -> Result<MergedEnvelope, StellarSdkError> {
+        let transaction_hash = self.get_hash(network);
+        if let Some(index) = envelopes
+            .iter()
+            .position(|envelope| envelope.get_hash(network) != transaction_hash)
+        {
+            return Err(StellarSdkError::TransactionHashMismatch { index })
+        }
+
+        let mut signers: Vec<SignerKey> = Vec::new();
+        let mut signatures: Vec<DecoratedSignature> = Vec::new();
+        let mut add_valid_signatures = |envelope: &TransactionEnvelope| {
+            let report = envelope.verify_signatures(network, candidate_signers);
+            let mut added_signature = false;
+            for (status, signature) in report.signatures.into_iter().zip(envelope.signatures().iter()) {
+                if let SignatureStatus::Valid { signer } = status {
+                    if !signers.contains(&signer) {
+                        signers.push(signer);
+                        signatures.push(signature.clone());
+                        added_signature = true;
+                    }
+                }
+            }
+            added_signature
+        };
+
+        add_valid_signatures(self);
+        let redundant_inputs = envelopes
+            .iter()
+            .enumerate()
+            .filter(|(_, envelope)| !add_valid_signatures(envelope))
+            .map(|(index, _)| index)
+            .collect();
+
+        let mut envelope = self.clone();
+        *envelope.get_signatures() =
+            LimitedVarArray::new(signatures).map_err(|_| StellarSdkError::TooManySignatures)?;
+
+        Ok(MergedEnvelope { envelope, redundant_inputs })
+    }
+
     pub fn get_hash(&self, network: &Network) -> BinarySha256Hash {
         let network_id = network.get_id().clone();
 
@@ -137,12 +328,12 @@ mod tests {
 
     use crate::{
         types::{
-            AlphaNum4, Asset, ManageSellOfferOp, Memo, MuxedAccount, Operation, OperationBody, PaymentOp,
-            Preconditions, Price, PublicKey, TimeBounds, Transaction, TransactionEnvelope, TransactionExt,
+            AlphaNum4, Asset, DecoratedSignature, ManageSellOfferOp, Memo, MuxedAccount, Operation, OperationBody,
+            PaymentOp, Preconditions, Price, PublicKey, TimeBounds, Transaction, TransactionEnvelope, TransactionExt,
             TransactionMeta, TransactionV1Envelope, Uint256,
         },
         xdr::compound_types::LimitedVarArray,
-        XdrCodec,
+        FeeBumpTransaction, SignatureStatus, SignerKey, StellarSdkError, StroopAmount, XdrCodec,
     };
 
     use crate::{network::TEST_NETWORK, secret_key::SecretKey};
@@ -614,4 +805,147 @@ mod tests {
 
         assert_eq!(transaction_envelope.to_base64_xdr().as_slice(), &expected_signed_xdr[..]);
     }
+
+    #[test]
+    fn verify_signatures() {
+        let keypair = SecretKey::from_encoding("SCDSVACTNFNSD5LQZ5LWUWEY3UIAML2J7ALPFCD6ZX4D3TVJV7X243N3").unwrap();
+        let other_keypair =
+            SecretKey::from_encoding("SCVKZEONBSU3XD6OTHXGAP6BTEWHOU4RPZQZJJ5AVAGPXUZ5A4D7MU6S").unwrap();
+        let signer: SignerKey = keypair.get_public().clone().into();
+
+        let mut envelope = TransactionEnvelope::from_base64_xdr(ENVELOPE).unwrap();
+        let original_signature = envelope.get_signatures().pop().unwrap();
+        envelope.sign(&TEST_NETWORK, vec![&keypair, &keypair, &other_keypair]).unwrap();
+        envelope
+            .get_signatures()
+            .push(DecoratedSignature { hint: signer.get_signature_hint(), ..original_signature })
+            .unwrap();
+
+        let report = envelope.verify_signatures(&TEST_NETWORK, &[signer.clone()]);
+        assert_eq!(
+            report.signatures,
+            vec![
+                SignatureStatus::Valid { signer: signer.clone() },
+                SignatureStatus::Duplicate { signer: signer.clone(), of_index: 0 },
+                SignatureStatus::UnknownHint,
+                SignatureStatus::Invalid,
+            ]
+        );
+        assert_eq!(report.inner_signatures, None);
+        assert!(!report.all_valid());
+
+        let mut inner_envelope = TransactionEnvelope::from_base64_xdr(ENVELOPE).unwrap();
+        inner_envelope.get_signatures().pop();
+        inner_envelope.sign(&TEST_NETWORK, vec![&keypair]).unwrap();
+        let mut fee_bump_envelope =
+            FeeBumpTransaction::new(other_keypair.get_public().clone(), StroopAmount(1000), inner_envelope)
+                .unwrap()
+                .into_transaction_envelope();
+        fee_bump_envelope.sign(&TEST_NETWORK, vec![&other_keypair]).unwrap();
+
+        let report = fee_bump_envelope
+            .verify_signatures(&TEST_NETWORK, &[signer.clone(), other_keypair.get_public().clone().into()]);
+        assert_eq!(
+            report.signatures,
+            vec![SignatureStatus::Valid { signer: other_keypair.get_public().clone().into() }]
+        );
+        assert_eq!(report.inner_signatures, Some(vec![SignatureStatus::Valid { signer }]));
+        assert!(report.all_valid());
+    }
+
+    #[test]
+    fn merge_signatures() {
+        let keypair = SecretKey::from_encoding("SCDSVACTNFNSD5LQZ5LWUWEY3UIAML2J7ALPFCD6ZX4D3TVJV7X243N3").unwrap();
+        let other_keypair =
+            SecretKey::from_encoding("SCVKZEONBSU3XD6OTHXGAP6BTEWHOU4RPZQZJJ5AVAGPXUZ5A4D7MU6S").unwrap();
+        let signers: Vec<SignerKey> =
+            vec![keypair.get_public().clone().into(), other_keypair.get_public().clone().into()];
+
+        let mut unsigned_envelope = TransactionEnvelope::from_base64_xdr(ENVELOPE).unwrap();
+        unsigned_envelope.get_signatures().pop();
+
+        let mut envelope1 = unsigned_envelope.clone();
+        envelope1.sign(&TEST_NETWORK, vec![&keypair]).unwrap();
+        let mut envelope2 = unsigned_envelope.clone();
+        envelope2.sign(&TEST_NETWORK, vec![&other_keypair, &keypair]).unwrap();
+
+        let merged = unsigned_envelope
+            .merge_signatures(&TEST_NETWORK, &[envelope1.clone(), envelope2, envelope1], &signers)
+            .unwrap();
+        assert_eq!(merged.redundant_inputs, vec![2]);
+        let report = merged.envelope.verify_signatures(&TEST_NETWORK, &signers);
+        assert_eq!(
+            report.signatures,
+            vec![
+                SignatureStatus::Valid { signer: signers[0].clone() },
+                SignatureStatus::Valid { signer: signers[1].clone() }
+            ]
+        );
+
+        let other_envelope = TransactionEnvelope::from_base64_xdr(ENVELOPE).unwrap();
+        let mut other_transaction = match other_envelope {
+            TransactionEnvelope::EnvelopeTypeTx(envelope) => envelope,
+            _ => unreachable!(),
+        };
+        other_transaction.tx.seq_num += 1;
+        assert_eq!(
+            unsigned_envelope.merge_signatures(
+                &TEST_NETWORK,
+                &[TransactionEnvelope::EnvelopeTypeTx(other_transaction)],
+                &signers
+            ),
+            Err(StellarSdkError::TransactionHashMismatch { index: 0 })
+        );
+    }
+
+    #[test]
+    fn merge_too_many_signatures() {
+        let keypairs: Vec<SecretKey> = (1..=21).map(|seed| SecretKey::from_binary([seed; 32])).collect();
+        let signers: Vec<SignerKey> = keypairs.iter().map(|keypair| keypair.get_public().clone().into()).collect();
+
+        let mut unsigned_envelope = TransactionEnvelope::from_base64_xdr(ENVELOPE).unwrap();
+        unsigned_envelope.get_signatures().pop();
+
+        let envelopes: Vec<TransactionEnvelope> = keypairs
+            .iter()
+            .map(|keypair| {
+                let mut envelope = unsigned_envelope.clone();
+                envelope.sign(&TEST_NETWORK, vec![keypair]).unwrap();
+                envelope
+            })
+            .collect();
+
+        let merged = unsigned_envelope
+            .merge_signatures(&TEST_NETWORK, &envelopes[..20], &signers)
+            .unwrap();
+        assert_eq!(merged.envelope.signatures().len(), 20);
+        assert_eq!(
+            unsigned_envelope.merge_signatures(&TEST_NETWORK, &envelopes, &signers),
+            Err(StellarSdkError::TooManySignatures)
+        );
+    }
+
+    #[test]
+    fn hash_x_and_pre_auth_signers() {
+        let mut envelope = TransactionEnvelope::from_base64_xdr(ENVELOPE).unwrap();
+        envelope.get_signatures().pop();
+
+        let transaction = match &envelope {
+            TransactionEnvelope::EnvelopeTypeTx(envelope) => envelope.tx.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            SignerKey::from_transaction(&transaction, &TEST_NETWORK),
+            SignerKey::SignerKeyTypePreAuthTx(envelope.get_hash(&TEST_NETWORK))
+        );
+
+        let preimage = b"hash lock preimage";
+        let hash_x_signer = SignerKey::from_hash_x_preimage(preimage);
+        envelope.add_hash_x_signature(preimage).unwrap();
+        assert_eq!(
+            envelope.verify_signatures(&TEST_NETWORK, &[hash_x_signer.clone()]).signatures,
+            vec![SignatureStatus::Valid { signer: hash_x_signer }]
+        );
+        assert!(envelope.add_hash_x_signature([0; 65]).is_err());
+    }
 }
@@ -0,0 +1,424 @@
+//! Static validation of transactions
+//!
+//! The checks in this module only depend on the transaction itself and not on the state of the
+//! ledger. They catch malformed transactions that stellar-core would reject anyway.
+
+use sp_std::vec::Vec;
+
+use crate::{
+    types::{
+        ChangeTrustAsset, FeeBumpTransactionInnerTx, LedgerBounds, LiquidityPoolParameters, MuxedAccountMed25519,
+        OperationBody, Preconditions, MAX_OPS_PER_TX,
+    },
+    Asset, AssetCode, MuxedAccount, Operation, Price, TimeBounds, Transaction, TransactionEnvelope, BASE_FEE_STROOPS,
+};
+
+/// A problem of a single operation of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperationValidationError {
+    /// An amount that has to be positive is zero or negative
+    NonPositiveAmount { amount: i64 },
+
+    /// An amount that must not be negative is negative
+    NegativeAmount { amount: i64 },
+
+    /// An asset code is empty, contains non-alphanumeric characters or is not padded correctly
+    InvalidAssetCode,
+
+    /// The source account pays native assets to itself
+    NativeSelfPayment,
+
+    /// The numerator or denominator of a price is not positive
+    InvalidPrice { n: i32, d: i32 },
+
+    /// The name of a data entry is empty or contains non-printable characters
+    InvalidDataName,
+
+    /// A weight or threshold is larger than 255
+    InvalidSignerWeight { weight: u32 },
+
+    /// A Soroban operation is not the only operation of the transaction
+    SorobanOperationNotAlone,
+}
+
+/// A problem of a transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The transaction does not contain any operation
+    NoOperations,
+
+    /// The transaction contains more than `MAX_OPS_PER_TX` operations
+    TooManyOperations { found: usize },
+
+    /// The fee is lower than `BASE_FEE_STROOPS` per operation
+    InsufficientFee { found: u32, minimum: u64 },
+
+    /// The lower time bound is after the upper time bound
+    InvalidTimeBounds { min_time: u64, max_time: u64 },
+
+    /// The lower ledger bound is not below the upper ledger bound
+    InvalidLedgerBounds { min_ledger: u32, max_ledger: u32 },
+
+    /// The operation with index `index` is invalid
+    Operation { index: usize, error: OperationValidationError },
+}
+
+fn is_valid_asset_code(code: &[u8], min_length: usize) -> bool {
+    let length = code.iter().position(|char| *char == 0).unwrap_or(code.len());
+    length >= min_length &&
+        code[..length].iter().all(|char| char.is_ascii_alphanumeric()) &&
+        code[length..].iter().all(|char| *char == 0)
+}
+
+fn check_asset_code(asset_code: &AssetCode) -> Result<(), OperationValidationError> {
+    let valid = match asset_code {
+        AssetCode::AssetTypeCreditAlphanum4(code) => is_valid_asset_code(code, 1),
+        AssetCode::AssetTypeCreditAlphanum12(code) => is_valid_asset_code(code, 5),
+        AssetCode::Default(_) => false,
+    };
+    match valid {
+        true => Ok(()),
+        false => Err(OperationValidationError::InvalidAssetCode),
+    }
+}
+
+fn check_asset(asset: &Asset) -> Result<(), OperationValidationError> {
+    let valid = match asset {
+        Asset::AssetTypeNative => true,
+        Asset::AssetTypeCreditAlphanum4(asset) => is_valid_asset_code(&asset.asset_code, 1),
+        Asset::AssetTypeCreditAlphanum12(asset) => is_valid_asset_code(&asset.asset_code, 5),
+        Asset::Default(_) => false,
+    };
+    match valid {
+        true => Ok(()),
+        false => Err(OperationValidationError::InvalidAssetCode),
+    }
+}
+
+fn check_positive(amount: i64) -> Result<(), OperationValidationError> {
+    match amount > 0 {
+        true => Ok(()),
+        false => Err(OperationValidationError::NonPositiveAmount { amount }),
+    }
+}
+
+fn check_non_negative(amount: i64) -> Result<(), OperationValidationError> {
+    match amount >= 0 {
+        true => Ok(()),
+        false => Err(OperationValidationError::NegativeAmount { amount }),
+    }
+}
+
+fn check_price(price: &Price) -> Result<(), OperationValidationError> {
+    match price.n > 0 && price.d > 0 {
+        true => Ok(()),
+        false => Err(OperationValidationError::InvalidPrice { n: price.n, d: price.d }),
+    }
+}
+
+fn check_weight(weight: Option<u32>) -> Result<(), OperationValidationError> {
+    match weight {
+        Some(weight) if weight > 255 => Err(OperationValidationError::InvalidSignerWeight { weight }),
+        _ => Ok(()),
+    }
+}
+
+fn get_ed25519(account: &MuxedAccount) -> Option<&[u8; 32]> {
+    match account {
+        MuxedAccount::KeyTypeEd25519(ed25519) => Some(ed25519),
+        MuxedAccount::KeyTypeMuxedEd25519(MuxedAccountMed25519 { ed25519, .. }) => Some(ed25519),
+        MuxedAccount::Default(_) => None,
+    }
+}
+
+fn is_soroban_operation(operation: &Operation) -> bool {
+    matches!(
+        operation.body,
+        OperationBody::InvokeHostFunction(_) |
+            OperationBody::ExtendFootprintTtl(_) |
+            OperationBody::RestoreFootprint(_)
+    )
+}
+
+impl Operation {
+    /// Check this operation for problems that stellar-core would reject
+    ///
+    /// `transaction_source` is the source account of the transaction; it is used for operations
+    /// that do not have their own source account.
+    pub fn validate(&self, transaction_source: &MuxedAccount) -> Result<(), OperationValidationError> {
+        let source_account = self.source_account.as_ref().unwrap_or(transaction_source);
+
+        match &self.body {
+            OperationBody::CreateAccount(operation) => check_non_negative(operation.starting_balance),
+            OperationBody::Payment(operation) => {
+                check_asset(&operation.asset)?;
+                check_positive(operation.amount)?;
+                if operation.asset == Asset::AssetTypeNative &&
+                    get_ed25519(&operation.destination) == get_ed25519(source_account)
+                {
+                    return Err(OperationValidationError::NativeSelfPayment)
+                }
+                Ok(())
+            },
+            OperationBody::PathPaymentStrictReceive(operation) => {
+                check_asset(&operation.send_asset)?;
+                check_asset(&operation.dest_asset)?;
+                operation.path.get_vec().iter().try_for_each(check_asset)?;
+                check_positive(operation.send_max)?;
+                check_positive(operation.dest_amount)
+            },
+            OperationBody::PathPaymentStrictSend(operation) => {
+                check_asset(&operation.send_asset)?;
+                check_asset(&operation.dest_asset)?;
+                operation.path.get_vec().iter().try_for_each(check_asset)?;
+                check_positive(operation.send_amount)?;
+                check_positive(operation.dest_min)
+            },
+            OperationBody::ManageSellOffer(operation) => {
+                check_asset(&operation.selling)?;
+                check_asset(&operation.buying)?;
+                check_non_negative(operation.amount)?;
+                check_price(&operation.price)
+            },
+            OperationBody::ManageBuyOffer(operation) => {
+                check_asset(&operation.selling)?;
+                check_asset(&operation.buying)?;
+                check_non_negative(operation.buy_amount)?;
+                check_price(&operation.price)
+            },
+            OperationBody::CreatePassiveSellOffer(operation) => {
+                check_asset(&operation.selling)?;
+                check_asset(&operation.buying)?;
+                check_positive(operation.amount)?;
+                check_price(&operation.price)
+            },
+            OperationBody::SetOptions(operation) => {
+                check_weight(operation.master_weight)?;
+                check_weight(operation.low_threshold)?;
+                check_weight(operation.med_threshold)?;
+                check_weight(operation.high_threshold)?;
+                check_weight(operation.signer.as_ref().map(|signer| signer.weight))
+            },
+            OperationBody::ChangeTrust(operation) => {
+                match &operation.line {
+                    ChangeTrustAsset::AssetTypeNative => (),
+                    ChangeTrustAsset::AssetTypeCreditAlphanum4(asset) =>
+                        check_asset(&Asset::AssetTypeCreditAlphanum4(asset.clone()))?,
+                    ChangeTrustAsset::AssetTypeCreditAlphanum12(asset) =>
+                        check_asset(&Asset::AssetTypeCreditAlphanum12(asset.clone()))?,
+                    ChangeTrustAsset::AssetTypePoolShare(parameters) => {
+                        let LiquidityPoolParameters::LiquidityPoolConstantProduct(parameters) = parameters;
+                        check_asset(&parameters.asset_a)?;
+                        check_asset(&parameters.asset_b)?;
+                    },
+                }
+                check_non_negative(operation.limit)
+            },
+            OperationBody::AllowTrust(operation) => check_asset_code(&operation.asset),
+            OperationBody::ManageData(operation) => {
+                let name = operation.data_name.get_vec();
+                match !name.is_empty() && name.iter().all(|char| (0x20..0x7f).contains(char)) {
+                    true => Ok(()),
+                    false => Err(OperationValidationError::InvalidDataName),
+                }
+            },
+            OperationBody::CreateClaimableBalance(operation) => {
+                check_asset(&operation.asset)?;
+                check_positive(operation.amount)
+            },
+            OperationBody::Clawback(operation) => {
+                check_asset(&operation.asset)?;
+                check_positive(operation.amount)
+            },
+            OperationBody::SetTrustLineFlags(operation) => check_asset(&operation.asset),
+            OperationBody::LiquidityPoolDeposit(operation) => {
+                check_positive(operation.max_amount_a)?;
+                check_positive(operation.max_amount_b)?;
+                check_price(&operation.min_price)?;
+                check_price(&operation.max_price)
+            },
+            OperationBody::LiquidityPoolWithdraw(operation) => {
+                check_positive(operation.amount)?;
+                check_non_negative(operation.min_amount_a)?;
+                check_non_negative(operation.min_amount_b)
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+fn check_time_bounds(time_bounds: &TimeBounds, errors: &mut Vec<ValidationError>) {
+    if time_bounds.max_time != 0 && time_bounds.min_time > time_bounds.max_time {
+        errors.push(ValidationError::InvalidTimeBounds {
+            min_time: time_bounds.min_time,
+            max_time: time_bounds.max_time,
+        });
+    }
+}
+
+fn check_ledger_bounds(ledger_bounds: &LedgerBounds, errors: &mut Vec<ValidationError>) {
+    if ledger_bounds.max_ledger != 0 && ledger_bounds.min_ledger >= ledger_bounds.max_ledger {
+        errors.push(ValidationError::InvalidLedgerBounds {
+            min_ledger: ledger_bounds.min_ledger,
+            max_ledger: ledger_bounds.max_ledger,
+        });
+    }
+}
+
+impl Transaction {
+    /// Check this transaction for problems that stellar-core would reject
+    ///
+    /// In contrast to `check_preconditions` this does not depend on the state of the ledger.
+    /// All problems that are found are returned, operation problems carry the index of the
+    /// operation.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        let operations = self.operations.get_vec();
+
+        if operations.is_empty() {
+            errors.push(ValidationError::NoOperations);
+        } else if operations.len() > MAX_OPS_PER_TX as usize {
+            errors.push(ValidationError::TooManyOperations { found: operations.len() });
+        }
+
+        let minimum_fee = BASE_FEE_STROOPS as u64 * operations.len() as u64;
+        if (self.fee as u64) < minimum_fee {
+            errors.push(ValidationError::InsufficientFee { found: self.fee, minimum: minimum_fee });
+        }
+
+        match &self.cond {
+            Preconditions::PrecondNone => (),
+            Preconditions::PrecondTime(time_bounds) => check_time_bounds(time_bounds, &mut errors),
+            Preconditions::PrecondV2(preconditions) => {
+                if let Some(time_bounds) = &preconditions.time_bounds {
+                    check_time_bounds(time_bounds, &mut errors);
+                }
+                if let Some(ledger_bounds) = &preconditions.ledger_bounds {
+                    check_ledger_bounds(ledger_bounds, &mut errors);
+                }
+            },
+        }
+
+        let contains_soroban_operation = operations.iter().any(is_soroban_operation);
+        for (index, operation) in operations.iter().enumerate() {
+            let result = match contains_soroban_operation && operations.len() > 1 {
+                true if is_soroban_operation(operation) => Err(OperationValidationError::SorobanOperationNotAlone),
+                _ => operation.validate(&self.source_account),
+            };
+            if let Err(error) = result {
+                errors.push(ValidationError::Operation { index, error });
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+}
+
+impl TransactionEnvelope {
+    /// Check the transaction in this envelope for problems that stellar-core would reject
+    ///
+    /// For a fee bump transaction the inner transaction is validated and the outer fee has to
+    /// be at least `BASE_FEE_STROOPS` for each operation plus one. See `Transaction::validate`.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        match self {
+            TransactionEnvelope::EnvelopeTypeTxV0(envelope) => Transaction::from(envelope.tx.clone()).validate(),
+            TransactionEnvelope::EnvelopeTypeTx(envelope) => envelope.tx.validate(),
+            TransactionEnvelope::EnvelopeTypeTxFeeBump(envelope) => {
+                let inner_transaction = match &envelope.tx.inner_tx {
+                    FeeBumpTransactionInnerTx::EnvelopeTypeTx(inner) => &inner.tx,
+                    FeeBumpTransactionInnerTx::Default(_) => unreachable!(),
+                };
+
+                let mut errors = inner_transaction.validate().err().unwrap_or_default();
+                let operation_count = inner_transaction.operations.get_vec().len() as u64;
+                let minimum_fee = BASE_FEE_STROOPS as u64 * (operation_count + 1);
+                if envelope.tx.fee < 0 || (envelope.tx.fee as u64) < minimum_fee {
+                    errors.push(ValidationError::InsufficientFee {
+                        found: envelope.tx.fee.clamp(0, u32::MAX as i64) as u32,
+                        minimum: minimum_fee,
+                    });
+                }
+
+                match errors.is_empty() {
+                    true => Ok(()),
+                    false => Err(errors),
+                }
+            },
+            TransactionEnvelope::Default(_) => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        compound_types::LimitedString,
+        types::{ManageDataOp, OperationBody, Preconditions},
+        Asset, IntoMuxedAccountId, Operation, OperationValidationError, Price, TimeBounds, Transaction,
+        ValidationError,
+    };
+
+    const ACCOUNT_ID1: &str = "GDGRDTRINPF66FNC47H22NY6BNWMCD5Q4XZTVA2KG7PFZ64WHRIU62TQ";
+    const ACCOUNT_ID2: &str = "GBNKQVTFRP25TIQRODMU5GJGSXDKHCEUDN7LNMOS5PNM427LMR77NV4M";
+
+    #[test]
+    fn validate_transaction() {
+        let payment = Operation::new_payment(ACCOUNT_ID2, Asset::native(), "1").unwrap();
+        let mut transaction = Transaction::new(ACCOUNT_ID1, 100, Some(100), Preconditions::PrecondNone, None).unwrap();
+        transaction.append_operation(payment.clone()).unwrap();
+        assert_eq!(transaction.validate(), Ok(()));
+
+        let mut self_payment = Operation::new_payment(ACCOUNT_ID1, Asset::native(), "1").unwrap();
+        self_payment.source_account = None;
+        let mut offer = Operation::new_manage_sell_offer(
+            Asset::native(),
+            Asset::from_asset_code("USD", ACCOUNT_ID2).unwrap(),
+            "1",
+            Price::from_fraction(1, 1).unwrap(),
+            None,
+        )
+        .unwrap();
+        if let OperationBody::ManageSellOffer(operation) = &mut offer.body {
+            operation.price.d = 0;
+        }
+
+        let mut transaction = Transaction::new(
+            ACCOUNT_ID1,
+            100,
+            Some(100),
+            Preconditions::PrecondTime(TimeBounds { min_time: 20, max_time: 10 }),
+            None,
+        )
+        .unwrap();
+        transaction.append_operation(payment).unwrap();
+        transaction.append_operation(self_payment).unwrap();
+        transaction.append_operation(offer.clone()).unwrap();
+
+        assert_eq!(
+            transaction.validate(),
+            Err(vec![
+                ValidationError::InsufficientFee { found: 100, minimum: 300 },
+                ValidationError::InvalidTimeBounds { min_time: 20, max_time: 10 },
+                ValidationError::Operation { index: 1, error: OperationValidationError::NativeSelfPayment },
+                ValidationError::Operation { index: 2, error: OperationValidationError::InvalidPrice { n: 1, d: 0 } },
+            ])
+        );
+
+        let source = ACCOUNT_ID2.into_muxed_account_id().unwrap();
+        offer.body = OperationBody::ManageData(ManageDataOp {
+            data_name: LimitedString::new(b"\n".to_vec()).unwrap(),
+            data_value: None,
+        });
+        assert_eq!(offer.validate(&source), Err(OperationValidationError::InvalidDataName));
+
+        let mut create_account = Operation::new_create_account(ACCOUNT_ID1, "0").unwrap();
+        assert_eq!(create_account.validate(&source), Ok(()));
+        if let OperationBody::CreateAccount(operation) = &mut create_account.body {
+            operation.starting_balance = -1;
+        }
+        assert_eq!(create_account.validate(&source), Err(OperationValidationError::NegativeAmount { amount: -1 }));
+    }
+}